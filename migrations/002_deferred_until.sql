@@ -0,0 +1,3 @@
+-- Deferred decisions can be parked until a given date
+
+ALTER TABLE suggestions ADD COLUMN deferred_until DATE;
@@ -0,0 +1,6 @@
+-- Push-to-main reviews have no PR number
+
+ALTER TABLE reviews ALTER COLUMN pr_number DROP NOT NULL;
+
+-- Index for looking up reviews by commit SHA (including prefix lookups)
+CREATE INDEX idx_reviews_repo_commit ON reviews(repo, commit_sha varchar_pattern_ops);
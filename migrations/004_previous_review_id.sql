@@ -0,0 +1,3 @@
+-- Link each PR review to the review it supersedes
+
+ALTER TABLE reviews ADD COLUMN previous_review_id UUID REFERENCES reviews(id) ON DELETE SET NULL;
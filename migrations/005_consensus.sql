@@ -0,0 +1,4 @@
+-- Record which ensemble reviewers reported each suggestion
+
+ALTER TABLE suggestions ADD COLUMN flagged_by TEXT[];
+ALTER TABLE suggestions ADD COLUMN ensemble_size INTEGER;
@@ -0,0 +1,6 @@
+-- Track how much of the model output had to be coerced, dropped or repaired
+
+ALTER TABLE reviews ADD COLUMN coerced_fields INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE reviews ADD COLUMN dropped_fields INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE reviews ADD COLUMN dropped_suggestions INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE reviews ADD COLUMN repair_rounds INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,22 @@
+-- Audit log of every LLM request and response made for a review
+-- Bodies are stored with secrets redacted
+
+CREATE TABLE llm_interactions (
+    id UUID PRIMARY KEY,
+    review_id UUID NOT NULL REFERENCES reviews(id) ON DELETE CASCADE,
+    provider VARCHAR(50) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    latency_ms BIGINT NOT NULL,
+    request JSONB NOT NULL,
+    response JSONB NOT NULL,
+    prompt_tokens INTEGER,
+    completion_tokens INTEGER,
+    cost_usd DOUBLE PRECISION
+);
+
+-- Index for loading the interactions of a review
+CREATE INDEX idx_llm_interactions_review ON llm_interactions(review_id);
+
+-- Index for monthly spend reports
+CREATE INDEX idx_llm_interactions_created ON llm_interactions(created_at);
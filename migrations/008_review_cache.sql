@@ -0,0 +1,9 @@
+-- Content-addressed cache of findings per file diff
+-- key hashes the normalized file diff, reviewer fingerprint and rejections
+
+CREATE TABLE review_cache (
+    key VARCHAR(64) PRIMARY KEY,
+    file_path TEXT NOT NULL,
+    suggestions JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
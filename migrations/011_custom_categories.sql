@@ -0,0 +1,9 @@
+-- Allow custom suggestion categories and record free-form tags
+-- Types are now any lowercase, hyphenated name
+
+ALTER TABLE suggestions DROP CONSTRAINT valid_type;
+ALTER TABLE suggestions ADD CONSTRAINT valid_type CHECK (suggestion_type ~ '^[a-z0-9]+(-[a-z0-9]+)*$');
+ALTER TABLE suggestions ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}';
+
+-- Index for per-category stats
+CREATE INDEX idx_suggestions_type ON suggestions(suggestion_type);
@@ -0,0 +1,5 @@
+-- Reviewer confidence per finding, from 0.0 to 1.0
+-- Separate from claude_confidence, which scores Claude's own recommendation
+
+ALTER TABLE suggestions ADD COLUMN confidence DOUBLE PRECISION;
+ALTER TABLE suggestions ADD CONSTRAINT valid_confidence CHECK (confidence BETWEEN 0 AND 1);
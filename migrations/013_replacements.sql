@@ -0,0 +1,4 @@
+-- Structured fixes from the reviewer and when `ai-review apply` wrote them
+
+ALTER TABLE suggestions ADD COLUMN replacement JSONB;
+ALTER TABLE suggestions ADD COLUMN applied_at TIMESTAMPTZ;
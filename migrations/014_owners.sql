@@ -0,0 +1,3 @@
+-- Owners assigned to each finding from CODEOWNERS
+
+ALTER TABLE suggestions ADD COLUMN owners TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,27 @@
+-- Append-only log of decision changes, each with the decision it replaced
+-- decision and previous hold JSON decision records; no foreign key, so the
+-- history outlives the review. A trigger rejects updates and deletes.
+
+ALTER TABLE suggestions ADD COLUMN decision_verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE decision_events (
+    id BIGSERIAL PRIMARY KEY,
+    review_id UUID NOT NULL,
+    suggestion_id VARCHAR(50) NOT NULL,
+    decision JSONB,
+    previous JSONB,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for loading the history of a review
+CREATE INDEX idx_decision_events_review ON decision_events(review_id, id);
+
+CREATE FUNCTION reject_decision_event_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'decision_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER decision_events_append_only
+    BEFORE UPDATE OR DELETE ON decision_events
+    FOR EACH ROW EXECUTE FUNCTION reject_decision_event_change();
@@ -0,0 +1,59 @@
+-- Initial schema for the embedded SQLite ledger
+-- Mirrors the PostgreSQL schema in ../001_initial_schema.sql and later migrations
+
+-- Reviews table: top-level record for each PR or commit review
+CREATE TABLE reviews (
+    id TEXT PRIMARY KEY,
+    pr_number INTEGER,
+    repo TEXT NOT NULL,
+    branch TEXT,
+    commit_sha TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    previous_review_id TEXT REFERENCES reviews(id) ON DELETE SET NULL,
+
+    CONSTRAINT valid_status CHECK (status IN ('pending', 'decided', 'applied', 'stale'))
+);
+
+-- Index for looking up reviews by repo and PR
+CREATE INDEX idx_reviews_repo_pr ON reviews(repo, pr_number);
+
+-- Index for looking up reviews by commit SHA
+CREATE INDEX idx_reviews_repo_commit ON reviews(repo, commit_sha);
+
+-- Index for listing pending reviews
+CREATE INDEX idx_reviews_pending ON reviews(status) WHERE status = 'pending';
+
+-- Suggestions table: individual code review suggestions
+CREATE TABLE suggestions (
+    id INTEGER PRIMARY KEY,
+    review_id TEXT NOT NULL REFERENCES reviews(id) ON DELETE CASCADE,
+    external_id TEXT NOT NULL,  -- S001, S002, etc.
+    suggestion_type TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line_start INTEGER NOT NULL,
+    line_end INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    proposed_fix TEXT,
+
+    -- Human decision
+    human_decision TEXT,  -- accepted, rejected, deferred
+    human_reason TEXT,
+    decided_by TEXT,
+    decided_at TEXT,
+    deferred_until TEXT,
+
+    CONSTRAINT valid_type CHECK (suggestion_type IN ('security', 'performance', 'style', 'logic', 'documentation')),
+    CONSTRAINT valid_severity CHECK (severity IN ('critical', 'high', 'medium', 'low')),
+    CONSTRAINT valid_human_decision CHECK (human_decision IS NULL OR human_decision IN ('accepted', 'rejected', 'deferred')),
+
+    -- Unique external_id per review
+    CONSTRAINT unique_external_id_per_review UNIQUE (review_id, external_id)
+);
+
+-- Index for finding pending suggestions
+CREATE INDEX idx_suggestions_pending ON suggestions(review_id) WHERE human_decision IS NULL;
+
+-- Index for severity filtering
+CREATE INDEX idx_suggestions_severity ON suggestions(severity);
@@ -0,0 +1,5 @@
+-- Record which ensemble reviewers reported each suggestion
+-- flagged_by holds a JSON array of reviewer names
+
+ALTER TABLE suggestions ADD COLUMN flagged_by TEXT;
+ALTER TABLE suggestions ADD COLUMN ensemble_size INTEGER;
@@ -0,0 +1,19 @@
+-- Audit log of every LLM request and response made for a review
+-- request and response hold the JSON bodies with secrets redacted
+
+CREATE TABLE llm_interactions (
+    id TEXT PRIMARY KEY,
+    review_id TEXT NOT NULL REFERENCES reviews(id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    request TEXT NOT NULL,
+    response TEXT NOT NULL,
+    prompt_tokens INTEGER,
+    completion_tokens INTEGER,
+    cost_usd REAL
+);
+
+-- Index for loading the interactions of a review
+CREATE INDEX idx_llm_interactions_review ON llm_interactions(review_id);
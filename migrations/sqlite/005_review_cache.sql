@@ -0,0 +1,10 @@
+-- Content-addressed cache of findings per file diff
+-- key hashes the normalized file diff, reviewer fingerprint and rejections
+-- suggestions holds a JSON array
+
+CREATE TABLE review_cache (
+    key TEXT PRIMARY KEY,
+    file_path TEXT NOT NULL,
+    suggestions TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
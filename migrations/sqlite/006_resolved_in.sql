@@ -0,0 +1,3 @@
+-- Record the commit in which a carried-forward finding's lines were modified
+
+ALTER TABLE suggestions ADD COLUMN resolved_in TEXT;
@@ -0,0 +1,3 @@
+-- Hash of the assembled system prompt each review was produced with
+
+ALTER TABLE reviews ADD COLUMN prompt_hash TEXT;
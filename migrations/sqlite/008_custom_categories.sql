@@ -0,0 +1,60 @@
+-- Allow custom suggestion categories and record free-form tags
+-- SQLite can't drop a CHECK constraint, so the suggestions table is rebuilt.
+-- Types are now any lowercase, hyphenated name; tags hold a JSON array of strings.
+
+CREATE TABLE suggestions_new (
+    id INTEGER PRIMARY KEY,
+    review_id TEXT NOT NULL REFERENCES reviews(id) ON DELETE CASCADE,
+    external_id TEXT NOT NULL,  -- S001, S002, etc.
+    suggestion_type TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line_start INTEGER NOT NULL,
+    line_end INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    proposed_fix TEXT,
+
+    -- Human decision
+    human_decision TEXT,  -- accepted, rejected, deferred
+    human_reason TEXT,
+    decided_by TEXT,
+    decided_at TEXT,
+    deferred_until TEXT,
+
+    -- Ensemble agreement
+    flagged_by TEXT,
+    ensemble_size INTEGER,
+
+    resolved_in TEXT,
+    tags TEXT NOT NULL DEFAULT '[]',
+
+    CONSTRAINT valid_type CHECK (suggestion_type <> '' AND suggestion_type NOT GLOB '*[^a-z0-9-]*'),
+    CONSTRAINT valid_severity CHECK (severity IN ('critical', 'high', 'medium', 'low')),
+    CONSTRAINT valid_human_decision CHECK (human_decision IS NULL OR human_decision IN ('accepted', 'rejected', 'deferred')),
+
+    -- Unique external_id per review
+    CONSTRAINT unique_external_id_per_review UNIQUE (review_id, external_id)
+);
+
+INSERT INTO suggestions_new (
+    id, review_id, external_id, suggestion_type, severity, file_path, line_start, line_end,
+    description, proposed_fix, human_decision, human_reason, decided_by, decided_at,
+    deferred_until, flagged_by, ensemble_size, resolved_in
+)
+SELECT
+    id, review_id, external_id, suggestion_type, severity, file_path, line_start, line_end,
+    description, proposed_fix, human_decision, human_reason, decided_by, decided_at,
+    deferred_until, flagged_by, ensemble_size, resolved_in
+FROM suggestions;
+
+DROP TABLE suggestions;
+ALTER TABLE suggestions_new RENAME TO suggestions;
+
+-- Index for finding pending suggestions
+CREATE INDEX idx_suggestions_pending ON suggestions(review_id) WHERE human_decision IS NULL;
+
+-- Index for severity filtering
+CREATE INDEX idx_suggestions_severity ON suggestions(severity);
+
+-- Index for per-category stats
+CREATE INDEX idx_suggestions_type ON suggestions(suggestion_type);
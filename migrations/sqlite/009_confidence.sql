@@ -0,0 +1,3 @@
+-- Reviewer confidence per finding, from 0.0 to 1.0
+
+ALTER TABLE suggestions ADD COLUMN confidence REAL CHECK (confidence BETWEEN 0 AND 1);
@@ -0,0 +1,4 @@
+-- Structured fixes from the reviewer and when `ai-review apply` wrote them
+
+ALTER TABLE suggestions ADD COLUMN replacement TEXT;
+ALTER TABLE suggestions ADD COLUMN applied_at TEXT;
@@ -0,0 +1,3 @@
+-- Owners assigned to each finding from CODEOWNERS, as a JSON array of strings
+
+ALTER TABLE suggestions ADD COLUMN owners TEXT NOT NULL DEFAULT '[]';
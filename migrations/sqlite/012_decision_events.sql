@@ -0,0 +1,27 @@
+-- Append-only log of decision changes, each with the decision it replaced
+-- decision and previous hold JSON decision records; no foreign key, so the
+-- history outlives the review. Triggers reject updates and deletes.
+
+ALTER TABLE suggestions ADD COLUMN decision_verified INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE decision_events (
+    id INTEGER PRIMARY KEY,
+    review_id TEXT NOT NULL,
+    suggestion_id TEXT NOT NULL,
+    decision TEXT,
+    previous TEXT,
+    recorded_at TEXT NOT NULL
+);
+
+-- Index for loading the history of a review
+CREATE INDEX idx_decision_events_review ON decision_events(review_id, id);
+
+CREATE TRIGGER decision_events_no_update BEFORE UPDATE ON decision_events
+BEGIN
+    SELECT RAISE(ABORT, 'decision_events is append-only');
+END;
+
+CREATE TRIGGER decision_events_no_delete BEFORE DELETE ON decision_events
+BEGIN
+    SELECT RAISE(ABORT, 'decision_events is append-only');
+END;
@@ -0,0 +1,279 @@
+use std::time::Instant;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+use uuid::Uuid;
+
+use super::error::ReviewerError;
+use super::prompt::{
+    build_system_prompt, build_user_prompt, diff_files, review_with_repair, reviewer_fingerprint,
+    ChatMessage, ChatModel, Completion,
+};
+use super::retry::{read_json, send_with_retry};
+use super::{ReviewOutput, ReviewPrompts, Reviewer};
+use crate::audit::redact_interaction;
+use crate::config::RetryConfig;
+use crate::models::{LlmInteraction, ReviewContext};
+use crate::suppressions::Rejections;
+
+const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const MAX_TOKENS: u32 = 8192;
+
+/// Adapter for the Anthropic Messages API
+pub struct AnthropicAdapter {
+    client: Client,
+    api_key: String,
+    model: String,
+    temperature: f32,
+    instructions: Vec<String>,
+    prompts: ReviewPrompts,
+    base_url: String,
+    retry: RetryConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct MessagesRequest {
+    model: String,
+    max_tokens: u32,
+    system: String,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    block_type: String,
+    #[serde(default)]
+    text: String,
+}
+
+impl AnthropicAdapter {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            model: "claude-sonnet-4-5".to_string(),
+            temperature: 0.1,
+            instructions: Vec::new(),
+            prompts: ReviewPrompts::default(),
+            base_url: ANTHROPIC_BASE_URL.to_string(),
+            retry: RetryConfig::default(),
+        }
+    }
+
+    pub fn with_model(mut self, model: &str) -> Self {
+        self.model = model.to_string();
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Add repository-specific instructions to the system prompt
+    pub fn with_instructions(mut self, instructions: Vec<String>) -> Self {
+        self.instructions = instructions;
+        self
+    }
+
+    /// Use the repository's review guidance, house rules and path rules
+    pub fn with_prompts(mut self, prompts: ReviewPrompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set request timeout and retry policy
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl ChatModel for AnthropicAdapter {
+    async fn complete(&self, system: &str, messages: &[ChatMessage]) -> Result<Completion> {
+        let request = MessagesRequest {
+            model: self.model.clone(),
+            max_tokens: MAX_TOKENS,
+            system: system.to_string(),
+            messages: messages.to_vec(),
+            temperature: self.temperature,
+        };
+
+        debug!("Sending request to Anthropic API");
+
+        let created_at = Utc::now();
+        let started = Instant::now();
+        let response = send_with_retry(self.name(), &self.retry, || {
+            self.client
+                .post(format!("{}/v1/messages", self.base_url))
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", ANTHROPIC_VERSION)
+                .json(&request)
+        })
+        .await?;
+        let body = read_json(self.name(), response).await?;
+        let latency = started.elapsed();
+
+        let messages_response: MessagesResponse =
+            serde_json::from_value(body.clone()).map_err(|source| {
+                ReviewerError::InvalidResponse {
+                    provider: self.name().to_string(),
+                    source,
+                }
+            })?;
+
+        let mut interaction = LlmInteraction {
+            id: Uuid::new_v4(),
+            review_id: None,
+            provider: self.name().to_string(),
+            model: self.model.clone(),
+            created_at,
+            latency_ms: latency.as_millis() as u64,
+            request: serde_json::to_value(&request)?,
+            response: body,
+            prompt_tokens: messages_response.usage.as_ref().map(|u| u.input_tokens),
+            completion_tokens: messages_response.usage.as_ref().map(|u| u.output_tokens),
+            cost_usd: None,
+        };
+        redact_interaction(&mut interaction, &[&self.api_key]);
+
+        if messages_response.stop_reason.as_deref() == Some("refusal") {
+            return Err(ReviewerError::Refusal {
+                provider: self.name().to_string(),
+                reason: "stop_reason was refusal".to_string(),
+            }
+            .into());
+        }
+
+        let content: String = messages_response
+            .content
+            .iter()
+            .filter(|b| b.block_type == "text")
+            .map(|b| b.text.as_str())
+            .collect();
+        if content.trim().is_empty() {
+            return Err(ReviewerError::EmptyResponse {
+                provider: self.name().to_string(),
+            }
+            .into());
+        }
+
+        Ok(Completion {
+            content,
+            interaction,
+        })
+    }
+}
+
+#[async_trait]
+impl Reviewer for AnthropicAdapter {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn fingerprint(&self) -> String {
+        reviewer_fingerprint(
+            self.name(),
+            &self.model,
+            self.temperature,
+            &self.prompts,
+            &self.instructions,
+        )
+    }
+
+    #[instrument(skip(self, diff, rejections), fields(pr = context.pr_number, repo = %context.repo))]
+    async fn review(
+        &self,
+        diff: &str,
+        context: &ReviewContext,
+        rejections: Option<&Rejections>,
+    ) -> Result<ReviewOutput> {
+        info!("Starting Anthropic review");
+
+        let system = build_system_prompt(&self.prompts, &self.instructions, &diff_files(diff));
+        let user = build_user_prompt(diff, context, rejections);
+        let output =
+            review_with_repair(self, system, user, &self.prompts.suggestion_types()).await?;
+
+        info!(
+            count = output.suggestions.len(),
+            "Anthropic review complete"
+        );
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_messages_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "test-key"))
+            .and(header("anthropic-version", ANTHROPIC_VERSION))
+            .and(body_partial_json(serde_json::json!({ "model": "claude-test" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{
+                    "type": "text",
+                    "text": "```json\n{\"suggestions\": [{\"id\": \"S001\", \"type\": \"performance\", \"severity\": \"medium\", \"location\": {\"file\": \"src/db.rs\", \"line_start\": 3, \"line_end\": 9}, \"description\": \"N+1 query\", \"proposed_fix\": null}]}\n```"
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let adapter = AnthropicAdapter::new("test-key".to_string())
+            .with_base_url(&server.uri())
+            .with_model("claude-test");
+
+        let context = ReviewContext {
+            pr_number: None,
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc1234".to_string(),
+            base_sha: None,
+        };
+
+        let suggestions = adapter
+            .review("diff", &context, None)
+            .await
+            .unwrap()
+            .suggestions;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].description, "N+1 query");
+    }
+}
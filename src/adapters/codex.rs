@@ -1,74 +1,96 @@
-use anyhow::{Context, Result};
+use std::time::Instant;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, instrument};
-
-use crate::models::{Location, ReviewContext, Severity, Suggestion, SuggestionType};
+use uuid::Uuid;
+
+use super::error::ReviewerError;
+use super::prompt::{
+    build_system_prompt, build_user_prompt, diff_files, review_schema, review_with_repair,
+    reviewer_fingerprint, ChatMessage, ChatModel, Completion, SCHEMA_NAME,
+};
+use super::retry::{read_json, send_with_retry};
+use super::{ReviewOutput, ReviewPrompts, Reviewer};
+use crate::audit::redact_interaction;
+use crate::config::RetryConfig;
+use crate::models::{LlmInteraction, ReviewContext};
 use crate::suppressions::Rejections;
 
-/// Adapter for OpenAI Codex code review
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+/// First GA Azure OpenAI API version with `json_schema` structured outputs
+const AZURE_API_VERSION: &str = "2024-10-21";
+
+/// Adapter for OpenAI chat completions code review
+///
+/// Also covers Azure OpenAI and any OpenAI-compatible server (vLLM, Ollama,
+/// LM Studio) via [`CodexAdapter::azure`] and [`CodexAdapter::with_base_url`].
 pub struct CodexAdapter {
     client: Client,
     api_key: String,
     model: String,
+    temperature: f32,
+    instructions: Vec<String>,
+    prompts: ReviewPrompts,
+    base_url: String,
+    azure_api_version: Option<String>,
+    retry: RetryConfig,
+    structured_output: bool,
 }
 
 #[derive(Debug, Serialize)]
 struct ChatRequest {
     model: String,
-    messages: Vec<Message>,
+    messages: Vec<ChatMessage>,
     response_format: ResponseFormat,
     temperature: f32,
 }
 
-#[derive(Debug, Serialize)]
-struct Message {
-    role: String,
-    content: String,
-}
-
 #[derive(Debug, Serialize)]
 struct ResponseFormat {
     #[serde(rename = "type")]
     format_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    json_schema: Option<JsonSchemaFormat>,
 }
 
-#[derive(Debug, Deserialize)]
-struct ChatResponse {
-    choices: Vec<Choice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Choice {
-    message: ResponseMessage,
+#[derive(Debug, Serialize)]
+struct JsonSchemaFormat {
+    name: String,
+    strict: bool,
+    schema: serde_json::Value,
 }
 
 #[derive(Debug, Deserialize)]
-struct ResponseMessage {
-    content: String,
+struct ChatResponse {
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<Usage>,
 }
 
 #[derive(Debug, Deserialize)]
-struct CodexReviewOutput {
-    suggestions: Vec<CodexSuggestion>,
+struct Usage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
 }
 
 #[derive(Debug, Deserialize)]
-struct CodexSuggestion {
-    id: String,
-    #[serde(rename = "type")]
-    suggestion_type: String,
-    severity: String,
-    location: CodexLocation,
-    description: String,
-    proposed_fix: Option<String>,
+struct Choice {
+    message: ResponseMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct CodexLocation {
-    file: String,
-    line_start: u32,
-    line_end: u32,
+struct ResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+    /// Set instead of `content` when the model declines the request
+    #[serde(default)]
+    refusal: Option<String>,
 }
 
 impl CodexAdapter {
@@ -77,6 +99,23 @@ impl CodexAdapter {
             client: Client::new(),
             api_key,
             model: "gpt-4o".to_string(),
+            temperature: 0.1,
+            instructions: Vec::new(),
+            prompts: ReviewPrompts::default(),
+            base_url: OPENAI_BASE_URL.to_string(),
+            azure_api_version: None,
+            retry: RetryConfig::default(),
+            structured_output: true,
+        }
+    }
+
+    /// Adapter for an Azure OpenAI resource, e.g. `https://my-resource.openai.azure.com`
+    ///
+    /// The model name is used as the deployment name.
+    pub fn azure(endpoint: &str, api_key: String, api_version: Option<&str>) -> Self {
+        Self {
+            azure_api_version: Some(api_version.unwrap_or(AZURE_API_VERSION).to_string()),
+            ..Self::new(api_key).with_base_url(endpoint)
         }
     }
 
@@ -85,189 +124,435 @@ impl CodexAdapter {
         self
     }
 
-    #[instrument(skip(self, diff, rejections), fields(pr = context.pr_number, repo = %context.repo))]
-    pub async fn review(
-        &self,
-        diff: &str,
-        context: &ReviewContext,
-        rejections: Option<&Rejections>,
-    ) -> Result<Vec<Suggestion>> {
-        info!("Starting Codex review");
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
 
-        let system_prompt = self.build_system_prompt();
-        let user_prompt = self.build_user_prompt(diff, context, rejections);
+    /// Send requests to an OpenAI-compatible server instead of api.openai.com
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
 
-        let request = ChatRequest {
-            model: self.model.clone(),
-            messages: vec![
-                Message {
-                    role: "system".to_string(),
-                    content: system_prompt,
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: user_prompt,
-                },
-            ],
-            response_format: self.build_response_format(),
-            temperature: 0.1,
-        };
+    /// Add repository-specific instructions to the system prompt
+    pub fn with_instructions(mut self, instructions: Vec<String>) -> Self {
+        self.instructions = instructions;
+        self
+    }
 
-        debug!("Sending request to OpenAI API");
+    /// Use the repository's review guidance, house rules and path rules
+    pub fn with_prompts(mut self, prompts: ReviewPrompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
 
-        let response = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send request to OpenAI")?;
+    /// Set request timeout and retry policy
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("OpenAI API error ({}): {}", status, error_text);
-        }
+    /// Request plain JSON mode instead of `json_schema` structured outputs, for
+    /// servers that don't support them
+    pub fn with_structured_output(mut self, structured_output: bool) -> Self {
+        self.structured_output = structured_output;
+        self
+    }
 
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .context("Failed to parse OpenAI response")?;
+    fn endpoint(&self) -> String {
+        match &self.azure_api_version {
+            Some(version) => format!(
+                "{}/openai/deployments/{}/chat/completions?api-version={}",
+                self.base_url, self.model, version
+            ),
+            None => format!("{}/chat/completions", self.base_url),
+        }
+    }
 
-        let content = chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.as_str())
-            .unwrap_or("{}");
+    fn build_response_format(&self) -> ResponseFormat {
+        if self.structured_output {
+            ResponseFormat {
+                format_type: "json_schema".to_string(),
+                json_schema: Some(JsonSchemaFormat {
+                    name: SCHEMA_NAME.to_string(),
+                    strict: true,
+                    schema: review_schema(&self.prompts.suggestion_types()),
+                }),
+            }
+        } else {
+            ResponseFormat {
+                format_type: "json_object".to_string(),
+                json_schema: None,
+            }
+        }
+    }
+}
 
-        let output: CodexReviewOutput =
-            serde_json::from_str(content).context("Failed to parse Codex review output")?;
+#[async_trait]
+impl ChatModel for CodexAdapter {
+    async fn complete(&self, system: &str, messages: &[ChatMessage]) -> Result<Completion> {
+        let mut all_messages = vec![ChatMessage {
+            role: "system".to_string(),
+            content: system.to_string(),
+        }];
+        all_messages.extend_from_slice(messages);
 
-        let suggestions: Vec<Suggestion> = output
-            .suggestions
-            .into_iter()
-            .map(|s| self.convert_suggestion(s))
-            .collect();
+        let request = ChatRequest {
+            model: self.model.clone(),
+            messages: all_messages,
+            response_format: self.build_response_format(),
+            temperature: self.temperature,
+        };
 
-        info!(count = suggestions.len(), "Codex review complete");
+        debug!(url = %self.endpoint(), "Sending request to OpenAI API");
+
+        let provider = self.name();
+        let created_at = Utc::now();
+        let started = Instant::now();
+        let response = send_with_retry(provider, &self.retry, || {
+            let builder = self.client.post(self.endpoint()).json(&request);
+            if self.azure_api_version.is_some() {
+                builder.header("api-key", &self.api_key)
+            } else if !self.api_key.is_empty() {
+                // Local OpenAI-compatible servers usually run without a key
+                builder.header("Authorization", format!("Bearer {}", self.api_key))
+            } else {
+                builder
+            }
+        })
+        .await?;
+        let body = read_json(provider, response).await?;
+        let latency = started.elapsed();
+
+        let chat_response: ChatResponse =
+            serde_json::from_value(body.clone()).map_err(|source| {
+                ReviewerError::InvalidResponse {
+                    provider: provider.to_string(),
+                    source,
+                }
+            })?;
+
+        let mut interaction = LlmInteraction {
+            id: Uuid::new_v4(),
+            review_id: None,
+            provider: provider.to_string(),
+            model: self.model.clone(),
+            created_at,
+            latency_ms: latency.as_millis() as u64,
+            request: serde_json::to_value(&request)?,
+            response: body,
+            prompt_tokens: chat_response.usage.as_ref().map(|u| u.prompt_tokens),
+            completion_tokens: chat_response.usage.as_ref().map(|u| u.completion_tokens),
+            cost_usd: None,
+        };
+        redact_interaction(&mut interaction, &[&self.api_key]);
 
-        Ok(suggestions)
+        Ok(Completion {
+            content: review_content(provider, chat_response)?,
+            interaction,
+        })
     }
+}
 
-    fn build_system_prompt(&self) -> String {
-        r#"You are an expert code reviewer. Analyze the provided diff and identify issues in these categories:
-- security: vulnerabilities, injection risks, authentication issues
-- performance: inefficient algorithms, unnecessary allocations, N+1 queries
-- logic: bugs, edge cases, incorrect behavior
-- style: readability issues, naming, code organization (only significant issues)
-- documentation: missing or incorrect documentation
-
-Focus on substantive issues. Ignore minor style preferences.
-Only review the changed lines (+ lines in diff), not removed lines.
-
-Respond with a JSON object in this exact format:
-{
-  "suggestions": [
-    {
-      "id": "S001",
-      "type": "security|performance|logic|style|documentation",
-      "severity": "critical|high|medium|low",
-      "location": {
-        "file": "path/to/file.rs",
-        "line_start": 10,
-        "line_end": 15
-      },
-      "description": "Clear description of the issue",
-      "proposed_fix": "The suggested fix or null if not applicable"
+#[async_trait]
+impl Reviewer for CodexAdapter {
+    fn name(&self) -> &str {
+        if self.azure_api_version.is_some() {
+            "azure"
+        } else {
+            "openai"
+        }
     }
-  ]
-}
 
-If there are no issues, return: {"suggestions": []}"#
-            .to_string()
+    fn fingerprint(&self) -> String {
+        reviewer_fingerprint(
+            self.name(),
+            &self.model,
+            self.temperature,
+            &self.prompts,
+            &self.instructions,
+        )
     }
 
-    fn build_user_prompt(
+    #[instrument(skip(self, diff, rejections), fields(pr = context.pr_number, repo = %context.repo))]
+    async fn review(
         &self,
         diff: &str,
         context: &ReviewContext,
         rejections: Option<&Rejections>,
-    ) -> String {
-        let target = match context.pr_number {
-            Some(pr) => format!("PR #{}", pr),
-            None => format!("commit {}", &context.commit_sha[..7.min(context.commit_sha.len())]),
-        };
+    ) -> Result<ReviewOutput> {
+        info!("Starting Codex review");
 
-        let rejections_prompt = rejections
-            .map(|r| r.to_prompt())
-            .unwrap_or_default();
+        let system = build_system_prompt(&self.prompts, &self.instructions, &diff_files(diff));
+        let user = build_user_prompt(diff, context, rejections);
+        let output =
+            review_with_repair(self, system, user, &self.prompts.suggestion_types()).await?;
 
-        format!(
-            "Review this diff from {} in {}:\n\n```diff\n{}\n```{}",
-            target, context.repo, diff, rejections_prompt
-        )
+        info!(count = output.suggestions.len(), "Codex review complete");
+
+        Ok(output)
     }
+}
 
-    fn build_response_format(&self) -> ResponseFormat {
-        ResponseFormat {
-            format_type: "json_object".to_string(),
-        }
+/// Extract the review text, treating refusals and empty answers as errors
+fn review_content(provider: &str, response: ChatResponse) -> Result<String, ReviewerError> {
+    let Some(choice) = response.choices.into_iter().next() else {
+        return Err(ReviewerError::EmptyResponse {
+            provider: provider.to_string(),
+        });
+    };
+
+    if let Some(reason) = choice.message.refusal {
+        return Err(ReviewerError::Refusal {
+            provider: provider.to_string(),
+            reason,
+        });
+    }
+    if choice.finish_reason.as_deref() == Some("content_filter") {
+        return Err(ReviewerError::Refusal {
+            provider: provider.to_string(),
+            reason: "response blocked by content filter".to_string(),
+        });
     }
 
-    fn convert_suggestion(&self, s: CodexSuggestion) -> Suggestion {
-        Suggestion {
-            id: s.id,
-            suggestion_type: match s.suggestion_type.as_str() {
-                "security" => SuggestionType::Security,
-                "performance" => SuggestionType::Performance,
-                "style" => SuggestionType::Style,
-                "logic" => SuggestionType::Logic,
-                "documentation" => SuggestionType::Documentation,
-                _ => SuggestionType::Logic,
-            },
-            severity: match s.severity.as_str() {
-                "critical" => Severity::Critical,
-                "high" => Severity::High,
-                "medium" => Severity::Medium,
-                _ => Severity::Low,
-            },
-            location: Location {
-                file: s.location.file,
-                line_start: s.location.line_start,
-                line_end: s.location.line_end,
-            },
-            description: s.description,
-            proposed_fix: s.proposed_fix,
-        }
+    match choice.message.content {
+        Some(content) if !content.trim().is_empty() => Ok(content),
+        _ => Err(ReviewerError::EmptyResponse {
+            provider: provider.to_string(),
+        }),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn context() -> ReviewContext {
+        ReviewContext {
+            pr_number: Some(7),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc1234".to_string(),
+            base_sha: None,
+        }
+    }
 
-    #[test]
-    fn test_convert_suggestion() {
-        let adapter = CodexAdapter::new("test-key".to_string());
-        let codex_suggestion = CodexSuggestion {
-            id: "S001".to_string(),
-            suggestion_type: "security".to_string(),
-            severity: "critical".to_string(),
-            location: CodexLocation {
-                file: "src/main.rs".to_string(),
-                line_start: 10,
-                line_end: 15,
-            },
-            description: "SQL injection vulnerability".to_string(),
-            proposed_fix: Some("Use parameterized queries".to_string()),
-        };
+    fn chat_response() -> serde_json::Value {
+        serde_json::json!({
+            "choices": [{
+                "message": {
+                    "content": r#"{"suggestions": [{"id": "S001", "type": "security", "severity": "critical", "location": {"file": "src/main.rs", "line_start": 10, "line_end": 15}, "description": "SQL injection vulnerability", "proposed_fix": "Use parameterized queries"}]}"#
+                }
+            }],
+            "usage": { "prompt_tokens": 1200, "completion_tokens": 80 }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_openai_compatible_base_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(header("Authorization", "Bearer test-key"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "llama3",
+                "response_format": {
+                    "type": "json_schema",
+                    "json_schema": { "name": "code_review", "strict": true }
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let adapter = CodexAdapter::new("test-key".to_string())
+            .with_base_url(&format!("{}/v1/", server.uri()))
+            .with_model("llama3");
+
+        let output = adapter.review("diff", &context(), None).await.unwrap();
+        assert_eq!(output.suggestions.len(), 1);
+        assert_eq!(
+            output.suggestions[0].description,
+            "SQL injection vulnerability"
+        );
+        assert_eq!(adapter.name(), "openai");
+
+        let interaction = &output.interactions[0];
+        assert_eq!(interaction.provider, "openai");
+        assert_eq!(interaction.model, "llama3");
+        assert_eq!(interaction.prompt_tokens, Some(1200));
+        assert_eq!(interaction.completion_tokens, Some(80));
+        assert_eq!(interaction.request["messages"][1]["role"], "user");
+        assert_eq!(interaction.response["usage"]["prompt_tokens"], 1200);
+    }
+
+    #[tokio::test]
+    async fn test_azure_deployment_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/openai/deployments/review-gpt4o/chat/completions"))
+            .and(query_param("api-version", AZURE_API_VERSION))
+            .and(header("api-key", "azure-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let adapter = CodexAdapter::azure(&server.uri(), "azure-key".to_string(), None)
+            .with_model("review-gpt4o");
+
+        let suggestions = adapter
+            .review("diff", &context(), None)
+            .await
+            .unwrap()
+            .suggestions;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(adapter.name(), "azure");
+    }
 
-        let suggestion = adapter.convert_suggestion(codex_suggestion);
+    #[tokio::test]
+    async fn test_api_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad key"))
+            .mount(&server)
+            .await;
+
+        let adapter = CodexAdapter::new("wrong".to_string()).with_base_url(&server.uri());
+        let err = adapter.review("diff", &context(), None).await.unwrap_err();
+        assert!(err.to_string().contains("401"));
+    }
+
+    fn fast_retry() -> RetryConfig {
+        RetryConfig {
+            max_retries: 2,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 5,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "0")
+                    .set_body_string("slow down"),
+            )
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let adapter = CodexAdapter::new("key".to_string())
+            .with_base_url(&server.uri())
+            .with_retry(fast_retry());
+
+        let suggestions = adapter
+            .review("diff", &context(), None)
+            .await
+            .unwrap()
+            .suggestions;
+        assert_eq!(suggestions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("overloaded"))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let adapter = CodexAdapter::new("key".to_string())
+            .with_base_url(&server.uri())
+            .with_retry(fast_retry());
+
+        let err = adapter.review("diff", &context(), None).await.unwrap_err();
+        match err.downcast_ref::<ReviewerError>() {
+            Some(ReviewerError::Api {
+                status, attempts, ..
+            }) => {
+                assert_eq!(status.as_u16(), 503);
+                assert_eq!(*attempts, 3);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repairs_malformed_output() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": { "content": "{\"suggestions\": [{\"id\": " } }]
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "messages": [{ "role": "system" }, { "role": "user" }, { "role": "assistant" }, { "role": "user" }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(chat_response()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let adapter = CodexAdapter::new("key".to_string())
+            .with_base_url(&server.uri())
+            .with_structured_output(false);
+
+        let output = adapter.review("diff", &context(), None).await.unwrap();
+        assert_eq!(output.suggestions.len(), 1);
+        assert_eq!(output.quality.repair_rounds, 1);
+    }
 
-        assert_eq!(suggestion.id, "S001");
-        assert_eq!(suggestion.suggestion_type, SuggestionType::Security);
-        assert_eq!(suggestion.severity, Severity::Critical);
-        assert_eq!(suggestion.location.file, "src/main.rs");
+    #[tokio::test]
+    async fn test_empty_and_refused_responses() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "choices": [] })),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{
+                    "message": { "content": null, "refusal": "I can't help with that." },
+                    "finish_reason": "stop"
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let adapter = CodexAdapter::new("key".to_string()).with_base_url(&server.uri());
+
+        let err = adapter.review("diff", &context(), None).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ReviewerError>(),
+            Some(ReviewerError::EmptyResponse { .. })
+        ));
+
+        let err = adapter.review("diff", &context(), None).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ReviewerError>(),
+            Some(ReviewerError::Refusal { .. })
+        ));
     }
 }
@@ -0,0 +1,392 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use tracing::{info, warn};
+
+use super::prompt::prompt_hash;
+use super::{ReviewOutput, Reviewer};
+use crate::models::{
+    description_similarity, Consensus, Location, OutputQuality, ReviewContext, Suggestion,
+};
+use crate::suppressions::Rejections;
+
+/// How far apart (in lines) two findings may be and still count as the same location
+const LINE_TOLERANCE: u32 = 3;
+
+/// Runs several reviewers concurrently and keeps the findings enough of them agree on
+pub struct EnsembleReviewer {
+    reviewers: Vec<(String, Box<dyn Reviewer>)>,
+    min_agreement: Option<usize>,
+    similarity: f64,
+}
+
+impl EnsembleReviewer {
+    pub fn new() -> Self {
+        Self {
+            reviewers: Vec::new(),
+            min_agreement: None,
+            similarity: 0.3,
+        }
+    }
+
+    /// Add a reviewer under the name used in agreement reports
+    pub fn with_reviewer(mut self, name: &str, reviewer: impl Reviewer + 'static) -> Self {
+        self.reviewers.push((name.to_string(), Box::new(reviewer)));
+        self
+    }
+
+    /// Number of reviewers that must report a finding (defaults to a majority)
+    pub fn with_min_agreement(mut self, min_agreement: usize) -> Self {
+        self.min_agreement = Some(min_agreement);
+        self
+    }
+
+    /// Minimum description similarity for findings to be merged
+    pub fn with_similarity(mut self, similarity: f64) -> Self {
+        self.similarity = similarity;
+        self
+    }
+
+    fn required_agreement(&self) -> usize {
+        self.min_agreement
+            .unwrap_or(self.reviewers.len() / 2 + 1)
+            .max(1)
+    }
+}
+
+impl Default for EnsembleReviewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Reviewer for EnsembleReviewer {
+    fn name(&self) -> &str {
+        "ensemble"
+    }
+
+    fn fingerprint(&self) -> String {
+        let members: Vec<String> = self
+            .reviewers
+            .iter()
+            .map(|(name, reviewer)| format!("{}={}", name, reviewer.fingerprint()))
+            .collect();
+        format!(
+            "ensemble/{}/{}/[{}]",
+            self.required_agreement(),
+            self.similarity,
+            members.join(",")
+        )
+    }
+
+    async fn review(
+        &self,
+        diff: &str,
+        context: &ReviewContext,
+        rejections: Option<&Rejections>,
+    ) -> Result<ReviewOutput> {
+        info!(reviewers = self.reviewers.len(), "Starting ensemble review");
+
+        let results = join_all(self.reviewers.iter().map(|(name, reviewer)| async move {
+            (name, reviewer.review(diff, context, rejections).await)
+        }))
+        .await;
+
+        let mut responses = Vec::new();
+        let mut quality = OutputQuality::default();
+        let mut interactions = Vec::new();
+        let mut prompts = Vec::new();
+        for (name, result) in results {
+            match result {
+                Ok(output) => {
+                    info!(reviewer = %name, count = output.suggestions.len(), "Reviewer finished");
+                    quality.add(&output.quality);
+                    interactions.extend(output.interactions);
+                    if let Some(hash) = output.prompt_hash {
+                        prompts.push(format!("{}={}", name, hash));
+                    }
+                    responses.push((name.clone(), output.suggestions));
+                }
+                Err(e) => warn!(reviewer = %name, error = %format!("{:#}", e), "Reviewer failed"),
+            }
+        }
+
+        let required = self.required_agreement();
+        if responses.len() < required {
+            anyhow::bail!(
+                "Only {} of {} reviewers responded, but {} must agree on a finding",
+                responses.len(),
+                self.reviewers.len(),
+                required
+            );
+        }
+
+        let merged = merge_findings(responses, required, self.similarity);
+
+        info!(count = merged.len(), required, "Ensemble review complete");
+
+        Ok(ReviewOutput {
+            suggestions: merged,
+            quality,
+            interactions,
+            // One hash over the members' prompts; each is in the audit log
+            prompt_hash: (!prompts.is_empty()).then(|| prompt_hash(&prompts.join(","))),
+        })
+    }
+}
+
+/// Cluster findings from several reviewers and keep those reported by at least `min_agreement`
+///
+/// Findings match when they are in the same file, their line ranges are within a
+/// few lines of each other and their descriptions are at least `similarity` alike.
+/// Each cluster keeps its most severe member, and kept findings are renumbered.
+pub fn merge_findings(
+    responses: Vec<(String, Vec<Suggestion>)>,
+    min_agreement: usize,
+    similarity: f64,
+) -> Vec<Suggestion> {
+    let reviewers = responses.len() as u32;
+    let mut clusters: Vec<Vec<(String, Suggestion)>> = Vec::new();
+
+    for (name, suggestions) in responses {
+        for suggestion in suggestions {
+            let cluster = clusters.iter_mut().find(|members| {
+                members.iter().all(|(n, _)| *n != name)
+                    && members
+                        .iter()
+                        .any(|(_, s)| is_same_location(s, &suggestion, similarity))
+            });
+
+            match cluster {
+                Some(members) => members.push((name.clone(), suggestion)),
+                None => clusters.push(vec![(name.clone(), suggestion)]),
+            }
+        }
+    }
+
+    let mut merged: Vec<Suggestion> = clusters
+        .into_iter()
+        .filter(|members| members.len() >= min_agreement)
+        .map(|members| {
+            // First reviewer wins ties, so config order sets precedence
+            let best = members
+                .iter()
+                .rev()
+                .max_by_key(|(_, s)| s.severity.rank())
+                .map(|(_, s)| s.clone())
+                .expect("clusters are never empty");
+
+            let proposed_fix = best
+                .proposed_fix
+                .clone()
+                .or_else(|| members.iter().find_map(|(_, s)| s.proposed_fix.clone()));
+
+            let replacement = best
+                .replacement
+                .clone()
+                .or_else(|| members.iter().find_map(|(_, s)| s.replacement.clone()));
+
+            let mut tags = best.tags.clone();
+            for tag in members.iter().flat_map(|(_, s)| &s.tags) {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+
+            // Mean of the members that reported a confidence
+            let reported: Vec<f64> = members.iter().filter_map(|(_, s)| s.confidence).collect();
+            let confidence = (!reported.is_empty())
+                .then(|| reported.iter().sum::<f64>() / reported.len() as f64);
+
+            Suggestion {
+                proposed_fix,
+                replacement,
+                tags,
+                confidence,
+                consensus: Some(Consensus {
+                    flagged_by: members.into_iter().map(|(name, _)| name).collect(),
+                    reviewers,
+                }),
+                ..best
+            }
+        })
+        .collect();
+
+    merged.sort_by(|a, b| {
+        (&a.location.file, a.location.line_start).cmp(&(&b.location.file, b.location.line_start))
+    });
+    for (i, suggestion) in merged.iter_mut().enumerate() {
+        suggestion.id = format!("S{:03}", i + 1);
+    }
+
+    merged
+}
+
+fn is_same_location(a: &Suggestion, b: &Suggestion, similarity: f64) -> bool {
+    a.location.file == b.location.file
+        && lines_near(&a.location, &b.location)
+        && description_similarity(&a.description, &b.description) >= similarity
+}
+
+fn lines_near(a: &Location, b: &Location) -> bool {
+    a.line_start <= b.line_end + LINE_TOLERANCE && b.line_start <= a.line_end + LINE_TOLERANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Severity, SuggestionItem};
+
+    fn make_suggestion(id: &str, file: &str, line: u32, description: &str) -> Suggestion {
+        SuggestionItem::test(id, file)
+            .with_lines(line, line + 2)
+            .with_description(description)
+            .suggestion
+    }
+
+    struct StaticReviewer(Vec<Suggestion>);
+
+    #[async_trait]
+    impl Reviewer for StaticReviewer {
+        fn name(&self) -> &str {
+            "static"
+        }
+
+        async fn review(
+            &self,
+            _diff: &str,
+            _context: &ReviewContext,
+            _rejections: Option<&Rejections>,
+        ) -> Result<ReviewOutput> {
+            Ok(ReviewOutput {
+                suggestions: self.0.clone(),
+                ..Default::default()
+            })
+        }
+    }
+
+    struct FailingReviewer;
+
+    #[async_trait]
+    impl Reviewer for FailingReviewer {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        async fn review(
+            &self,
+            _diff: &str,
+            _context: &ReviewContext,
+            _rejections: Option<&Rejections>,
+        ) -> Result<ReviewOutput> {
+            anyhow::bail!("rate limited")
+        }
+    }
+
+    fn context() -> ReviewContext {
+        ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc1234".to_string(),
+            base_sha: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_findings() {
+        let mut critical = make_suggestion("S002", "src/db.rs", 41, "SQL injection in user query");
+        critical.severity = Severity::Critical;
+        critical.confidence = Some(0.75);
+        let mut injection = make_suggestion(
+            "S001",
+            "src/db.rs",
+            40,
+            "Possible SQL injection in the user query",
+        );
+        injection.confidence = Some(0.25);
+
+        let responses = vec![
+            (
+                "gpt".to_string(),
+                vec![
+                    injection,
+                    make_suggestion("S002", "src/lib.rs", 5, "Unused import"),
+                ],
+            ),
+            (
+                "claude".to_string(),
+                vec![
+                    make_suggestion("S001", "src/api.rs", 12, "Missing timeout on HTTP client"),
+                    critical,
+                ],
+            ),
+            (
+                "llama".to_string(),
+                vec![make_suggestion(
+                    "S001",
+                    "src/db.rs",
+                    90,
+                    "SQL injection in user query",
+                )],
+            ),
+        ];
+
+        let merged = merge_findings(responses, 2, 0.3);
+        assert_eq!(merged.len(), 1);
+
+        let finding = &merged[0];
+        assert_eq!(finding.id, "S001");
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.confidence, Some(0.5));
+        assert_eq!(finding.location.line_start, 41);
+        let consensus = finding.consensus.as_ref().unwrap();
+        assert_eq!(consensus.flagged_by, vec!["gpt", "claude"]);
+        assert_eq!(consensus.label(), "flagged by 2/3 reviewers");
+    }
+
+    #[test]
+    fn test_merge_findings_renumbers() {
+        let responses = vec![(
+            "gpt".to_string(),
+            vec![
+                make_suggestion("S007", "src/b.rs", 1, "Second"),
+                make_suggestion("S003", "src/a.rs", 1, "First"),
+            ],
+        )];
+
+        let merged = merge_findings(responses, 1, 0.3);
+        assert_eq!(merged[0].id, "S001");
+        assert_eq!(merged[0].location.file, "src/a.rs");
+        assert_eq!(merged[1].id, "S002");
+    }
+
+    #[tokio::test]
+    async fn test_ensemble_review() {
+        let shared = make_suggestion("S001", "src/main.rs", 10, "Off by one in loop bound");
+
+        let ensemble = EnsembleReviewer::new()
+            .with_reviewer("a", StaticReviewer(vec![shared.clone()]))
+            .with_reviewer("b", StaticReviewer(vec![shared]))
+            .with_reviewer("c", FailingReviewer);
+
+        let suggestions = ensemble
+            .review("diff", &context(), None)
+            .await
+            .unwrap()
+            .suggestions;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(
+            suggestions[0].consensus.as_ref().unwrap().label(),
+            "flagged by 2/2 reviewers"
+        );
+
+        let ensemble = EnsembleReviewer::new()
+            .with_reviewer("a", StaticReviewer(Vec::new()))
+            .with_reviewer("b", FailingReviewer)
+            .with_min_agreement(2);
+        let err = ensemble.review("diff", &context(), None).await.unwrap_err();
+        assert!(err.to_string().contains("Only 1 of 2 reviewers responded"));
+    }
+}
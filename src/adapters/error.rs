@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use thiserror::Error;
+
+/// Failures talking to an LLM provider
+#[derive(Debug, Error)]
+pub enum ReviewerError {
+    #[error("{provider} request timed out after {timeout:?} ({attempts} attempts)")]
+    Timeout {
+        provider: String,
+        timeout: Duration,
+        attempts: u32,
+    },
+
+    #[error("{provider} rate limit still exceeded after {attempts} attempts: {body}")]
+    RateLimited {
+        provider: String,
+        attempts: u32,
+        body: String,
+    },
+
+    #[error("{provider} API error ({status}) after {attempts} attempts: {body}")]
+    Api {
+        provider: String,
+        status: StatusCode,
+        attempts: u32,
+        body: String,
+    },
+
+    #[error("Request to {provider} failed")]
+    Transport {
+        provider: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Failed to parse {provider} response")]
+    InvalidResponse {
+        provider: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("{provider} returned no review content")]
+    EmptyResponse { provider: String },
+
+    #[error("{provider} declined to review the diff: {reason}")]
+    Refusal { provider: String, reason: String },
+}
+
+impl ReviewerError {
+    /// Whether another attempt could succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ReviewerError::Timeout { .. } | ReviewerError::RateLimited { .. } => true,
+            ReviewerError::Api { status, .. } => is_transient_status(*status),
+            ReviewerError::Transport { source, .. } => source.is_connect() || source.is_timeout(),
+            ReviewerError::InvalidResponse { .. }
+            | ReviewerError::EmptyResponse { .. }
+            | ReviewerError::Refusal { .. } => false,
+        }
+    }
+}
+
+/// Rate limits, overload and server errors are worth retrying
+pub(crate) fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status.is_server_error()
+}
@@ -1,3 +1,179 @@
+pub mod anthropic;
 pub mod codex;
+pub mod ensemble;
+mod error;
+mod prompt;
+mod retry;
 
+pub use anthropic::AnthropicAdapter;
 pub use codex::CodexAdapter;
+pub use ensemble::EnsembleReviewer;
+pub use error::ReviewerError;
+pub use prompt::ReviewPrompts;
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use crate::config::{CodexModelConfig, Config, Provider, RetryConfig};
+use crate::models::{LlmInteraction, OutputQuality, ReviewContext, Suggestion};
+use crate::suppressions::Rejections;
+
+/// Suggestions from a reviewer, with counts of output that had to be fixed up
+/// and the model calls made to produce them
+#[derive(Debug, Clone, Default)]
+pub struct ReviewOutput {
+    pub suggestions: Vec<Suggestion>,
+    pub quality: OutputQuality,
+    pub interactions: Vec<LlmInteraction>,
+    /// Hash of the system prompt sent to the model, if one was called
+    pub prompt_hash: Option<String>,
+}
+
+/// An LLM provider that reviews diffs
+#[async_trait]
+pub trait Reviewer: Send + Sync {
+    /// Short provider name for logs
+    fn name(&self) -> &str;
+
+    /// Model, prompt and settings identity, part of the review cache key
+    fn fingerprint(&self) -> String {
+        self.name().to_string()
+    }
+
+    /// Review a diff and return the suggestions found
+    async fn review(
+        &self,
+        diff: &str,
+        context: &ReviewContext,
+        rejections: Option<&Rejections>,
+    ) -> Result<ReviewOutput>;
+}
+
+#[async_trait]
+impl<R: Reviewer + ?Sized> Reviewer for Box<R> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn fingerprint(&self) -> String {
+        (**self).fingerprint()
+    }
+
+    async fn review(
+        &self,
+        diff: &str,
+        context: &ReviewContext,
+        rejections: Option<&Rejections>,
+    ) -> Result<ReviewOutput> {
+        (**self).review(diff, context, rejections).await
+    }
+}
+
+/// Build the reviewer selected by `models.provider`, or the ensemble in
+/// `models.ensemble`, reading API keys from the environment
+///
+/// Every reviewer gets the review prompts from the `prompts` section and the
+/// custom `categories`.
+pub fn reviewer_from_config(config: &Config) -> Result<Box<dyn Reviewer>> {
+    let prompts = ReviewPrompts::from_config(config)?;
+    let config = &config.models;
+    let ensemble = &config.ensemble;
+    if ensemble.reviewers.is_empty() {
+        return build_reviewer(config.provider, &config.codex, &config.retry, &prompts);
+    }
+
+    let mut reviewer = EnsembleReviewer::new().with_similarity(ensemble.similarity);
+    if let Some(min_agreement) = ensemble.min_agreement {
+        if min_agreement > ensemble.reviewers.len() {
+            anyhow::bail!(
+                "models.ensemble.min_agreement ({}) is larger than the number of reviewers ({})",
+                min_agreement,
+                ensemble.reviewers.len()
+            );
+        }
+        reviewer = reviewer.with_min_agreement(min_agreement);
+    }
+
+    let mut names = HashSet::new();
+    for member in &ensemble.reviewers {
+        if !names.insert(member.name()) {
+            anyhow::bail!(
+                "Duplicate ensemble reviewer name '{}'; set a unique name",
+                member.name()
+            );
+        }
+        let built = build_reviewer(member.provider, &member.settings, &config.retry, &prompts)
+            .with_context(|| format!("Failed to configure reviewer '{}'", member.name()))?;
+        reviewer = reviewer.with_reviewer(member.name(), built);
+    }
+
+    Ok(Box::new(reviewer))
+}
+
+fn build_reviewer(
+    provider: Provider,
+    settings: &CodexModelConfig,
+    retry: &RetryConfig,
+    prompts: &ReviewPrompts,
+) -> Result<Box<dyn Reviewer>> {
+    let key_var = settings.api_key_env.as_deref().unwrap_or(match provider {
+        Provider::OpenAi | Provider::OpenAiCompatible => "OPENAI_API_KEY",
+        Provider::Anthropic => "ANTHROPIC_API_KEY",
+        Provider::Azure => "AZURE_OPENAI_API_KEY",
+    });
+    let api_key = std::env::var(key_var);
+
+    // OpenAI-style endpoints differ only in how they're reached
+    let adapter = match provider {
+        Provider::OpenAi => {
+            let api_key = api_key.with_context(|| format!("{} not set", key_var))?;
+            let adapter = CodexAdapter::new(api_key);
+            match &settings.base_url {
+                Some(url) => adapter.with_base_url(url),
+                None => adapter,
+            }
+        }
+        Provider::OpenAiCompatible => {
+            let url = settings
+                .base_url
+                .as_deref()
+                .context("models.codex.base_url is required for openai_compatible")?;
+            CodexAdapter::new(api_key.unwrap_or_default()).with_base_url(url)
+        }
+        Provider::Azure => {
+            let api_key = api_key.with_context(|| format!("{} not set", key_var))?;
+            let url = settings
+                .base_url
+                .as_deref()
+                .context("models.codex.base_url is required for azure")?;
+            CodexAdapter::azure(url, api_key, settings.api_version.as_deref())
+        }
+        Provider::Anthropic => {
+            let api_key = api_key.with_context(|| format!("{} not set", key_var))?;
+            let mut adapter = AnthropicAdapter::new(api_key);
+            if let Some(url) = &settings.base_url {
+                adapter = adapter.with_base_url(url);
+            }
+            return Ok(Box::new(
+                adapter
+                    .with_model(&settings.model)
+                    .with_temperature(settings.temperature)
+                    .with_instructions(settings.instructions.clone())
+                    .with_prompts(prompts.clone())
+                    .with_retry(retry.clone()),
+            ));
+        }
+    };
+
+    Ok(Box::new(
+        adapter
+            .with_model(&settings.model)
+            .with_temperature(settings.temperature)
+            .with_instructions(settings.instructions.clone())
+            .with_prompts(prompts.clone())
+            .with_retry(retry.clone())
+            .with_structured_output(settings.structured_output),
+    ))
+}
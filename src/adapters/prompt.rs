@@ -0,0 +1,1145 @@
+// Prompt building and response parsing shared by all reviewers
+
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use super::ReviewOutput;
+use crate::apply::is_repo_path;
+use crate::config::Config;
+use crate::models::{
+    LlmInteraction, Location, OutputQuality, Replacement, ReviewContext, Severity, Suggestion,
+    SuggestionType,
+};
+use crate::suppressions::Rejections;
+
+/// What to look for; replaced by `prompts.system` in the config
+const REVIEW_GUIDANCE: &str = r#"You are an expert code reviewer. Analyze the provided diff and identify issues in these categories:
+- security: vulnerabilities, injection risks, authentication issues
+- performance: inefficient algorithms, unnecessary allocations, N+1 queries
+- logic: bugs, edge cases, incorrect behavior
+- style: readability issues, naming, code organization (only significant issues)
+- documentation: missing or incorrect documentation
+
+Focus on substantive issues. Ignore minor style preferences.
+Only review the changed lines (+ lines in diff), not removed lines."#;
+
+/// Answer format the response parser relies on, always part of the system prompt
+const RESPONSE_FORMAT: &str = r#"Respond with a JSON object in this exact format:
+{
+  "suggestions": [
+    {
+      "id": "S001",
+      "type": "{types}",
+      "severity": "critical|high|medium|low",
+      "location": {
+        "file": "path/to/file.rs",
+        "line_start": 10,
+        "line_end": 15
+      },
+      "description": "Clear description of the issue",
+      "proposed_fix": "The suggested fix or null if not applicable",
+      "replacement": {
+        "line_start": 10,
+        "line_end": 11,
+        "old_text": "The exact current text of lines 10-11 in the new file",
+        "new_text": "The text to replace them with"
+      },
+      "tags": ["optional", "short-labels"],
+      "confidence": 0.8
+    }
+  ]
+}
+
+"replacement" is optional: give it only when the fix is a self-contained edit of consecutive
+lines, copying "old_text" character for character. Otherwise use null.
+"confidence" is how sure you are that the issue is real, from 0.0 (a guess) to 1.0 (certain).
+If there are no issues, return: {"suggestions": []}"#;
+
+/// Bump when prompt or parsing changes should invalidate cached findings
+pub(crate) const PROMPT_VERSION: u32 = 4;
+
+/// Repository review guidance and house rules, from the `prompts` config section
+#[derive(Debug, Clone, Default)]
+pub struct ReviewPrompts {
+    guidance: Option<String>,
+    house_rules: Vec<String>,
+    paths: Vec<PathRule>,
+    /// Custom categories and their descriptions
+    categories: Vec<(SuggestionType, String)>,
+}
+
+/// Instructions that apply only when the diff touches matching files
+#[derive(Debug, Clone)]
+struct PathRule {
+    patterns: Vec<glob::Pattern>,
+    instructions: String,
+}
+
+impl ReviewPrompts {
+    /// Build from the config's `prompts` and `categories`, reading the
+    /// instructions file if it exists
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut categories: Vec<(SuggestionType, String)> = Vec::new();
+        for category in &config.categories {
+            let name = &category.name;
+            if !SuggestionType::is_valid_name(name) {
+                anyhow::bail!(
+                    "Invalid category name '{}'; use lowercase words joined by hyphens",
+                    name
+                );
+            }
+            let suggestion_type: SuggestionType = name.parse()?;
+            if SuggestionType::ALL.contains(&suggestion_type)
+                || categories.iter().any(|(t, _)| *t == suggestion_type)
+            {
+                anyhow::bail!("Category '{}' is defined more than once", name);
+            }
+            categories.push((suggestion_type, category.description.trim().to_string()));
+        }
+
+        let config = &config.prompts;
+        let mut house_rules: Vec<String> =
+            config.instructions.iter().map(|r| r.to_text()).collect();
+
+        let path = Path::new(&config.instructions_file);
+        if path.exists() {
+            let text = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read instructions: {}", path.display()))?;
+            house_rules.push(text);
+        }
+
+        let paths = config
+            .paths
+            .iter()
+            .map(|rule| {
+                let patterns = rule
+                    .paths
+                    .iter()
+                    .map(|p| {
+                        glob::Pattern::new(p)
+                            .with_context(|| format!("Invalid path pattern in prompts: {}", p))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(PathRule {
+                    patterns,
+                    instructions: rule.instructions.clone(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            guidance: config.system.clone(),
+            house_rules: house_rules
+                .into_iter()
+                .map(|rule| rule.trim().to_string())
+                .filter(|rule| !rule.is_empty())
+                .collect(),
+            paths,
+            categories,
+        })
+    }
+
+    /// Built-in types followed by the custom categories
+    pub(crate) fn suggestion_types(&self) -> Vec<SuggestionType> {
+        SuggestionType::ALL
+            .into_iter()
+            .chain(self.categories.iter().map(|(t, _)| t.clone()))
+            .collect()
+    }
+
+    /// Feed the path rules into `hasher`, matched or not
+    fn hash_paths(&self, hasher: &mut Sha256) {
+        for rule in &self.paths {
+            for pattern in &rule.patterns {
+                hasher.update([0]);
+                hasher.update(pattern.as_str());
+            }
+            hasher.update([1]);
+            hasher.update(&rule.instructions);
+        }
+    }
+}
+
+/// Identifies a reviewer's model and prompt for the review cache
+///
+/// Path rules are hashed whether or not they match, so a cached file's findings
+/// are reused only while the rules stay the same.
+pub(crate) fn reviewer_fingerprint(
+    provider: &str,
+    model: &str,
+    temperature: f32,
+    prompts: &ReviewPrompts,
+    instructions: &[String],
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(build_system_prompt(prompts, instructions, &[]));
+    prompts.hash_paths(&mut hasher);
+    let prompt = hasher.finalize();
+    format!(
+        "{}/{}/t{}/v{}/{}",
+        provider,
+        model,
+        temperature,
+        PROMPT_VERSION,
+        &format!("{:x}", prompt)[..12]
+    )
+}
+
+/// Assemble the system prompt for a diff touching `files`
+///
+/// `instructions` are the reviewer's own from `models`; house rules and the path
+/// rules matching any of `files` follow them.
+pub(crate) fn build_system_prompt(
+    prompts: &ReviewPrompts,
+    instructions: &[String],
+    files: &[String],
+) -> String {
+    let guidance = prompts.guidance.as_deref().unwrap_or(REVIEW_GUIDANCE);
+    let mut prompt = guidance.trim_end().to_string();
+
+    if !prompts.categories.is_empty() {
+        prompt.push_str("\n\nAlso use these repository-specific categories:\n");
+        for (category, description) in &prompts.categories {
+            prompt.push_str(&format!("- {}: {}\n", category, description));
+        }
+    }
+
+    let types = prompts.suggestion_types();
+    let names: Vec<&str> = types.iter().map(|t| t.as_str()).collect();
+    prompt.push_str("\n\n");
+    prompt.push_str(&RESPONSE_FORMAT.replace("{types}", &names.join("|")));
+
+    if !instructions.is_empty() {
+        prompt.push_str("\n\nAdditional instructions for this repository:\n");
+        for instruction in instructions {
+            prompt.push_str(&format!("- {}\n", instruction));
+        }
+    }
+
+    if !prompts.house_rules.is_empty() {
+        prompt.push_str("\n\nHouse rules for this repository:\n");
+        prompt.push_str(&prompts.house_rules.join("\n\n"));
+        prompt.push('\n');
+    }
+
+    for rule in &prompts.paths {
+        let matched = files
+            .iter()
+            .any(|file| rule.patterns.iter().any(|p| p.matches(file)));
+        if matched {
+            let patterns: Vec<&str> = rule.patterns.iter().map(|p| p.as_str()).collect();
+            prompt.push_str(&format!(
+                "\n\nFor files matching {}:\n{}\n",
+                patterns.join(", "),
+                rule.instructions.trim()
+            ));
+        }
+    }
+
+    prompt
+}
+
+/// Short hash identifying an assembled system prompt
+pub(crate) fn prompt_hash(system_prompt: &str) -> String {
+    format!("{:x}", Sha256::digest(system_prompt.as_bytes()))[..16].to_string()
+}
+
+/// Paths of the files a unified diff touches
+pub(crate) fn diff_files(diff: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+
+    for line in diff.lines() {
+        let path = line
+            .strip_prefix("+++ b/")
+            .or_else(|| line.strip_prefix("--- a/"));
+        if let Some(path) = path.map(str::trim_end) {
+            if !files.iter().any(|f| f == path) {
+                files.push(path.to_string());
+            }
+        }
+    }
+
+    files
+}
+
+pub(crate) fn build_user_prompt(
+    diff: &str,
+    context: &ReviewContext,
+    rejections: Option<&Rejections>,
+) -> String {
+    let target = match context.pr_number {
+        Some(pr) => format!("PR #{}", pr),
+        None => format!(
+            "commit {}",
+            &context.commit_sha[..7.min(context.commit_sha.len())]
+        ),
+    };
+
+    // Incremental reviews only see what changed since the last reviewed commit
+    let scope = match &context.base_sha {
+        Some(base) => format!(
+            " (only the changes since the already reviewed commit {})",
+            &base[..7.min(base.len())]
+        ),
+        None => String::new(),
+    };
+
+    let rejections_prompt = rejections.map(|r| r.to_prompt()).unwrap_or_default();
+
+    format!(
+        "Review this diff from {}{} in {}:\n\n```diff\n{}\n```{}",
+        target, scope, context.repo, diff, rejections_prompt
+    )
+}
+
+/// Name of the structured output schema sent to OpenAI
+pub(crate) const SCHEMA_NAME: &str = "code_review";
+
+/// A chat message in the conversation with the model
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: String) -> Self {
+        Self {
+            role: "user".to_string(),
+            content,
+        }
+    }
+
+    pub fn assistant(content: String) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content,
+        }
+    }
+}
+
+/// The model's answer, with the exchange recorded for the audit log
+#[derive(Debug, Clone)]
+pub(crate) struct Completion {
+    pub content: String,
+    pub interaction: LlmInteraction,
+}
+
+/// A model that answers a conversation with text
+#[async_trait]
+pub(crate) trait ChatModel: Send + Sync {
+    async fn complete(&self, system: &str, messages: &[ChatMessage]) -> Result<Completion>;
+}
+
+/// JSON schema for the review answer, with enum values taken from the `Suggestion` model
+/// and the allowed `types`
+pub(crate) fn review_schema(types: &[SuggestionType]) -> Value {
+    let types: Vec<Value> = types.iter().map(|t| json!(t)).collect();
+    let severities: Vec<Value> = Severity::ALL.iter().map(|s| json!(s)).collect();
+
+    json!({
+        "type": "object",
+        "properties": {
+            "suggestions": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "string" },
+                        "type": { "type": "string", "enum": types },
+                        "severity": { "type": "string", "enum": severities },
+                        "location": {
+                            "type": "object",
+                            "properties": {
+                                "file": { "type": "string" },
+                                "line_start": { "type": "integer" },
+                                "line_end": { "type": "integer" }
+                            },
+                            "required": ["file", "line_start", "line_end"],
+                            "additionalProperties": false
+                        },
+                        "description": { "type": "string" },
+                        "proposed_fix": { "type": ["string", "null"] },
+                        "replacement": {
+                            "type": ["object", "null"],
+                            "properties": {
+                                "line_start": { "type": "integer" },
+                                "line_end": { "type": "integer" },
+                                "old_text": { "type": "string" },
+                                "new_text": { "type": "string" }
+                            },
+                            "required": ["line_start", "line_end", "old_text", "new_text"],
+                            "additionalProperties": false
+                        },
+                        "tags": { "type": "array", "items": { "type": "string" } },
+                        "confidence": { "type": ["number", "null"] }
+                    },
+                    "required": [
+                        "id", "type", "severity", "location", "description", "proposed_fix",
+                        "replacement", "tags", "confidence"
+                    ],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["suggestions"],
+        "additionalProperties": false
+    })
+}
+
+/// Ask the model for a review, giving it one chance to repair an invalid answer
+///
+/// The repair round sends the parse or validation errors back to the model. If the
+/// repaired answer is still unusable, whatever passed validation the first time is kept.
+pub(crate) async fn review_with_repair(
+    model: &impl ChatModel,
+    system_prompt: String,
+    user_prompt: String,
+    types: &[SuggestionType],
+) -> Result<ReviewOutput> {
+    let mut messages = vec![ChatMessage::user(user_prompt)];
+    let Completion {
+        content,
+        interaction,
+    } = model.complete(&system_prompt, &messages).await?;
+    let mut interactions = vec![interaction];
+
+    let (error, fallback) = match parse_output(&content, types) {
+        Ok(parsed) if parsed.problems.is_empty() => {
+            let mut output = parsed.output;
+            output.interactions = interactions;
+            output.prompt_hash = Some(prompt_hash(&system_prompt));
+            return Ok(output);
+        }
+        Ok(parsed) => (parsed.problems.join("\n"), Some(parsed.output)),
+        Err(e) => (format!("{:#}", e), None),
+    };
+
+    warn!(%error, "Model output failed validation, requesting a repair");
+
+    messages.push(ChatMessage::assistant(content));
+    messages.push(ChatMessage::user(format!(
+        "Your previous response could not be used:\n{}\n\n\
+         Respond again with only the JSON object in the required format.",
+        error
+    )));
+    let repaired = model.complete(&system_prompt, &messages).await?;
+    interactions.push(repaired.interaction);
+
+    let mut output = match (parse_output(&repaired.content, types), fallback) {
+        (Ok(parsed), _) => parsed.output,
+        (Err(e), Some(fallback)) => {
+            warn!(error = %format!("{:#}", e), "Repair failed, keeping the valid suggestions");
+            fallback
+        }
+        (Err(e), None) => return Err(e.context("Model output still invalid after repair")),
+    };
+    output.quality.repair_rounds += 1;
+    output.interactions = interactions;
+    output.prompt_hash = Some(prompt_hash(&system_prompt));
+
+    Ok(output)
+}
+
+/// Suggestions that passed validation, and why the others were dropped
+#[derive(Debug, Default)]
+pub(crate) struct ParsedOutput {
+    pub output: ReviewOutput,
+    pub problems: Vec<String>,
+}
+
+/// Parse and validate the model's JSON answer
+///
+/// Providers without a JSON response mode sometimes wrap the object in prose or a
+/// code fence, so everything outside the outermost braces is ignored. Fields that
+/// are recoverable are coerced; suggestions with missing or out-of-range required
+/// fields are dropped. Both are counted in the output quality. Types other than
+/// `types` count as unknown.
+pub(crate) fn parse_output(content: &str, types: &[SuggestionType]) -> Result<ParsedOutput> {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => content,
+    };
+
+    let value: Value = serde_json::from_str(json).context("Response is not valid JSON")?;
+    let items = value
+        .get("suggestions")
+        .and_then(Value::as_array)
+        .context("Response has no \"suggestions\" array")?;
+
+    let mut parsed = ParsedOutput::default();
+    for (i, item) in items.iter().enumerate() {
+        let mut quality = OutputQuality::default();
+        match validate_suggestion(item, i, types, &mut quality) {
+            Ok(suggestion) => {
+                parsed.output.suggestions.push(suggestion);
+                parsed.output.quality.add(&quality);
+            }
+            Err(problem) => {
+                parsed.output.quality.dropped_suggestions += 1;
+                parsed
+                    .problems
+                    .push(format!("suggestions[{}]: {}", i, problem));
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn validate_suggestion(
+    item: &Value,
+    index: usize,
+    types: &[SuggestionType],
+    quality: &mut OutputQuality,
+) -> Result<Suggestion, String> {
+    let id = match item.get("id").and_then(Value::as_str).map(str::trim) {
+        Some(id) if !id.is_empty() => id.to_string(),
+        _ => {
+            quality.coerced_fields += 1;
+            format!("S{:03}", index + 1)
+        }
+    };
+
+    let suggestion_type: SuggestionType = enum_field(item, "type", quality)?;
+    if !types.contains(&suggestion_type) {
+        return Err(format!("unknown type {:?}", suggestion_type.as_str()));
+    }
+    let severity = enum_field(item, "severity", quality)?;
+
+    let location = item.get("location").ok_or("missing \"location\"")?;
+    let file = non_empty_str(location, "file")?;
+    if !is_repo_path(&file) {
+        return Err(format!(
+            "location.file {:?} is not a path inside the repository",
+            file
+        ));
+    }
+    let mut line_start = line_number(location, "line_start", quality)?;
+    let mut line_end = line_number(location, "line_end", quality)?;
+    if line_end < line_start {
+        std::mem::swap(&mut line_start, &mut line_end);
+        quality.coerced_fields += 1;
+    }
+
+    let description = non_empty_str(item, "description")?;
+
+    let proposed_fix = match item.get("proposed_fix") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(fix)) if fix.trim().is_empty() => {
+            quality.coerced_fields += 1;
+            None
+        }
+        Some(Value::String(fix)) => Some(fix.clone()),
+        Some(_) => {
+            quality.dropped_fields += 1;
+            None
+        }
+    };
+
+    let tags = match item.get("tags") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(values)) => {
+            let mut tags: Vec<String> = Vec::new();
+            for value in values {
+                match value.as_str().map(|tag| tag.trim().to_lowercase()) {
+                    Some(tag) if !tag.is_empty() => {
+                        if !tags.contains(&tag) {
+                            tags.push(tag);
+                        }
+                    }
+                    _ => quality.dropped_fields += 1,
+                }
+            }
+            tags
+        }
+        Some(_) => {
+            quality.dropped_fields += 1;
+            Vec::new()
+        }
+    };
+
+    let confidence = confidence(item, quality);
+    let replacement = replacement(item, quality);
+
+    Ok(Suggestion {
+        id,
+        suggestion_type,
+        severity,
+        location: Location {
+            file,
+            line_start,
+            line_end,
+        },
+        description,
+        proposed_fix,
+        consensus: None,
+        tags,
+        confidence,
+        replacement,
+    })
+}
+
+/// Structured fix, dropped unless it has a valid line range and both texts
+fn replacement(item: &Value, quality: &mut OutputQuality) -> Option<Replacement> {
+    let value = match item.get("replacement") {
+        None | Some(Value::Null) => return None,
+        Some(value) => value,
+    };
+
+    let parsed = (|| {
+        let mut line_start = line_number(value, "line_start", quality).ok()?;
+        let mut line_end = line_number(value, "line_end", quality).ok()?;
+        if line_end < line_start {
+            std::mem::swap(&mut line_start, &mut line_end);
+            quality.coerced_fields += 1;
+        }
+        let text = |name| value.get(name).and_then(Value::as_str).map(str::to_string);
+        Some(Replacement {
+            line_start,
+            line_end,
+            old_text: text("old_text").filter(|t| !t.is_empty())?,
+            new_text: text("new_text")?,
+        })
+    })();
+
+    if parsed.is_none() {
+        quality.dropped_fields += 1;
+    }
+    parsed
+}
+
+/// Confidence as a fraction, coerced from strings and percentages; dropped if out of range
+fn confidence(item: &Value, quality: &mut OutputQuality) -> Option<f64> {
+    let value = match item.get("confidence") {
+        None | Some(Value::Null) => return None,
+        Some(Value::Number(n)) => n.as_f64(),
+        Some(Value::String(s)) => {
+            quality.coerced_fields += 1;
+            s.trim().trim_end_matches('%').trim().parse().ok()
+        }
+        Some(_) => None,
+    };
+
+    match value {
+        Some(v) if (0.0..=1.0).contains(&v) => Some(v),
+        Some(v) if v > 1.0 && v <= 100.0 => {
+            quality.coerced_fields += 1;
+            Some(v / 100.0)
+        }
+        _ => {
+            quality.dropped_fields += 1;
+            None
+        }
+    }
+}
+
+/// An enum field matching the schema exactly, or coerced from a case or spacing variant
+fn enum_field<T>(item: &Value, name: &str, quality: &mut OutputQuality) -> Result<T, String>
+where
+    T: DeserializeOwned + FromStr,
+{
+    let raw = item
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing \"{}\"", name))?;
+
+    if let Ok(value) = serde_json::from_value(json!(raw)) {
+        return Ok(value);
+    }
+
+    match raw.trim().parse() {
+        Ok(value) => {
+            quality.coerced_fields += 1;
+            Ok(value)
+        }
+        Err(_) => Err(format!("unknown {} {:?}", name, raw)),
+    }
+}
+
+fn non_empty_str(item: &Value, name: &str) -> Result<String, String> {
+    match item.get(name).and_then(Value::as_str).map(str::trim) {
+        Some(value) if !value.is_empty() => Ok(value.to_string()),
+        _ => Err(format!("missing \"{}\"", name)),
+    }
+}
+
+/// A 1-based line number; numeric strings and whole floats are coerced
+fn line_number(location: &Value, name: &str, quality: &mut OutputQuality) -> Result<u32, String> {
+    let value = location
+        .get(name)
+        .ok_or_else(|| format!("missing \"location.{}\"", name))?;
+
+    let number = match value {
+        Value::Number(n) if n.is_u64() || n.is_i64() => n.as_i64(),
+        Value::Number(n) => {
+            quality.coerced_fields += 1;
+            n.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i64)
+        }
+        Value::String(s) => {
+            quality.coerced_fields += 1;
+            s.trim().parse().ok()
+        }
+        _ => None,
+    };
+
+    match number {
+        Some(n) if n >= 1 && n <= u32::MAX as i64 => Ok(n as u32),
+        Some(n) => Err(format!("location.{} {} is out of range", name, n)),
+        None => Err(format!("location.{} is not a line number", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PromptsConfig;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_convert_suggestion() {
+        let raw = json!({
+            "suggestions": [{
+                "id": "S001",
+                "type": "security",
+                "severity": "critical",
+                "location": { "file": "src/main.rs", "line_start": 10, "line_end": 15 },
+                "description": "SQL injection vulnerability",
+                "proposed_fix": "Use parameterized queries"
+            }]
+        });
+
+        let parsed = parse_output(&raw.to_string(), &SuggestionType::ALL).unwrap();
+        let suggestion = &parsed.output.suggestions[0];
+
+        assert_eq!(suggestion.id, "S001");
+        assert_eq!(suggestion.suggestion_type, SuggestionType::Security);
+        assert_eq!(suggestion.severity, Severity::Critical);
+        assert_eq!(suggestion.location.file, "src/main.rs");
+        assert!(parsed.output.quality.is_clean());
+        assert!(parsed.problems.is_empty());
+    }
+
+    #[test]
+    fn test_system_prompt_instructions() {
+        let prompts = ReviewPrompts::default();
+        assert!(!build_system_prompt(&prompts, &[], &[]).contains("Additional instructions"));
+
+        let prompt = build_system_prompt(
+            &prompts,
+            &["Skip doc comments on private items".to_string()],
+            &[],
+        );
+        assert!(prompt.contains("Additional instructions for this repository"));
+        assert!(prompt.ends_with("- Skip doc comments on private items\n"));
+    }
+
+    #[test]
+    fn test_review_prompts() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("instructions.md");
+        fs::write(&file, "We use `anyhow` everywhere.\n").unwrap();
+
+        let config: Config = serde_yaml::from_str(&format!(
+            r#"
+prompts:
+  system: You review Rust services.
+  instructions: Don't flag unwrap in tests.
+  instructions_file: {}
+  paths:
+    - paths: ["migrations/**"]
+      instructions: Check for locking DDL.
+"#,
+            file.display()
+        ))
+        .unwrap();
+        let prompts = ReviewPrompts::from_config(&config).unwrap();
+
+        let diff = "diff --git a/src/db.rs b/src/db.rs\n--- a/src/db.rs\n+++ b/src/db.rs\n";
+        let prompt = build_system_prompt(&prompts, &[], &diff_files(diff));
+        assert!(prompt.starts_with("You review Rust services.\n\nRespond with a JSON object"));
+        assert!(prompt.contains("Don't flag unwrap in tests."));
+        assert!(prompt.contains(
+            "House rules for this repository:\nDon't flag unwrap in tests.\n\nWe use `anyhow` everywhere.\n"
+        ));
+        assert_eq!(prompt.matches("House rules").count(), 1);
+        assert!(!prompt.contains("locking DDL"));
+
+        let files = vec![
+            "src/db.rs".to_string(),
+            "migrations/001_init.sql".to_string(),
+        ];
+        let scoped = build_system_prompt(&prompts, &[], &files);
+        assert!(scoped.ends_with("For files matching migrations/**:\nCheck for locking DDL.\n"));
+        assert_ne!(prompt_hash(&prompt), prompt_hash(&scoped));
+
+        // Path rules are part of the cache fingerprint even when they don't match
+        let without_paths = ReviewPrompts {
+            paths: Vec::new(),
+            ..prompts.clone()
+        };
+        assert_ne!(
+            reviewer_fingerprint("openai", "gpt-4o", 0.1, &prompts, &[]),
+            reviewer_fingerprint("openai", "gpt-4o", 0.1, &without_paths, &[])
+        );
+
+        let missing = Config {
+            prompts: PromptsConfig {
+                instructions_file: dir.path().join("missing.md").display().to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let prompts = ReviewPrompts::from_config(&missing).unwrap();
+        assert_eq!(
+            build_system_prompt(&prompts, &[], &files),
+            build_system_prompt(&ReviewPrompts::default(), &[], &[])
+        );
+    }
+
+    #[test]
+    fn test_custom_categories() {
+        let config: Config = serde_yaml::from_str(
+            r#"
+categories:
+  - name: concurrency
+    description: Data races, deadlocks and lock ordering
+  - name: api-compat
+    description: Breaking changes to public APIs
+"#,
+        )
+        .unwrap();
+        let prompts = ReviewPrompts::from_config(&config).unwrap();
+        let types = prompts.suggestion_types();
+        assert_eq!(types.len(), 7);
+        assert_eq!(types[6], SuggestionType::Custom("api-compat".to_string()));
+
+        let prompt = build_system_prompt(&prompts, &[], &[]);
+        assert!(prompt.contains("- concurrency: Data races, deadlocks and lock ordering\n"));
+        assert!(prompt.contains(
+            "\"type\": \"security|performance|logic|style|documentation|concurrency|api-compat\""
+        ));
+        assert_eq!(
+            review_schema(&types)["properties"]["suggestions"]["items"]["properties"]["type"]
+                ["enum"][5],
+            json!("concurrency")
+        );
+
+        let raw = json!({
+            "suggestions": [
+                {
+                    "id": "S001",
+                    "type": "Concurrency",
+                    "severity": "high",
+                    "location": { "file": "a.rs", "line_start": 1, "line_end": 2 },
+                    "description": "Lock taken in the wrong order",
+                    "proposed_fix": null,
+                    "tags": ["Locking", "locking", " mutex ", 3]
+                },
+                {
+                    "id": "S002",
+                    "type": "testing",
+                    "severity": "low",
+                    "location": { "file": "a.rs", "line_start": 5, "line_end": 5 },
+                    "description": "Missing test",
+                    "proposed_fix": null
+                }
+            ]
+        });
+        let parsed = parse_output(&raw.to_string(), &types).unwrap();
+        let suggestion = &parsed.output.suggestions[0];
+        assert_eq!(suggestion.suggestion_type.as_str(), "concurrency");
+        assert_eq!(suggestion.tags, vec!["locking", "mutex"]);
+        assert_eq!(parsed.output.quality.coerced_fields, 1);
+        assert_eq!(parsed.output.quality.dropped_fields, 1);
+        assert!(parsed.problems[0].contains("unknown type \"testing\""));
+
+        for bad in ["Not_Valid!", "security"] {
+            let config = Config {
+                categories: vec![crate::config::CategoryConfig {
+                    name: bad.to_string(),
+                    description: String::new(),
+                }],
+                ..Default::default()
+            };
+            assert!(ReviewPrompts::from_config(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_confidence() {
+        let item = |confidence: Value| {
+            json!({
+                "suggestions": [{
+                    "id": "S001",
+                    "type": "logic",
+                    "severity": "high",
+                    "location": { "file": "a.rs", "line_start": 1, "line_end": 2 },
+                    "description": "Bug",
+                    "proposed_fix": null,
+                    "confidence": confidence
+                }]
+            })
+            .to_string()
+        };
+
+        for (raw, expected, coerced, dropped) in [
+            (json!(0.75), Some(0.75), 0, 0),
+            (json!(null), None, 0, 0),
+            (json!("0.5"), Some(0.5), 1, 0),
+            (json!(80), Some(0.8), 1, 0),
+            (json!(-1), None, 0, 1),
+            (json!("very"), None, 1, 1),
+        ] {
+            let parsed = parse_output(&item(raw.clone()), &SuggestionType::ALL).unwrap();
+            assert_eq!(parsed.output.suggestions[0].confidence, expected, "{}", raw);
+            assert_eq!(parsed.output.quality.coerced_fields, coerced, "{}", raw);
+            assert_eq!(parsed.output.quality.dropped_fields, dropped, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_parse_replacement() {
+        let item = |replacement: Value| {
+            json!({
+                "suggestions": [{
+                    "id": "S001",
+                    "type": "logic",
+                    "severity": "high",
+                    "location": { "file": "a.rs", "line_start": 1, "line_end": 2 },
+                    "description": "Bug",
+                    "proposed_fix": "Use checked_add",
+                    "replacement": replacement
+                }]
+            })
+            .to_string()
+        };
+
+        let raw = json!({ "line_start": 2, "line_end": 1, "old_text": "a + b", "new_text": "" });
+        let parsed = parse_output(&item(raw), &SuggestionType::ALL).unwrap();
+        let replacement = parsed.output.suggestions[0].replacement.as_ref().unwrap();
+        assert_eq!((replacement.line_start, replacement.line_end), (1, 2));
+        assert_eq!(replacement.new_text, "");
+        assert_eq!(parsed.output.quality.coerced_fields, 1);
+
+        for raw in [
+            json!({ "line_start": 1, "line_end": 1, "old_text": "", "new_text": "x" }),
+            json!({ "line_start": 0, "line_end": 1, "old_text": "a", "new_text": "x" }),
+            json!("replace a with x"),
+        ] {
+            let parsed = parse_output(&item(raw.clone()), &SuggestionType::ALL).unwrap();
+            assert!(
+                parsed.output.suggestions[0].replacement.is_none(),
+                "{}",
+                raw
+            );
+            assert_eq!(parsed.output.quality.dropped_fields, 1, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_parse_fenced_output() {
+        let content = "Here is the review:\n```json\n{\"suggestions\": [{\"id\": \"S001\", \"type\": \"logic\", \"severity\": \"high\", \"location\": {\"file\": \"a.rs\", \"line_start\": 1, \"line_end\": 2}, \"description\": \"Bug\", \"proposed_fix\": null}]}\n```";
+
+        let parsed = parse_output(content, &SuggestionType::ALL).unwrap();
+        assert_eq!(parsed.output.suggestions.len(), 1);
+        assert_eq!(parsed.output.suggestions[0].severity, Severity::High);
+
+        assert!(parse_output("no json here", &SuggestionType::ALL).is_err());
+        assert!(parse_output("{\"issues\": []}", &SuggestionType::ALL).is_err());
+    }
+
+    #[test]
+    fn test_coerce_and_drop() {
+        let raw = json!({
+            "suggestions": [
+                {
+                    "type": "Security ",
+                    "severity": "HIGH",
+                    "location": { "file": "a.rs", "line_start": "12", "line_end": 4 },
+                    "description": "Token logged",
+                    "proposed_fix": ["not", "a", "string"]
+                },
+                {
+                    "id": "S002",
+                    "type": "typo",
+                    "severity": "low",
+                    "location": { "file": "b.rs", "line_start": 1, "line_end": 1 },
+                    "description": "Misspelled word",
+                    "proposed_fix": null
+                },
+                {
+                    "id": "S003",
+                    "type": "logic",
+                    "severity": "medium",
+                    "location": { "file": "c.rs", "line_start": 0, "line_end": 3 },
+                    "description": "Off by one",
+                    "proposed_fix": ""
+                },
+                {
+                    "id": "S004",
+                    "type": "logic",
+                    "severity": "medium",
+                    "location": { "file": "../../.ssh/authorized_keys", "line_start": 1, "line_end": 1 },
+                    "description": "Escapes the repository",
+                    "proposed_fix": null
+                },
+                {
+                    "id": "S005",
+                    "type": "logic",
+                    "severity": "medium",
+                    "location": { "file": "/etc/cron.d/x", "line_start": 1, "line_end": 1 },
+                    "description": "Absolute path",
+                    "proposed_fix": null
+                }
+            ]
+        });
+
+        let parsed = parse_output(&raw.to_string(), &SuggestionType::ALL).unwrap();
+        assert_eq!(parsed.output.suggestions.len(), 1);
+
+        let s = &parsed.output.suggestions[0];
+        assert_eq!(s.id, "S001");
+        assert_eq!(s.suggestion_type, SuggestionType::Security);
+        assert_eq!(s.severity, Severity::High);
+        assert_eq!((s.location.line_start, s.location.line_end), (4, 12));
+        assert_eq!(s.proposed_fix, None);
+
+        // id, type, severity, line_start and the reversed range
+        assert_eq!(
+            parsed.output.quality,
+            OutputQuality {
+                coerced_fields: 5,
+                dropped_fields: 1,
+                dropped_suggestions: 4,
+                repair_rounds: 0,
+            }
+        );
+        assert_eq!(parsed.problems.len(), 4);
+        assert!(parsed.problems[0].contains("unknown type \"typo\""));
+        assert!(parsed.problems[1].contains("location.line_start 0 is out of range"));
+        assert!(parsed.problems[2].contains("not a path inside the repository"));
+        assert!(parsed.problems[3].contains("\"/etc/cron.d/x\""));
+    }
+
+    #[test]
+    fn test_schema_enums() {
+        let schema = review_schema(&SuggestionType::ALL);
+        let item = &schema["properties"]["suggestions"]["items"];
+
+        assert_eq!(
+            item["properties"]["type"]["enum"],
+            json!(["security", "performance", "logic", "style", "documentation"])
+        );
+        assert_eq!(
+            item["properties"]["severity"]["enum"],
+            json!(["critical", "high", "medium", "low"])
+        );
+        assert_eq!(item["required"].as_array().unwrap().len(), 9);
+    }
+
+    /// Replays canned answers and records the conversations it was sent
+    struct ScriptedModel {
+        answers: Mutex<Vec<&'static str>>,
+        seen: Mutex<Vec<Vec<ChatMessage>>>,
+    }
+
+    impl ScriptedModel {
+        fn new(mut answers: Vec<&'static str>) -> Self {
+            answers.reverse();
+            Self {
+                answers: Mutex::new(answers),
+                seen: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ChatModel for ScriptedModel {
+        async fn complete(&self, _system: &str, messages: &[ChatMessage]) -> Result<Completion> {
+            self.seen.lock().unwrap().push(messages.to_vec());
+            let content = self.answers.lock().unwrap().pop().unwrap().to_string();
+            Ok(Completion {
+                interaction: LlmInteraction {
+                    id: uuid::Uuid::new_v4(),
+                    review_id: None,
+                    provider: "scripted".to_string(),
+                    model: "test".to_string(),
+                    created_at: chrono::Utc::now(),
+                    latency_ms: 1,
+                    request: json!({ "messages": messages }),
+                    response: json!({ "content": content }),
+                    prompt_tokens: Some(100),
+                    completion_tokens: Some(20),
+                    cost_usd: None,
+                },
+                content,
+            })
+        }
+    }
+
+    const VALID: &str = r#"{"suggestions": [{"id": "S001", "type": "logic", "severity": "high", "location": {"file": "a.rs", "line_start": 1, "line_end": 2}, "description": "Bug", "proposed_fix": null}]}"#;
+
+    #[tokio::test]
+    async fn test_repair_round() {
+        let model = ScriptedModel::new(vec!["{\"suggestions\": [", VALID]);
+
+        let output = review_with_repair(
+            &model,
+            "System".to_string(),
+            "Review this".to_string(),
+            &SuggestionType::ALL,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.suggestions.len(), 1);
+        assert_eq!(output.quality.repair_rounds, 1);
+        assert_eq!(output.interactions.len(), 2);
+        assert_eq!(output.prompt_hash, Some(prompt_hash("System")));
+
+        let seen = model.seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[1].len(), 3);
+        assert_eq!(seen[1][1].role, "assistant");
+        assert!(seen[1][2].content.contains("Response is not valid JSON"));
+    }
+
+    #[tokio::test]
+    async fn test_repair_keeps_valid_suggestions() {
+        let partial = r#"{"suggestions": [{"id": "S001", "type": "logic", "severity": "high", "location": {"file": "a.rs", "line_start": 1, "line_end": 2}, "description": "Bug", "proposed_fix": null}, {"id": "S002", "type": "logic", "severity": "urgent", "location": {"file": "a.rs", "line_start": 5, "line_end": 5}, "description": "Other", "proposed_fix": null}]}"#;
+        let model = ScriptedModel::new(vec![partial, "still not json"]);
+
+        let output = review_with_repair(
+            &model,
+            "System".to_string(),
+            "Review this".to_string(),
+            &SuggestionType::ALL,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.suggestions.len(), 1);
+        assert_eq!(output.quality.dropped_suggestions, 1);
+        assert_eq!(output.quality.repair_rounds, 1);
+
+        let model = ScriptedModel::new(vec![VALID]);
+        let output = review_with_repair(
+            &model,
+            "System".to_string(),
+            "Review this".to_string(),
+            &SuggestionType::ALL,
+        )
+        .await
+        .unwrap();
+        assert!(output.quality.is_clean());
+        assert_eq!(model.seen.lock().unwrap().len(), 1);
+    }
+}
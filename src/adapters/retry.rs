@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde_json::Value;
+use tracing::warn;
+
+use super::error::ReviewerError;
+use crate::config::RetryConfig;
+
+/// Send a request, retrying rate limits, server errors and timeouts with backoff
+///
+/// `request` builds a fresh request for every attempt. A `Retry-After` header on
+/// the response takes precedence over the computed backoff, up to `max_backoff`.
+pub(crate) async fn send_with_retry(
+    provider: &str,
+    retry: &RetryConfig,
+    request: impl Fn() -> RequestBuilder,
+) -> Result<Response, ReviewerError> {
+    let timeout = Duration::from_secs(retry.timeout_secs);
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+
+        let (error, retry_after) = match request().timeout(timeout).send().await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let status = response.status();
+                let retry_after = retry_after(response.headers());
+                let body = response.text().await.unwrap_or_default();
+                let error = if status == StatusCode::TOO_MANY_REQUESTS {
+                    ReviewerError::RateLimited {
+                        provider: provider.to_string(),
+                        attempts,
+                        body,
+                    }
+                } else {
+                    ReviewerError::Api {
+                        provider: provider.to_string(),
+                        status,
+                        attempts,
+                        body,
+                    }
+                };
+                (error, retry_after)
+            }
+            Err(source) if source.is_timeout() => (
+                ReviewerError::Timeout {
+                    provider: provider.to_string(),
+                    timeout,
+                    attempts,
+                },
+                None,
+            ),
+            Err(source) => (
+                ReviewerError::Transport {
+                    provider: provider.to_string(),
+                    source,
+                },
+                None,
+            ),
+        };
+
+        if attempts > retry.max_retries || !error.is_transient() {
+            return Err(error);
+        }
+
+        let delay = retry_delay(retry, attempts, retry_after);
+        warn!(
+            provider,
+            attempt = attempts,
+            delay_ms = delay.as_millis() as u64,
+            error = %error,
+            "Retrying LLM request"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Read a successful response body as JSON
+pub(crate) async fn read_json(provider: &str, response: Response) -> Result<Value, ReviewerError> {
+    let body = response
+        .text()
+        .await
+        .map_err(|source| ReviewerError::Transport {
+            provider: provider.to_string(),
+            source,
+        })?;
+
+    serde_json::from_str(&body).map_err(|source| ReviewerError::InvalidResponse {
+        provider: provider.to_string(),
+        source,
+    })
+}
+
+/// Exponential backoff with jitter: a random delay between half and all of
+/// `initial_backoff * 2^(attempt - 1)`, capped at `max_backoff`
+fn backoff_delay(retry: &RetryConfig, attempt: u32) -> Duration {
+    let exponential = retry
+        .initial_backoff_ms
+        .saturating_mul(1u64 << (attempt - 1).min(20));
+    let capped = exponential.min(retry.max_backoff_ms);
+    let jittered = rand::thread_rng().gen_range(capped / 2..=capped);
+
+    Duration::from_millis(jittered)
+}
+
+/// Delay before the next attempt: the server's `Retry-After` if it sent one, else
+/// backoff, never more than `max_backoff` so a hostile or confused server can't stall
+/// the review
+fn retry_delay(retry: &RetryConfig, attempt: u32, retry_after: Option<Duration>) -> Duration {
+    let max = Duration::from_millis(retry.max_backoff_ms);
+    retry_after
+        .map(|delay| delay.min(max))
+        .unwrap_or_else(|| backoff_delay(retry, attempt))
+}
+
+/// Parse `Retry-After` as either delay seconds or an HTTP date
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&Utc) - Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_backoff_delay() {
+        let retry = RetryConfig {
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 5000,
+            ..Default::default()
+        };
+
+        for _ in 0..20 {
+            let first = backoff_delay(&retry, 1).as_millis();
+            assert!((500..=1000).contains(&first));
+
+            let third = backoff_delay(&retry, 3).as_millis();
+            assert!((2000..=4000).contains(&third));
+
+            let capped = backoff_delay(&retry, 30).as_millis();
+            assert!((2500..=5000).contains(&capped));
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_caps_retry_after() {
+        let retry = RetryConfig {
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 5000,
+            ..Default::default()
+        };
+
+        let short = Some(Duration::from_secs(2));
+        assert_eq!(retry_delay(&retry, 1, short), Duration::from_secs(2));
+
+        let day = Some(Duration::from_secs(86_400));
+        assert_eq!(retry_delay(&retry, 1, day), Duration::from_secs(5));
+
+        let backoff = retry_delay(&retry, 1, None).as_millis();
+        assert!((500..=1000).contains(&backoff));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("0.5"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(500)));
+
+        // Dates in the past mean "retry now"
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+}
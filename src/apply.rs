@@ -0,0 +1,486 @@
+//! Apply accepted fixes to the working tree
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use tracing::debug;
+
+use crate::models::{HumanDecision, Replacement, Review, SuggestionItem};
+
+/// Outcome of applying the accepted fixes of a review
+#[derive(Debug, Default)]
+pub struct ApplyReport {
+    /// Suggestions whose fix was written
+    pub applied: Vec<String>,
+    /// Files changed by the applied fixes
+    pub files: Vec<PathBuf>,
+    /// Fixes that don't match the current file contents
+    pub conflicts: Vec<ApplyConflict>,
+    /// Accepted suggestions without a structured fix, left for a human
+    pub manual: Vec<String>,
+}
+
+/// A fix that couldn't be applied, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplyConflict {
+    pub suggestion_id: String,
+    pub file: String,
+    pub reason: String,
+}
+
+/// Apply every accepted, not yet applied fix of `review` to the files under `root`
+///
+/// A fix applies where its `old_text` still sits at the reported lines, or at the one
+/// other place it occurs if the code has moved since the review. Anything else is
+/// reported as a conflict and that fix is skipped. Nothing is written with `dry_run`.
+pub fn apply_fixes(root: &Path, review: &Review, dry_run: bool) -> Result<ApplyReport> {
+    let items = review.suggestions.iter().filter(|i| i.applied_at.is_none());
+    apply_items(root, items, dry_run)
+}
+
+/// Apply every accepted fix of `review`, including ones applied before
+///
+/// For rebuilding a fix branch from the reviewed commit, see `fix_pr`.
+pub fn reapply_fixes(root: &Path, review: &Review) -> Result<ApplyReport> {
+    apply_items(root, review.suggestions.iter(), false)
+}
+
+/// Whether `file` is a relative path that can't leave the repository root
+///
+/// File paths come from the model, so an absolute path or a `..` must never reach the
+/// file system.
+pub fn is_repo_path(file: &str) -> bool {
+    let path = Path::new(file);
+    !file.is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+fn apply_items<'a>(
+    root: &Path,
+    items: impl Iterator<Item = &'a SuggestionItem>,
+    dry_run: bool,
+) -> Result<ApplyReport> {
+    let mut report = ApplyReport::default();
+    let mut by_file: BTreeMap<&str, Vec<(&str, &Replacement)>> = BTreeMap::new();
+
+    for item in items {
+        let accepted = item
+            .decision
+            .as_ref()
+            .is_some_and(|d| d.decision == HumanDecision::Accepted);
+        if !accepted || item.resolved_in.is_some() {
+            continue;
+        }
+
+        let s = &item.suggestion;
+        match &s.replacement {
+            Some(replacement) => by_file
+                .entry(&s.location.file)
+                .or_default()
+                .push((&s.id, replacement)),
+            None => report.manual.push(s.id.clone()),
+        }
+    }
+
+    for (file, fixes) in by_file {
+        let path = root.join(file);
+        let content = match resolve_inside(root, file, &path)
+            .and_then(|()| fs::read_to_string(&path).map_err(|e| format!("can't read file: {}", e)))
+        {
+            Ok(content) => content,
+            Err(reason) => {
+                for (id, _) in fixes {
+                    report.conflicts.push(ApplyConflict {
+                        suggestion_id: id.to_string(),
+                        file: file.to_string(),
+                        reason: reason.clone(),
+                    });
+                }
+                continue;
+            }
+        };
+
+        let mut edits: Vec<(Range<usize>, String, &str)> = Vec::new();
+        for (id, replacement) in fixes {
+            let crlf = content.contains("\r\n");
+            let (old_text, new_text) = normalize(replacement, crlf);
+
+            let located = locate(&content, replacement, &old_text).and_then(|range| {
+                match edits.iter().find(|(other, _, _)| overlaps(other, &range)) {
+                    Some((_, _, other)) => Err(format!("overlaps the fix for {}", other)),
+                    None => Ok(range),
+                }
+            });
+
+            match located {
+                Ok(range) => edits.push((range, new_text, id)),
+                Err(reason) => report.conflicts.push(ApplyConflict {
+                    suggestion_id: id.to_string(),
+                    file: file.to_string(),
+                    reason,
+                }),
+            }
+        }
+
+        if edits.is_empty() {
+            continue;
+        }
+
+        // Apply back to front so earlier byte offsets stay valid
+        edits.sort_by_key(|(range, _, _)| std::cmp::Reverse(range.start));
+        let mut updated = content.clone();
+        for (range, new_text, id) in &edits {
+            updated.replace_range(range.clone(), new_text);
+            report.applied.push(id.to_string());
+        }
+
+        if !dry_run {
+            fs::write(&path, updated)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        debug!(file, fixes = edits.len(), dry_run, "Applied fixes");
+        report.files.push(PathBuf::from(file));
+    }
+
+    report.applied.sort();
+    Ok(report)
+}
+
+/// Check that `path`, the joined `file`, resolves to a file inside `root`
+///
+/// Also catches symlinks that point out of the repository.
+fn resolve_inside(root: &Path, file: &str, path: &Path) -> Result<(), String> {
+    let outside = || "the path is outside the repository".to_string();
+    if !is_repo_path(file) {
+        return Err(outside());
+    }
+
+    let root = fs::canonicalize(root).map_err(|e| format!("can't resolve the root: {}", e))?;
+    match fs::canonicalize(path) {
+        Ok(resolved) if resolved.starts_with(&root) => Ok(()),
+        Ok(_) => Err(outside()),
+        Err(e) => Err(format!("can't read file: {}", e)),
+    }
+}
+
+/// Old and new text in the file's line endings, without a trailing newline on `old_text`
+fn normalize(replacement: &Replacement, crlf: bool) -> (String, String) {
+    let mut old_text = replacement.old_text.replace("\r\n", "\n");
+    let mut new_text = replacement.new_text.replace("\r\n", "\n");
+    if old_text.ends_with('\n') {
+        old_text.pop();
+        if new_text.ends_with('\n') {
+            new_text.pop();
+        }
+    }
+
+    if crlf {
+        (
+            old_text.replace('\n', "\r\n"),
+            new_text.replace('\n', "\r\n"),
+        )
+    } else {
+        (old_text, new_text)
+    }
+}
+
+/// Byte range of `old_text` in `content`, at the reported lines or wherever it uniquely occurs
+fn locate(
+    content: &str,
+    replacement: &Replacement,
+    old_text: &str,
+) -> Result<Range<usize>, String> {
+    if old_text.is_empty() {
+        return Err("the fix has no text to replace".to_string());
+    }
+
+    if let Some(range) = line_range(content, replacement.line_start, replacement.line_end) {
+        if content[range.clone()] == *old_text {
+            return Ok(range);
+        }
+    }
+
+    let found: Vec<usize> = content.match_indices(old_text).map(|(i, _)| i).collect();
+    match found.as_slice() {
+        [start] => Ok(*start..start + old_text.len()),
+        [] => Err(format!(
+            "lines {}-{} no longer contain the text the fix replaces",
+            replacement.line_start, replacement.line_end
+        )),
+        _ => Err(format!(
+            "lines {}-{} changed and the replaced text occurs {} times",
+            replacement.line_start,
+            replacement.line_end,
+            found.len()
+        )),
+    }
+}
+
+/// Byte range of lines `start..=end` (1-based), excluding the final line break
+fn line_range(content: &str, start: u32, end: u32) -> Option<Range<usize>> {
+    if start == 0 || end < start {
+        return None;
+    }
+
+    // A final line break doesn't start another line
+    let body = content.strip_suffix('\n').unwrap_or(content);
+    let mut offsets = vec![0];
+    offsets.extend(body.match_indices('\n').map(|(i, _)| i + 1));
+
+    let from = *offsets.get(start as usize - 1)?;
+    let to = match offsets.get(end as usize) {
+        Some(&next) => next - 1,
+        None if end as usize == offsets.len() => body.len(),
+        None => return None,
+    };
+    let to = if body[..to].ends_with('\r') {
+        to - 1
+    } else {
+        to
+    };
+
+    Some(from..to)
+}
+
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Commit message for the applied fixes of `review`
+pub fn commit_message(review: &Review, applied: &[String]) -> String {
+    format!(
+        "Apply accepted review fixes for {}\n\nSuggestions {} from review {}",
+        target_label(review),
+        applied.join(", "),
+        review.id
+    )
+}
+
+/// "PR #12" or "commit abc1234"
+pub(crate) fn target_label(review: &Review) -> String {
+    match review.pr_number {
+        Some(pr) => format!("PR #{}", pr),
+        None => format!(
+            "commit {}",
+            &review.commit_sha[..7.min(review.commit_sha.len())]
+        ),
+    }
+}
+
+/// Commit the changed files with git, returning the new commit SHA
+pub fn commit_fixes(root: &Path, files: &[PathBuf], message: &str) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["commit", "--only", "-m", message, "--"])
+        .args(files)
+        .output()
+        .context("Failed to run git commit")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git commit failed: {}", stderr.trim());
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .context("Failed to run git rev-parse")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git rev-parse failed: {}", stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DecisionRecord, ReviewContext, ReviewStatus, Severity, SuggestionItem};
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    fn make_item(id: &str, file: &str, replacement: Option<Replacement>) -> SuggestionItem {
+        let mut item = SuggestionItem::test(id, file).with_severity(Severity::High);
+        item.suggestion.replacement = replacement;
+        SuggestionItem {
+            decision: Some(DecisionRecord {
+                suggestion_id: id.to_string(),
+                decision: HumanDecision::Accepted,
+                reason: None,
+                decided_by: "alice".to_string(),
+                decided_at: Utc::now(),
+                deferred_until: None,
+                verified: false,
+            }),
+            ..item
+        }
+    }
+
+    fn replace(line_start: u32, line_end: u32, old_text: &str, new_text: &str) -> Replacement {
+        Replacement {
+            line_start,
+            line_end,
+            old_text: old_text.to_string(),
+            new_text: new_text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_fixes() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("lib.rs"),
+            "fn a() {\n    let x = 1;\n}\n\nfn b() {\n    old();\n}\n",
+        )
+        .unwrap();
+
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+        review.status = ReviewStatus::Decided;
+        review.suggestions = vec![
+            make_item(
+                "S001",
+                "lib.rs",
+                Some(replace(2, 2, "    let x = 1;", "    let x = 2;")),
+            ),
+            // Reported at the wrong lines, but the text is unique
+            make_item(
+                "S002",
+                "lib.rs",
+                Some(replace(3, 3, "    old();\n", "    new();\n")),
+            ),
+            make_item(
+                "S003",
+                "lib.rs",
+                Some(replace(2, 2, "    let y = 1;", "    let y = 2;")),
+            ),
+            make_item("S004", "lib.rs", None),
+            make_item("S005", "missing.rs", Some(replace(1, 1, "a", "b"))),
+        ];
+
+        let report = apply_fixes(dir.path(), &review, true).unwrap();
+        assert_eq!(report.applied, vec!["S001", "S002"]);
+        let content = fs::read_to_string(dir.path().join("lib.rs")).unwrap();
+        assert!(content.contains("let x = 1"));
+
+        let report = apply_fixes(dir.path(), &review, false).unwrap();
+        assert_eq!(report.applied, vec!["S001", "S002"]);
+        assert_eq!(report.files, vec![PathBuf::from("lib.rs")]);
+        assert_eq!(report.manual, vec!["S004"]);
+        let conflicts: Vec<&str> = report
+            .conflicts
+            .iter()
+            .map(|c| c.suggestion_id.as_str())
+            .collect();
+        assert_eq!(conflicts, vec!["S003", "S005"]);
+        assert!(report.conflicts[0].reason.contains("no longer contain"));
+
+        let content = fs::read_to_string(dir.path().join("lib.rs")).unwrap();
+        assert_eq!(
+            content,
+            "fn a() {\n    let x = 2;\n}\n\nfn b() {\n    new();\n}\n"
+        );
+
+        review.mark_applied(&report.applied, Utc::now());
+        assert!(review.suggestions[0].applied_at.is_some());
+        // S003 and S005 are still waiting for a fix
+        assert_eq!(review.status, ReviewStatus::Decided);
+
+        let report = apply_fixes(dir.path(), &review, false).unwrap();
+        assert!(report.applied.is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_and_crlf() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "one\r\ntwo\r\nthree\r\n").unwrap();
+
+        let mut review = Review::new(ReviewContext {
+            pr_number: None,
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+        review.suggestions = vec![
+            make_item("S001", "a.txt", Some(replace(1, 2, "one\ntwo", "uno\ndos"))),
+            make_item("S002", "a.txt", Some(replace(2, 2, "two", "deux"))),
+        ];
+
+        let report = apply_fixes(dir.path(), &review, false).unwrap();
+        assert_eq!(report.applied, vec!["S001"]);
+        assert_eq!(report.conflicts[0].reason, "overlaps the fix for S001");
+        let content = fs::read_to_string(dir.path().join("a.txt")).unwrap();
+        assert_eq!(content, "uno\r\ndos\r\nthree\r\n");
+
+        review.suggestions.truncate(1);
+        review.mark_applied(&report.applied, Utc::now());
+        assert_eq!(review.status, ReviewStatus::Applied);
+    }
+
+    #[test]
+    fn test_paths_outside_root() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("repo");
+        fs::create_dir(&root).unwrap();
+        fs::write(dir.path().join("outside.rs"), "secret\n").unwrap();
+
+        let mut review = Review::new(ReviewContext {
+            pr_number: None,
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+        let absolute = dir.path().join("outside.rs").display().to_string();
+        review.suggestions = vec![
+            make_item(
+                "S001",
+                "../outside.rs",
+                Some(replace(1, 1, "secret", "pwned")),
+            ),
+            make_item("S002", &absolute, Some(replace(1, 1, "secret", "pwned"))),
+        ];
+
+        let report = apply_fixes(&root, &review, false).unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(report.conflicts.len(), 2);
+        assert_eq!(report.conflicts[0].suggestion_id, "S001");
+        assert_eq!(
+            report.conflicts[0].reason,
+            "the path is outside the repository"
+        );
+        let content = fs::read_to_string(dir.path().join("outside.rs")).unwrap();
+        assert_eq!(content, "secret\n");
+
+        assert!(is_repo_path("src/lib.rs"));
+        assert!(is_repo_path("./src/lib.rs"));
+        assert!(!is_repo_path("src/../../etc/passwd"));
+        assert!(!is_repo_path("/etc/cron.d/x"));
+        assert!(!is_repo_path(""));
+    }
+
+    #[test]
+    fn test_line_range() {
+        let content = "a\nbb\nccc";
+        assert_eq!(line_range(content, 1, 1), Some(0..1));
+        assert_eq!(line_range(content, 2, 3), Some(2..8));
+        assert_eq!(line_range(content, 4, 4), None);
+    }
+}
@@ -0,0 +1,321 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::models::{HumanDecision, Review, Severity, SuggestionItem, SuggestionType};
+
+/// Filter for bulk decisions; empty filter (`--all`) matches every pending suggestion
+#[derive(Debug, Clone)]
+pub struct BulkFilter {
+    recommended: bool,
+    severity: Option<Severity>,
+    suggestion_type: Option<SuggestionType>,
+    file: Option<glob::Pattern>,
+}
+
+impl BulkFilter {
+    pub fn new(
+        all: bool,
+        recommended: bool,
+        severity: Option<Severity>,
+        suggestion_type: Option<SuggestionType>,
+        file: Option<String>,
+    ) -> Result<Self> {
+        if !all && !recommended && severity.is_none() && suggestion_type.is_none() && file.is_none()
+        {
+            anyhow::bail!(
+                "Must specify --suggestion, --all, --accept-all-recommended, --severity, --type or --file"
+            );
+        }
+
+        let file = file
+            .map(|f| glob::Pattern::new(&f).with_context(|| format!("Invalid file glob: {}", f)))
+            .transpose()?;
+
+        Ok(Self {
+            recommended,
+            severity,
+            suggestion_type,
+            file,
+        })
+    }
+
+    /// Whether `item` is selected, with recommendations judged by `config`
+    pub fn matches(&self, item: &SuggestionItem, config: &Config) -> bool {
+        let s = &item.suggestion;
+        (!self.recommended || is_recommended(item, config))
+            && self.severity.is_none_or(|sev| s.severity == sev)
+            && self
+                .suggestion_type
+                .as_ref()
+                .is_none_or(|t| s.suggestion_type == *t)
+            && self
+                .file
+                .as_ref()
+                .is_none_or(|p| p.matches(&s.location.file))
+    }
+}
+
+/// Whether the reviewers recommend acting on a finding: it has a confidence that meets
+/// `review.min_confidence`, and in an ensemble a majority of reviewers reported it
+pub fn is_recommended(item: &SuggestionItem, config: &Config) -> bool {
+    let s = &item.suggestion;
+    s.confidence.is_some()
+        && config.meets_min_confidence(s)
+        && s.consensus
+            .as_ref()
+            .is_none_or(|c| c.flagged_by.len() * 2 > c.reviewers as usize)
+}
+
+/// Pending suggestions a bulk decision would be recorded for, and why others are skipped
+pub struct BulkPlan<'a> {
+    pub matched: Vec<&'a SuggestionItem>,
+    pub skipped: Vec<anyhow::Error>,
+}
+
+impl<'a> BulkPlan<'a> {
+    /// Select the pending suggestions of `review` matching `filter`; `check` refuses
+    /// those the decision can't be recorded for
+    pub fn new(
+        review: &'a Review,
+        filter: &BulkFilter,
+        config: &Config,
+        check: impl Fn(&SuggestionItem) -> Result<()>,
+    ) -> Self {
+        let mut plan = Self {
+            matched: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        for item in review.pending_suggestions() {
+            if !filter.matches(item, config) {
+                continue;
+            }
+            match check(item) {
+                Ok(()) => plan.matched.push(item),
+                Err(e) => plan.skipped.push(e),
+            }
+        }
+
+        plan
+    }
+
+    /// Print what would be recorded, returning the suggestion IDs to record: none
+    /// unless `yes` confirms the preview
+    pub fn preview(
+        &self,
+        decision: HumanDecision,
+        yes: bool,
+        out: &mut impl Write,
+    ) -> Result<Vec<String>> {
+        if !self.skipped.is_empty() {
+            writeln!(out, "Skipping {} suggestions:", self.skipped.len())?;
+            for e in &self.skipped {
+                writeln!(out, "  {}", e)?;
+            }
+            writeln!(out)?;
+        }
+
+        if self.matched.is_empty() {
+            writeln!(out, "No pending suggestions match.")?;
+            return Ok(Vec::new());
+        }
+
+        writeln!(
+            out,
+            "{:?} would be recorded for {} suggestions:\n",
+            decision,
+            self.matched.len()
+        )?;
+        for item in &self.matched {
+            let s = &item.suggestion;
+            writeln!(
+                out,
+                "  {} [{:?}/{:?}] {} (lines {}-{})",
+                s.id,
+                s.severity,
+                s.suggestion_type,
+                s.location.file,
+                s.location.line_start,
+                s.location.line_end
+            )?;
+        }
+        writeln!(out)?;
+
+        if !yes {
+            writeln!(out, "Re-run with --yes to record these decisions.")?;
+            return Ok(Vec::new());
+        }
+
+        Ok(self
+            .matched
+            .iter()
+            .map(|item| item.suggestion.id.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Consensus, ReviewContext};
+
+    fn make_review() -> Review {
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+
+        let findings = [
+            (
+                "S001",
+                Severity::Low,
+                SuggestionType::Style,
+                "src/legacy/a.rs",
+            ),
+            ("S002", Severity::Low, SuggestionType::Logic, "src/main.rs"),
+            (
+                "S003",
+                Severity::High,
+                SuggestionType::Style,
+                "src/legacy/b.rs",
+            ),
+        ];
+        for (id, severity, suggestion_type, file) in findings {
+            review.suggestions.push(
+                SuggestionItem::test(id, file)
+                    .with_type(suggestion_type)
+                    .with_severity(severity)
+                    .with_lines(1, 2)
+                    .with_description(format!("Description of {}", id)),
+            );
+        }
+
+        review
+    }
+
+    fn selected(review: &Review, filter: &BulkFilter, config: &Config) -> Vec<String> {
+        BulkPlan::new(review, filter, config, |_| Ok(()))
+            .matched
+            .iter()
+            .map(|item| item.suggestion.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let review = make_review();
+        let config = Config::default();
+
+        assert!(BulkFilter::new(false, false, None, None, None).is_err());
+        assert!(BulkFilter::new(false, false, None, None, Some("[".to_string())).is_err());
+
+        let all = BulkFilter::new(true, false, None, None, None).unwrap();
+        assert_eq!(selected(&review, &all, &config), ["S001", "S002", "S003"]);
+
+        let low = BulkFilter::new(false, false, Some(Severity::Low), None, None).unwrap();
+        assert_eq!(selected(&review, &low, &config), ["S001", "S002"]);
+
+        let legacy_style = BulkFilter::new(
+            false,
+            false,
+            None,
+            Some(SuggestionType::Style),
+            Some("src/legacy/**".to_string()),
+        )
+        .unwrap();
+        assert_eq!(selected(&review, &legacy_style, &config), ["S001", "S003"]);
+
+        let low_legacy = BulkFilter::new(
+            false,
+            false,
+            Some(Severity::Low),
+            None,
+            Some("src/legacy/**".to_string()),
+        )
+        .unwrap();
+        assert_eq!(selected(&review, &low_legacy, &config), ["S001"]);
+    }
+
+    #[test]
+    fn test_recommended() {
+        let mut review = make_review();
+        let config = Config::parse("review:\n  min_confidence: 0.7\n").unwrap();
+
+        // Confident; not confident enough; confident but a minority of the ensemble
+        review.suggestions[0].suggestion.confidence = Some(0.9);
+        review.suggestions[1].suggestion.confidence = Some(0.5);
+        review.suggestions[2].suggestion.confidence = Some(0.95);
+        review.suggestions[2].suggestion.consensus = Some(Consensus {
+            flagged_by: vec!["gpt".to_string()],
+            reviewers: 3,
+        });
+
+        let recommended = BulkFilter::new(false, true, None, None, None).unwrap();
+        assert_eq!(selected(&review, &recommended, &config), ["S001"]);
+
+        review.suggestions[2].suggestion.consensus = Some(Consensus {
+            flagged_by: vec!["gpt".to_string(), "claude".to_string()],
+            reviewers: 3,
+        });
+        assert_eq!(selected(&review, &recommended, &config), ["S001", "S003"]);
+
+        // Findings without a confidence are never recommended
+        review.suggestions[0].suggestion.confidence = None;
+        assert_eq!(selected(&review, &recommended, &config), ["S003"]);
+    }
+
+    #[test]
+    fn test_preview_and_confirm() {
+        let mut review = make_review();
+        review.suggestions[1].decision = Some(crate::policy::Identity::from("bob").record(
+            "S002",
+            HumanDecision::Accepted,
+            None,
+            None,
+        ));
+        let config = Config::default();
+        let all = BulkFilter::new(true, false, None, None, None).unwrap();
+        let plan = BulkPlan::new(&review, &all, &config, |item| {
+            if item.suggestion.id == "S003" {
+                anyhow::bail!("S003 is owned by @carol");
+            }
+            Ok(())
+        });
+
+        // Without --yes nothing is recorded
+        let mut out = Vec::new();
+        let ids = plan
+            .preview(HumanDecision::Rejected, false, &mut out)
+            .unwrap();
+        assert!(ids.is_empty());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Skipping 1 suggestions:\n  S003 is owned by @carol\n"));
+        assert!(out.contains("Rejected would be recorded for 1 suggestions:"));
+        assert!(out.contains("  S001 [Low/Style] src/legacy/a.rs (lines 1-2)\n"));
+        assert!(out.ends_with("Re-run with --yes to record these decisions.\n"));
+
+        let mut out = Vec::new();
+        let ids = plan
+            .preview(HumanDecision::Rejected, true, &mut out)
+            .unwrap();
+        assert_eq!(ids, ["S001"]);
+        assert!(!String::from_utf8(out).unwrap().contains("--yes"));
+
+        let low_logic =
+            BulkFilter::new(false, false, None, Some(SuggestionType::Logic), None).unwrap();
+        let mut out = Vec::new();
+        let ids = BulkPlan::new(&review, &low_logic, &config, |_| Ok(()))
+            .preview(HumanDecision::Rejected, true, &mut out)
+            .unwrap();
+        assert!(ids.is_empty());
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "No pending suggestions match.\n"
+        );
+    }
+}
@@ -0,0 +1,192 @@
+// Content-addressed cache of per-file review results
+
+use sha2::{Digest, Sha256};
+
+use crate::suppressions::Rejections;
+
+/// One file's section of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: String,
+    pub text: String,
+}
+
+/// Split a git diff into one section per file
+///
+/// Returns an empty list when the diff has no `diff --git` headers or has text
+/// before the first one, in which case it can't be cached per file.
+pub fn split_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+
+    for line in diff.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: header_path(header.trim_end()),
+                text: String::new(),
+            });
+        }
+
+        match files.last_mut() {
+            Some(file) => {
+                if let Some(path) = line.strip_prefix("+++ b/") {
+                    file.path = path.trim_end().to_string();
+                }
+                file.text.push_str(line);
+            }
+            None if line.trim().is_empty() => {}
+            None => return Vec::new(),
+        }
+    }
+
+    files
+}
+
+/// New path from the `a/<old> b/<new>` part of a `diff --git` header
+fn header_path(header: &str) -> String {
+    header
+        .rsplit_once(" b/")
+        .map(|(_, path)| path)
+        .unwrap_or(header)
+        .to_string()
+}
+
+/// Drop the parts of a file diff that change without the content changing
+///
+/// Blob hashes on `index` lines differ after a rebase, and the function name git
+/// appends to hunk headers depends on surrounding code. Hunk ranges are kept so
+/// cached line numbers stay correct.
+fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+
+    for line in text.lines() {
+        if line.starts_with("index ") {
+            continue;
+        }
+
+        let line = match line.strip_prefix("@@ ") {
+            Some(rest) => match rest.split_once(" @@") {
+                Some((ranges, _)) => format!("@@ {} @@", ranges),
+                None => line.to_string(),
+            },
+            None => line.trim_end().to_string(),
+        };
+        normalized.push_str(&line);
+        normalized.push('\n');
+    }
+
+    normalized
+}
+
+/// Cache key for a file diff reviewed by `fingerprint` with the given rejections
+///
+/// Only rejections in the same file are part of the key, so rejecting a finding
+/// invalidates the cache for that file alone.
+pub fn cache_key(file: &FileDiff, fingerprint: &str, rejections: Option<&Rejections>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(fingerprint.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(file.path.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(normalize(&file.text).as_bytes());
+
+    let mut rejected: Vec<String> = rejections
+        .map(|r| r.items.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.file == file.path)
+        .map(|r| {
+            format!(
+                "{}:{}-{}:{}:{}",
+                r.line_start, r.line_end, r.finding_type, r.description, r.reason
+            )
+        })
+        .collect();
+    rejected.sort();
+    for r in rejected {
+        hasher.update(b"\0");
+        hasher.update(r.as_bytes());
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suppressions::RejectedFinding;
+
+    const DIFF: &str = "\
+diff --git a/src/a.rs b/src/a.rs
+index 1111111..2222222 100644
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1,3 +1,4 @@ fn main() {
+ let a = 1;
++let b = 2;
+diff --git a/src/b.rs b/src/b.rs
+new file mode 100644
+--- /dev/null
++++ b/src/b.rs
+@@ -0,0 +1 @@
++pub fn b() {}
+";
+
+    #[test]
+    fn test_split_diff() {
+        let files = split_diff(DIFF);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/a.rs");
+        assert!(files[0].text.starts_with("diff --git a/src/a.rs"));
+        assert!(files[0].text.ends_with("+let b = 2;\n"));
+        assert_eq!(files[1].path, "src/b.rs");
+        assert_eq!(
+            files.iter().map(|f| f.text.as_str()).collect::<String>(),
+            DIFF
+        );
+
+        assert!(split_diff("--- a.rs\n+++ a.rs\n@@ -1 +1 @@\n-a\n+b\n").is_empty());
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let file = &split_diff(DIFF)[0];
+        let key = cache_key(file, "openai/gpt-4o", None);
+        assert_eq!(key.len(), 64);
+
+        // Rebased: new blob hashes and hunk context, same content
+        let rebased = FileDiff {
+            path: file.path.clone(),
+            text: file
+                .text
+                .replace("1111111..2222222", "3333333..4444444")
+                .replace("@@ fn main() {", "@@ fn start() {"),
+        };
+        assert_eq!(cache_key(&rebased, "openai/gpt-4o", None), key);
+
+        let edited = FileDiff {
+            path: file.path.clone(),
+            text: file.text.replace("let b = 2;", "let b = 3;"),
+        };
+        assert_ne!(cache_key(&edited, "openai/gpt-4o", None), key);
+        assert_ne!(cache_key(file, "anthropic/claude-sonnet-4-5", None), key);
+
+        let rejection = |file: &str| Rejections {
+            items: vec![RejectedFinding {
+                file: file.to_string(),
+                line_start: 2,
+                line_end: 2,
+                finding_type: "style".to_string(),
+                description: "Unused variable".to_string(),
+                reason: "Used later".to_string(),
+            }],
+        };
+        assert_eq!(
+            cache_key(file, "openai/gpt-4o", Some(&rejection("src/other.rs"))),
+            key
+        );
+        assert_ne!(
+            cache_key(file, "openai/gpt-4o", Some(&rejection("src/a.rs"))),
+            key
+        );
+    }
+}
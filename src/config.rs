@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -5,6 +6,11 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
+use crate::models::{LlmInteraction, Severity, Suggestion, SuggestionType};
+
+/// Where a repository keeps its configuration, relative to its root
+pub const CONFIG_PATH: &str = ".ai-review/config.yml";
+
 /// Main configuration structure
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
@@ -15,6 +21,11 @@ pub struct Config {
     pub staleness: StalenessConfig,
     pub notifications: NotificationsConfig,
     pub models: ModelsConfig,
+    pub prompts: PromptsConfig,
+    /// Suggestion categories beyond the built-in ones
+    pub categories: Vec<CategoryConfig>,
+    pub owners: OwnersConfig,
+    pub decision_policy: DecisionPolicy,
 }
 
 /// Review file filtering configuration
@@ -23,6 +34,10 @@ pub struct Config {
 pub struct ReviewConfig {
     pub include_patterns: Vec<String>,
     pub exclude_patterns: Vec<String>,
+    /// Lowest severity reported per suggestion type, e.g. `documentation: high`
+    pub min_severity: BTreeMap<String, String>,
+    /// Findings below this confidence are left out of the PR comment but kept in the ledger
+    pub min_confidence: MinConfidence,
 }
 
 impl Default for ReviewConfig {
@@ -46,10 +61,136 @@ impl Default for ReviewConfig {
                 "**/vendor/**".to_string(),
                 "**/target/**".to_string(),
             ],
+            min_severity: BTreeMap::new(),
+            min_confidence: MinConfidence::default(),
+        }
+    }
+}
+
+/// Minimum confidence for a finding to be shown, either one value or per severity
+///
+/// Per-severity maps may use `default` for the severities they don't list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MinConfidence {
+    All(f64),
+    PerSeverity(BTreeMap<String, f64>),
+}
+
+impl Default for MinConfidence {
+    fn default() -> Self {
+        Self::All(0.0)
+    }
+}
+
+impl MinConfidence {
+    /// Threshold for findings of `severity`
+    pub fn for_severity(&self, severity: Severity) -> f64 {
+        match self {
+            Self::All(min) => *min,
+            Self::PerSeverity(map) => map
+                .iter()
+                .find(|(s, _)| s.parse::<Severity>().is_ok_and(|s| s == severity))
+                .or_else(|| map.get_key_value("default"))
+                .map_or(0.0, |(_, min)| *min),
+        }
+    }
+}
+
+/// Review prompt customization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptsConfig {
+    /// Replaces the built-in review guidance; the response format is always kept
+    pub system: Option<String>,
+    /// House rules added to every review
+    pub instructions: Option<HouseRules>,
+    /// Markdown file with more house rules, used if it exists
+    pub instructions_file: String,
+    /// Instructions added only when the diff touches matching files
+    pub paths: Vec<PathInstructions>,
+}
+
+impl Default for PromptsConfig {
+    fn default() -> Self {
+        Self {
+            system: None,
+            instructions: None,
+            instructions_file: ".ai-review/instructions.md".to_string(),
+            paths: Vec::new(),
         }
     }
 }
 
+/// House rules, either one block of text or a list of rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HouseRules {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl HouseRules {
+    /// The rules as one block, a list becoming one `- ` line per rule
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::List(rules) => rules
+                .iter()
+                .map(|rule| format!("- {}", rule.trim()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// A custom suggestion category; the description tells the model when to use it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryConfig {
+    pub name: String,
+    pub description: String,
+}
+
+/// Instructions scoped to files matching any of `paths`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathInstructions {
+    pub paths: Vec<String>,
+    pub instructions: String,
+}
+
+/// Finding ownership and who may decide on findings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OwnersConfig {
+    /// CODEOWNERS file (defaults to `CODEOWNERS`, `.github/CODEOWNERS` or `docs/CODEOWNERS`)
+    pub codeowners: Option<String>,
+    /// Owners for matching paths, taking precedence over CODEOWNERS
+    pub overrides: Vec<OwnerOverride>,
+    /// Users allowed to decide on any finding
+    pub admins: Vec<String>,
+    /// Members of the teams named as owners, e.g. `"@org/backend": [alice, bob]`
+    pub teams: BTreeMap<String, Vec<String>>,
+}
+
+/// Who may decide on findings, on top of finding ownership
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecisionPolicy {
+    /// Users or teams allowed to decide on findings of a severity; unlisted severities are open
+    pub deciders: BTreeMap<String, Vec<String>>,
+    /// Severities whose findings can only be rejected with a reason
+    pub reject_reason_required: Vec<String>,
+    /// Only accept decisions made in CI, where the GitHub username is verified
+    pub require_verified: bool,
+}
+
+/// Owners for files matching any of `paths` (CODEOWNERS patterns)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerOverride {
+    pub paths: Vec<String>,
+    pub owners: Vec<String>,
+}
+
 /// Severity threshold configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -119,6 +260,8 @@ pub struct SlackConfig {
     pub channel: Option<String>,
     pub on_critical: bool,
     pub on_new_review: bool,
+    /// Slack member IDs of finding owners, so notifications can mention them
+    pub mentions: BTreeMap<String, String>,
 }
 
 impl Default for SlackConfig {
@@ -129,6 +272,7 @@ impl Default for SlackConfig {
             channel: None,
             on_critical: true,
             on_new_review: false,
+            mentions: BTreeMap::new(),
         }
     }
 }
@@ -137,7 +281,145 @@ impl Default for SlackConfig {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ModelsConfig {
+    /// LLM provider used for reviews
+    pub provider: Provider,
     pub codex: CodexModelConfig,
+    /// Several reviewers run together; replaces `provider` and `codex` when non-empty
+    pub ensemble: EnsembleConfig,
+    /// Timeouts and retries for LLM API calls
+    pub retry: RetryConfig,
+    /// Prices per model, added to or overriding the built-in table
+    pub pricing: BTreeMap<String, ModelPrice>,
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPrice {
+    const fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    pub fn cost(&self, prompt_tokens: u32, completion_tokens: u32) -> f64 {
+        (prompt_tokens as f64 * self.input_per_million
+            + completion_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// List prices used when `models.pricing` has no entry for a model
+const DEFAULT_PRICES: &[(&str, ModelPrice)] = &[
+    ("gpt-4o", ModelPrice::new(2.50, 10.00)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.60)),
+    ("gpt-4.1", ModelPrice::new(2.00, 8.00)),
+    ("gpt-4.1-mini", ModelPrice::new(0.40, 1.60)),
+    ("claude-sonnet-4-5", ModelPrice::new(3.00, 15.00)),
+    ("claude-haiku-4-5", ModelPrice::new(1.00, 5.00)),
+    ("claude-opus-4-1", ModelPrice::new(15.00, 75.00)),
+];
+
+impl ModelsConfig {
+    /// Price for a model, from `models.pricing` or the built-in table
+    pub fn price(&self, model: &str) -> Option<ModelPrice> {
+        self.pricing.get(model).copied().or_else(|| {
+            DEFAULT_PRICES
+                .iter()
+                .find(|(name, _)| *name == model)
+                .map(|(_, price)| *price)
+        })
+    }
+
+    /// Estimated cost of an interaction, if the model is priced and usage was reported
+    pub fn estimate_cost(&self, interaction: &LlmInteraction) -> Option<f64> {
+        let price = self.price(&interaction.model)?;
+        Some(price.cost(interaction.prompt_tokens?, interaction.completion_tokens?))
+    }
+}
+
+/// Timeout and retry policy for LLM API calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Per-attempt request timeout
+    pub timeout_secs: u64,
+    /// Retries after the first attempt on rate limits, 5xx errors and timeouts
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on each further retry
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the backoff between retries
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 120,
+            max_retries: 3,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+/// Multi-reviewer ensemble configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnsembleConfig {
+    /// Reviewers that must report a finding for it to be kept (defaults to a majority)
+    pub min_agreement: Option<usize>,
+    /// Minimum description similarity (0.0-1.0) for findings to be merged
+    pub similarity: f64,
+    pub reviewers: Vec<EnsembleMember>,
+}
+
+impl Default for EnsembleConfig {
+    fn default() -> Self {
+        Self {
+            min_agreement: None,
+            similarity: 0.3,
+            reviewers: Vec::new(),
+        }
+    }
+}
+
+/// One reviewer in an ensemble, configured like `models.codex`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleMember {
+    /// Name shown in agreement reports (defaults to the model name)
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub provider: Provider,
+    #[serde(flatten)]
+    pub settings: CodexModelConfig,
+}
+
+impl EnsembleMember {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.settings.model)
+    }
+}
+
+/// Supported LLM providers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Provider {
+    #[default]
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    #[serde(rename = "azure")]
+    Azure,
+    /// Any server speaking the OpenAI chat completions API (vLLM, Ollama, LM Studio)
+    #[serde(rename = "openai_compatible")]
+    OpenAiCompatible,
 }
 
 /// Codex (OpenAI) model configuration
@@ -146,6 +428,16 @@ pub struct ModelsConfig {
 pub struct CodexModelConfig {
     pub model: String,
     pub temperature: f32,
+    /// Extra instructions appended to the system prompt
+    pub instructions: Vec<String>,
+    /// API base URL; required for `azure` and `openai_compatible`
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key (defaults per provider)
+    pub api_key_env: Option<String>,
+    /// Azure OpenAI API version
+    pub api_version: Option<String>,
+    /// Use `json_schema` structured outputs on OpenAI-style APIs (plain JSON mode when off)
+    pub structured_output: bool,
 }
 
 impl Default for CodexModelConfig {
@@ -153,6 +445,11 @@ impl Default for CodexModelConfig {
         Self {
             model: "gpt-4o".to_string(),
             temperature: 0.1,
+            instructions: Vec::new(),
+            base_url: None,
+            api_key_env: None,
+            api_version: None,
+            structured_output: true,
         }
     }
 }
@@ -170,7 +467,7 @@ impl Config {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
-        let config: Config = serde_yaml::from_str(&content)
+        let config = Self::parse(&content)
             .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
 
         info!(path = %path.display(), "Loaded configuration");
@@ -180,7 +477,34 @@ impl Config {
 
     /// Load configuration from the default location (.ai-review/config.yml)
     pub fn load_default() -> Result<Self> {
-        Self::load(".ai-review/config.yml")
+        Self::load(CONFIG_PATH)
+    }
+
+    /// Parse configuration from YAML content
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: Self = serde_yaml::from_str(content).context("Invalid configuration")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reject settings serde accepts but that would silently do nothing
+    fn validate(&self) -> Result<()> {
+        for (t, min) in &self.review.min_severity {
+            let known = match t.parse::<SuggestionType>() {
+                Ok(SuggestionType::Custom(name)) => self.categories.iter().any(|c| c.name == name),
+                Ok(_) => true,
+                Err(_) => false,
+            };
+            if !known {
+                anyhow::bail!(
+                    "review.min_severity: unknown suggestion type '{}'; use a built-in type or one from categories",
+                    t
+                );
+            }
+            min.parse::<Severity>()
+                .with_context(|| format!("review.min_severity: invalid severity for '{}'", t))?;
+        }
+        Ok(())
     }
 
     /// Check if a file path should be included in review
@@ -207,6 +531,29 @@ impl Config {
         false
     }
 
+    /// Check if a suggestion meets the minimum severity configured for its type
+    ///
+    /// Keys and severities are checked when the config is parsed.
+    pub fn meets_min_severity(&self, suggestion: &Suggestion) -> bool {
+        self.review.min_severity.iter().all(|(t, min)| {
+            match (t.parse::<SuggestionType>(), min.parse::<Severity>()) {
+                (Ok(t), Ok(min)) if t == suggestion.suggestion_type => {
+                    suggestion.severity.rank() >= min.rank()
+                }
+                _ => true,
+            }
+        })
+    }
+
+    /// Check if a suggestion should be shown given the configured minimum confidence
+    ///
+    /// Findings without a confidence are always shown.
+    pub fn meets_min_confidence(&self, suggestion: &Suggestion) -> bool {
+        suggestion.confidence.is_none_or(|confidence| {
+            confidence >= self.review.min_confidence.for_severity(suggestion.severity)
+        })
+    }
+
     /// Check if a severity level is blocking
     pub fn is_blocking_severity(&self, severity: &str) -> bool {
         self.severity_thresholds
@@ -227,6 +574,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::SuggestionItem;
 
     #[test]
     fn test_default_config() {
@@ -287,5 +635,170 @@ staleness:
         assert_eq!(config.review.include_patterns.len(), 1);
         assert_eq!(config.severity_thresholds.blocking.len(), 2);
         assert_eq!(config.staleness.warn_after_days, 5);
+        assert_eq!(config.models.provider, Provider::OpenAi);
+    }
+
+    #[test]
+    fn test_min_confidence() {
+        let mut suggestion = SuggestionItem::test("S001", "src/main.rs")
+            .with_severity(Severity::Low)
+            .suggestion;
+        suggestion.confidence = Some(0.6);
+
+        let config = Config::default();
+        assert!(config.meets_min_confidence(&suggestion));
+
+        let config: Config = serde_yaml::from_str("review:\n  min_confidence: 0.7\n").unwrap();
+        assert!(!config.meets_min_confidence(&suggestion));
+
+        let config: Config = serde_yaml::from_str(
+            r#"
+review:
+  min_confidence:
+    low: 0.8
+    default: 0.5
+"#,
+        )
+        .unwrap();
+        assert!(!config.meets_min_confidence(&suggestion));
+        suggestion.severity = Severity::High;
+        assert!(config.meets_min_confidence(&suggestion));
+        suggestion.confidence = Some(0.4);
+        assert!(!config.meets_min_confidence(&suggestion));
+
+        // Unscored findings are never hidden
+        suggestion.confidence = None;
+        assert!(config.meets_min_confidence(&suggestion));
+    }
+
+    #[test]
+    fn test_house_rules() {
+        let config = Config::parse("prompts:\n  instructions: |\n    Use anyhow.\n").unwrap();
+        assert_eq!(
+            config.prompts.instructions.unwrap().to_text(),
+            "Use anyhow.\n"
+        );
+
+        let config = Config::parse(
+            r#"
+prompts:
+  instructions:
+    - "Use anyhow."
+    - "Don't flag unwrap in tests."
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.prompts.instructions.unwrap().to_text(),
+            "- Use anyhow.\n- Don't flag unwrap in tests."
+        );
+    }
+
+    #[test]
+    fn test_min_severity_keys() {
+        let config = Config::parse(
+            r#"
+review:
+  min_severity:
+    documentation: high
+    concurrency: medium
+categories:
+  - name: concurrency
+    description: Data races
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.review.min_severity.len(), 2);
+
+        let err = Config::parse("review:\n  min_severity:\n    docs: high\n").unwrap_err();
+        assert!(format!("{:#}", err).contains("unknown suggestion type 'docs'"));
+
+        let err = Config::parse("review:\n  min_severity:\n    Logic!: high\n").unwrap_err();
+        assert!(format!("{:#}", err).contains("unknown suggestion type"));
+
+        let err = Config::parse("review:\n  min_severity:\n    style: severe\n").unwrap_err();
+        assert!(format!("{:#}", err).contains("invalid severity for 'style'"));
+    }
+
+    #[test]
+    fn test_parse_provider() {
+        let yaml = r#"
+models:
+  provider: openai_compatible
+  codex:
+    model: llama3
+    base_url: http://localhost:11434/v1
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.models.provider, Provider::OpenAiCompatible);
+        assert_eq!(config.models.codex.model, "llama3");
+        assert_eq!(config.models.codex.temperature, 0.1);
+        assert_eq!(
+            config.models.codex.base_url.as_deref(),
+            Some("http://localhost:11434/v1")
+        );
+    }
+
+    #[test]
+    fn test_parse_ensemble() {
+        let yaml = r#"
+models:
+  ensemble:
+    min_agreement: 2
+    reviewers:
+      - name: gpt
+        model: gpt-4o
+      - provider: anthropic
+        model: claude-sonnet-4-5
+        instructions:
+          - "Focus on security"
+      - provider: openai_compatible
+        model: llama3
+        base_url: http://localhost:11434/v1
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let ensemble = &config.models.ensemble;
+        assert_eq!(ensemble.min_agreement, Some(2));
+        assert_eq!(ensemble.similarity, 0.3);
+        assert_eq!(ensemble.reviewers.len(), 3);
+
+        assert_eq!(ensemble.reviewers[0].name(), "gpt");
+        assert_eq!(ensemble.reviewers[0].provider, Provider::OpenAi);
+        assert_eq!(ensemble.reviewers[1].name(), "claude-sonnet-4-5");
+        assert_eq!(ensemble.reviewers[1].provider, Provider::Anthropic);
+        assert_eq!(ensemble.reviewers[1].settings.instructions.len(), 1);
+        assert_eq!(ensemble.reviewers[1].settings.temperature, 0.1);
+        assert_eq!(
+            ensemble.reviewers[2].settings.base_url.as_deref(),
+            Some("http://localhost:11434/v1")
+        );
+    }
+
+    #[test]
+    fn test_pricing() {
+        let yaml = r#"
+models:
+  pricing:
+    gpt-4o:
+      input_per_million: 2.0
+      output_per_million: 8.0
+    llama3:
+      input_per_million: 0.0
+      output_per_million: 0.0
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let models = &config.models;
+        assert_eq!(models.price("gpt-4o"), Some(ModelPrice::new(2.0, 8.0)));
+        assert_eq!(
+            models.price("claude-sonnet-4-5"),
+            Some(ModelPrice::new(3.0, 15.0))
+        );
+        assert_eq!(models.price("unknown-model"), None);
+
+        let cost = ModelPrice::new(3.0, 15.0).cost(1_000_000, 100_000);
+        assert!((cost - 4.5).abs() < 1e-9);
     }
 }
@@ -0,0 +1,741 @@
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use serde::Serialize;
+
+use crate::models::{description_similarity, HumanDecision, Review, Severity, SuggestionType};
+use crate::stats::CountedFindings;
+
+/// Minimum description similarity for two rejected findings to share a cluster
+const CLUSTER_SIMILARITY: f64 = 0.5;
+
+/// Smallest cluster of rejected findings worth reporting
+const MIN_CLUSTER_SIZE: u64 = 3;
+
+/// How many rejection reasons to keep per category
+const TOP_REASONS: usize = 3;
+
+const SEVERITIES: [Severity; 4] = [
+    Severity::Low,
+    Severity::Medium,
+    Severity::High,
+    Severity::Critical,
+];
+
+/// A group of suggestions whose precision is measured together
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Category {
+    Type {
+        suggestion_type: SuggestionType,
+    },
+    TypeSeverity {
+        suggestion_type: SuggestionType,
+        severity: Severity,
+    },
+    FilePattern {
+        pattern: String,
+    },
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::Type { suggestion_type } => {
+                write!(f, "{} findings", suggestion_type.as_str())
+            }
+            Category::TypeSeverity {
+                suggestion_type,
+                severity,
+            } => write!(
+                f,
+                "{} {} findings",
+                severity_name(*severity),
+                suggestion_type.as_str()
+            ),
+            Category::FilePattern { pattern } => write!(f, "findings in {}", pattern),
+        }
+    }
+}
+
+/// Accept/reject counts for a category
+///
+/// Deferred suggestions say nothing about precision and are not counted.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryRate {
+    pub category: Category,
+    pub accepted: u64,
+    pub rejected: u64,
+    pub rejection_rate: f64,
+    pub top_reasons: Vec<String>,
+}
+
+/// Rejected findings with near-identical descriptions
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectionCluster {
+    pub suggestion_type: SuggestionType,
+    /// Description of the first finding in the cluster
+    pub description: String,
+    pub rejected: u64,
+    pub top_reasons: Vec<String>,
+}
+
+/// Rejection analysis over a set of reviews
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedbackReport {
+    pub min_decisions: u64,
+    pub threshold: f64,
+    /// Categories with at least `min_decisions` decisions, highest rejection rate first
+    pub categories: Vec<CategoryRate>,
+    pub clusters: Vec<RejectionCluster>,
+}
+
+#[derive(Default)]
+struct Tally<'a> {
+    accepted: u64,
+    rejected: u64,
+    reasons: Vec<&'a str>,
+}
+
+struct ClusterTally<'a> {
+    suggestion_type: SuggestionType,
+    description: &'a str,
+    reasons: Vec<&'a str>,
+    rejected: u64,
+}
+
+impl FeedbackReport {
+    /// Analyse accepted and rejected suggestions
+    ///
+    /// Categories need at least `min_decisions` accept/reject decisions to be reported;
+    /// those rejected at least `threshold` of the time are flagged. A finding carried
+    /// across pushes counts once, as in `stats`.
+    pub fn compute(reviews: &[Review], min_decisions: u64, threshold: f64) -> Self {
+        let mut tallies: HashMap<Category, Tally> = HashMap::new();
+        let mut clusters: Vec<ClusterTally> = Vec::new();
+        let counted = CountedFindings::new(reviews);
+
+        for item in reviews.iter().flat_map(|r| counted.findings(r)) {
+            let Some(record) = &item.decision else {
+                continue;
+            };
+            let s = &item.suggestion;
+            let rejected = match record.decision {
+                HumanDecision::Accepted => false,
+                HumanDecision::Rejected => true,
+                HumanDecision::Deferred => continue,
+            };
+            let reason = record
+                .reason
+                .as_deref()
+                .map(str::trim)
+                .filter(|r| !r.is_empty());
+
+            let mut categories = vec![
+                Category::Type {
+                    suggestion_type: s.suggestion_type.clone(),
+                },
+                Category::TypeSeverity {
+                    suggestion_type: s.suggestion_type.clone(),
+                    severity: s.severity,
+                },
+            ];
+            categories.extend(
+                file_patterns(&s.location.file).map(|pattern| Category::FilePattern { pattern }),
+            );
+
+            for category in categories {
+                let tally = tallies.entry(category).or_default();
+                if rejected {
+                    tally.rejected += 1;
+                    tally.reasons.extend(reason);
+                } else {
+                    tally.accepted += 1;
+                }
+            }
+
+            if !rejected {
+                continue;
+            }
+
+            match clusters.iter_mut().find(|c| {
+                c.suggestion_type == s.suggestion_type
+                    && description_similarity(c.description, &s.description) >= CLUSTER_SIMILARITY
+            }) {
+                Some(cluster) => {
+                    cluster.rejected += 1;
+                    cluster.reasons.extend(reason);
+                }
+                None => clusters.push(ClusterTally {
+                    suggestion_type: s.suggestion_type.clone(),
+                    description: &s.description,
+                    reasons: reason.into_iter().collect(),
+                    rejected: 1,
+                }),
+            }
+        }
+
+        let mut categories: Vec<CategoryRate> = tallies
+            .into_iter()
+            .filter(|(_, t)| t.accepted + t.rejected >= min_decisions.max(1))
+            .map(|(category, t)| CategoryRate {
+                category,
+                accepted: t.accepted,
+                rejected: t.rejected,
+                rejection_rate: t.rejected as f64 / (t.accepted + t.rejected) as f64,
+                top_reasons: top_reasons(t.reasons),
+            })
+            .collect();
+        categories.sort_by(|a, b| {
+            b.rejection_rate
+                .total_cmp(&a.rejection_rate)
+                .then_with(|| b.rejected.cmp(&a.rejected))
+                .then_with(|| a.category.to_string().cmp(&b.category.to_string()))
+        });
+
+        let mut clusters: Vec<RejectionCluster> = clusters
+            .into_iter()
+            .filter(|c| c.rejected >= MIN_CLUSTER_SIZE)
+            .map(|c| RejectionCluster {
+                suggestion_type: c.suggestion_type,
+                description: c.description.to_string(),
+                rejected: c.rejected,
+                top_reasons: top_reasons(c.reasons),
+            })
+            .collect();
+        clusters.sort_by_key(|c| std::cmp::Reverse(c.rejected));
+
+        Self {
+            min_decisions,
+            threshold,
+            categories,
+            clusters,
+        }
+    }
+
+    /// Categories rejected at least `threshold` of the time
+    pub fn flagged(&self) -> impl Iterator<Item = &CategoryRate> {
+        self.categories
+            .iter()
+            .filter(|c| c.rejection_rate >= self.threshold)
+    }
+
+    /// Render the report as plain text
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+
+        writeln!(
+            out,
+            "Rejection rates (categories with at least {} decisions):\n",
+            self.min_decisions
+        )
+        .unwrap();
+        if self.categories.is_empty() {
+            writeln!(out, "  Not enough decisions yet.").unwrap();
+        }
+        for c in &self.categories {
+            let marker = if c.rejection_rate >= self.threshold {
+                "!"
+            } else {
+                " "
+            };
+            writeln!(
+                out,
+                "{} {:>4.0}%  {:>4} of {:<4}  {}",
+                marker,
+                c.rejection_rate * 100.0,
+                c.rejected,
+                c.accepted + c.rejected,
+                c.category
+            )
+            .unwrap();
+            if c.rejection_rate >= self.threshold {
+                for reason in &c.top_reasons {
+                    writeln!(out, "                        - {}", reason).unwrap();
+                }
+            }
+        }
+
+        if !self.clusters.is_empty() {
+            writeln!(out, "\nRepeatedly rejected findings:\n").unwrap();
+            for c in &self.clusters {
+                writeln!(
+                    out,
+                    "  {:>4}x  [{}] {}",
+                    c.rejected,
+                    c.suggestion_type.as_str(),
+                    c.description
+                )
+                .unwrap();
+                for reason in &c.top_reasons {
+                    writeln!(out, "          - {}", reason).unwrap();
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Suggested `.ai-review/config.yml` changes, or `None` if nothing is flagged
+    ///
+    /// The patch is meant to be reviewed by hand, so every entry carries a comment
+    /// with the numbers behind it.
+    pub fn config_patch(&self) -> Option<String> {
+        let flagged: Vec<&CategoryRate> = self.flagged().collect();
+
+        let mut min_severity = Vec::new();
+        let mut instructions = Vec::new();
+        let mut rules = Vec::new();
+        // Dismissing everything in a language is never what the numbers mean, so these
+        // are only shown for a human to narrow down
+        let mut broad_rules = Vec::new();
+
+        for rate in &flagged {
+            match &rate.category {
+                Category::Type { suggestion_type } => {
+                    instructions.push((
+                        describe(rate),
+                        format!(
+                            "Only report {} issues that clearly matter; reviewers rejected most of them in this repository.",
+                            suggestion_type.as_str()
+                        ),
+                    ));
+
+                    // Raise the bar just above the highest non-critical severity that is
+                    // rejected as often as the type as a whole
+                    let highest = SEVERITIES
+                        .iter()
+                        .filter(|s| **s != Severity::Critical)
+                        .rfind(|s| {
+                            flagged.iter().any(|r| {
+                                r.category
+                                    == Category::TypeSeverity {
+                                        suggestion_type: suggestion_type.clone(),
+                                        severity: **s,
+                                    }
+                            })
+                        });
+                    if let Some(highest) = highest {
+                        let min = SEVERITIES[highest.rank() as usize + 1];
+                        min_severity.push((
+                            format!(
+                                "{} below {} are mostly rejected",
+                                rate.category,
+                                severity_name(min)
+                            ),
+                            suggestion_type.as_str(),
+                            severity_name(min),
+                        ));
+                    }
+                }
+                Category::FilePattern { pattern } => {
+                    let rule = (
+                        describe(rate),
+                        format!("file_path matches '{}'", pattern),
+                        format!("Findings in {} are usually rejected", pattern),
+                    );
+                    if is_extension_pattern(pattern) {
+                        broad_rules.push(rule);
+                    } else {
+                        rules.push(rule);
+                    }
+                }
+                Category::TypeSeverity { .. } => {}
+            }
+        }
+
+        for cluster in &self.clusters {
+            let mut comment = format!(
+                "rejected {} times: {}",
+                cluster.rejected,
+                cluster.description.replace('\n', " ")
+            );
+            if let Some(reason) = cluster.top_reasons.first() {
+                comment.push_str(&format!(" ({})", reason));
+            }
+            instructions.push((
+                comment,
+                format!(
+                    "Do not report {} findings like: {}",
+                    cluster.suggestion_type.as_str(),
+                    cluster.description
+                ),
+            ));
+        }
+
+        if min_severity.is_empty()
+            && instructions.is_empty()
+            && rules.is_empty()
+            && broad_rules.is_empty()
+        {
+            return None;
+        }
+
+        let mut out = String::new();
+        writeln!(out, "# Suggested by `ai-review feedback`. Review before merging into .ai-review/config.yml.").unwrap();
+
+        if !min_severity.is_empty() {
+            writeln!(out, "review:\n  min_severity:").unwrap();
+            for (comment, t, min) in &min_severity {
+                writeln!(out, "    # {}\n    {}: {}", comment, t, min).unwrap();
+            }
+        }
+
+        if !instructions.is_empty() {
+            writeln!(out, "prompts:\n  instructions:").unwrap();
+            for (comment, instruction) in &instructions {
+                writeln!(out, "    # {}\n    - {}", comment, yaml_string(instruction)).unwrap();
+            }
+        }
+
+        if !rules.is_empty() {
+            writeln!(out, "auto_rules:").unwrap();
+            for (comment, condition, reason) in &rules {
+                writeln!(
+                    out,
+                    "  # {}\n  - condition: {}\n    action: auto_dismiss\n    reason: {}",
+                    comment,
+                    yaml_string(condition),
+                    yaml_string(reason)
+                )
+                .unwrap();
+            }
+        }
+
+        if !broad_rules.is_empty() {
+            writeln!(
+                out,
+                "# Not applied: these would dismiss every finding in a language. Narrow the\n# pattern to the directories that are rejected before using one."
+            )
+            .unwrap();
+            for (comment, condition, reason) in &broad_rules {
+                writeln!(
+                    out,
+                    "#   # {}\n#   - condition: {}\n#     action: auto_dismiss\n#     reason: {}",
+                    comment,
+                    yaml_string(condition),
+                    yaml_string(reason)
+                )
+                .unwrap();
+            }
+        }
+
+        Some(out)
+    }
+}
+
+/// Whether `pattern` is one of the extension globs from [`file_patterns`]
+fn is_extension_pattern(pattern: &str) -> bool {
+    pattern.starts_with("**/*.")
+}
+
+/// Directory and extension globs a file belongs to
+fn file_patterns(file: &str) -> impl Iterator<Item = String> {
+    let path = std::path::Path::new(file);
+
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(|p| format!("{}/**", p.display()));
+    let ext = path
+        .extension()
+        .map(|e| format!("**/*.{}", e.to_string_lossy()));
+
+    dir.into_iter().chain(ext)
+}
+
+fn top_reasons(reasons: Vec<&str>) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for reason in reasons {
+        *counts.entry(reason).or_default() += 1;
+    }
+
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+        .into_iter()
+        .take(TOP_REASONS)
+        .map(|(reason, _)| reason.to_string())
+        .collect()
+}
+
+fn describe(rate: &CategoryRate) -> String {
+    format!(
+        "{} rejected {:.0}% of the time ({} of {})",
+        rate.category,
+        rate.rejection_rate * 100.0,
+        rate.rejected,
+        rate.accepted + rate.rejected
+    )
+}
+
+/// Double-quoted YAML scalar (JSON strings are valid YAML)
+fn yaml_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+fn severity_name(s: Severity) -> &'static str {
+    match s {
+        Severity::Critical => "critical",
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, HouseRules};
+    use crate::models::{DecisionRecord, ReviewContext, ReviewStatus, SuggestionItem};
+
+    fn review_with(
+        findings: &[(SuggestionType, Severity, &str, &str, HumanDecision, &str)],
+    ) -> Review {
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+
+        for (i, (t, severity, file, description, decision, reason)) in findings.iter().enumerate() {
+            let id = format!("S{:03}", i + 1);
+            let item = SuggestionItem::test(&id, file)
+                .with_type(t.clone())
+                .with_severity(*severity)
+                .with_description(*description);
+            review.suggestions.push(SuggestionItem {
+                decision: Some(DecisionRecord {
+                    suggestion_id: id,
+                    decision: *decision,
+                    reason: Some(reason.to_string()),
+                    decided_by: "alice".to_string(),
+                    decided_at: chrono::Utc::now(),
+                    deferred_until: None,
+                    verified: false,
+                }),
+                ..item
+            });
+        }
+
+        review
+    }
+
+    fn sample() -> Vec<Review> {
+        use HumanDecision::*;
+        use Severity::*;
+        use SuggestionType::*;
+
+        vec![review_with(&[
+            (
+                Documentation,
+                Low,
+                "src/api.rs",
+                "Missing doc comment on public function handle",
+                Rejected,
+                "Obvious from the name",
+            ),
+            (
+                Documentation,
+                Low,
+                "src/db.rs",
+                "Missing doc comment on public function connect",
+                Rejected,
+                "Obvious from the name",
+            ),
+            (
+                Documentation,
+                Low,
+                "src/lib.rs",
+                "Missing doc comment on public function run",
+                Rejected,
+                "Noise",
+            ),
+            (
+                Documentation,
+                Medium,
+                "src/api.rs",
+                "Module lacks overview documentation",
+                Rejected,
+                "Noise",
+            ),
+            (
+                Documentation,
+                High,
+                "src/api.rs",
+                "Safety section missing on unsafe fn",
+                Accepted,
+                "",
+            ),
+            (
+                Logic,
+                High,
+                "src/api.rs",
+                "Off by one in pagination",
+                Accepted,
+                "",
+            ),
+            (
+                Logic,
+                Medium,
+                "src/db.rs",
+                "Unchecked unwrap on query result",
+                Accepted,
+                "",
+            ),
+            (
+                Logic,
+                High,
+                "src/lib.rs",
+                "Race between check and insert",
+                Rejected,
+                "Guarded by a lock",
+            ),
+            (
+                Logic,
+                Medium,
+                "src/db.rs",
+                "Transaction not rolled back",
+                Accepted,
+                "",
+            ),
+        ])]
+    }
+
+    #[test]
+    fn test_rejection_rates() {
+        let report = FeedbackReport::compute(&sample(), 3, 0.7);
+
+        let docs = report
+            .categories
+            .iter()
+            .find(|c| {
+                c.category
+                    == Category::Type {
+                        suggestion_type: SuggestionType::Documentation,
+                    }
+            })
+            .unwrap();
+        assert_eq!(docs.rejected, 4);
+        assert_eq!(docs.accepted, 1);
+        assert_eq!(docs.rejection_rate, 0.8);
+        assert_eq!(docs.top_reasons[0], "Noise");
+
+        let flagged: Vec<String> = report.flagged().map(|c| c.category.to_string()).collect();
+        assert!(flagged.contains(&"documentation findings".to_string()));
+        assert!(flagged.contains(&"low documentation findings".to_string()));
+        assert!(!flagged.contains(&"logic findings".to_string()));
+
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].rejected, 3);
+        assert_eq!(report.clusters[0].top_reasons[0], "Obvious from the name");
+    }
+
+    #[test]
+    fn test_pr_pushed_three_times() {
+        let rejected = [(
+            SuggestionType::Style,
+            Severity::Low,
+            "src/api.rs",
+            "Prefer early return in handler",
+            HumanDecision::Rejected,
+            "Matches the surrounding code",
+        )];
+
+        // Each push carries the rejected finding and its decision forward
+        let chain = |statuses: [ReviewStatus; 3]| {
+            let mut reviews: Vec<Review> = Vec::new();
+            for status in statuses {
+                let mut review = review_with(&rejected);
+                review.status = status;
+                review.previous_review_id = reviews.last().map(|r| r.id);
+                reviews.push(review);
+            }
+            reviews
+        };
+
+        use ReviewStatus::*;
+        for statuses in [
+            [Stale, Stale, Pending],
+            [Decided, Decided, Decided],
+            [Decided, Stale, Pending],
+        ] {
+            let report = FeedbackReport::compute(&chain(statuses), 1, 0.7);
+            assert!(report.clusters.is_empty());
+            let style = report
+                .categories
+                .iter()
+                .find(|c| {
+                    c.category
+                        == Category::Type {
+                            suggestion_type: SuggestionType::Style,
+                        }
+                })
+                .unwrap();
+            assert_eq!(style.rejected, 1);
+        }
+    }
+
+    #[test]
+    fn test_config_patch_parses() {
+        let report = FeedbackReport::compute(&sample(), 3, 0.7);
+        let patch = report.config_patch().unwrap();
+
+        let config: Config = serde_yaml::from_str(&patch).unwrap();
+        assert_eq!(
+            config
+                .review
+                .min_severity
+                .get("documentation")
+                .map(String::as_str),
+            Some("medium")
+        );
+        let Some(HouseRules::List(rules)) = &config.prompts.instructions else {
+            panic!("expected a list of house rules");
+        };
+        assert!(rules.len() >= 2);
+        assert!(patch.contains("documentation findings rejected 80% of the time (4 of 5)"));
+    }
+
+    #[test]
+    fn test_only_clusters_below_threshold() {
+        let report = FeedbackReport::compute(&sample(), 3, 0.95);
+        let patch = report.config_patch().unwrap();
+        assert!(!patch.contains("min_severity"));
+        assert!(!patch.contains("auto_rules"));
+        assert!(patch.contains("Do not report documentation findings like"));
+    }
+
+    #[test]
+    fn test_no_patch_without_decisions() {
+        let report = FeedbackReport::compute(&[], 3, 0.7);
+        assert!(report.config_patch().is_none());
+        assert!(report.to_table().contains("Not enough decisions"));
+    }
+
+    #[test]
+    fn test_extension_rules_are_not_applied() {
+        use HumanDecision::*;
+        use Severity::*;
+        use SuggestionType::*;
+
+        let review = review_with(&[
+            (Logic, Low, "api/a.py", "Mutable default", Rejected, ""),
+            (Style, Low, "db/b.py", "Use f-strings", Rejected, ""),
+            (Performance, Low, "cli/c.py", "Loop append", Rejected, ""),
+            (Security, High, "web/d.rs", "Unescaped HTML", Accepted, ""),
+        ]);
+        let report = FeedbackReport::compute(&[review], 3, 0.7);
+        assert!(report.flagged().any(|c| c.category
+            == Category::FilePattern {
+                pattern: "**/*.py".to_string()
+            }));
+
+        let patch = report.config_patch().unwrap();
+        assert!(patch.contains("#   - condition: \"file_path matches '**/*.py'\""));
+        let config: Config = serde_yaml::from_str(&patch).unwrap();
+        assert!(config.auto_rules.is_empty());
+    }
+}
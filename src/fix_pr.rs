@@ -0,0 +1,436 @@
+//! Open a pull request with the accepted fixes of a review
+
+use std::ffi::OsStr;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use tracing::{debug, info};
+
+use crate::apply::{commit_fixes, commit_message, reapply_fixes, target_label, ApplyReport};
+use crate::github::{GitHubClient, PullRequestRef};
+use crate::models::Review;
+
+/// Outcome of `FixPr::open`
+#[derive(Debug)]
+pub struct FixPrOutcome {
+    pub branch: String,
+    pub pr: PullRequestRef,
+    /// Whether this run opened the PR rather than updating it
+    pub created: bool,
+    pub report: ApplyReport,
+}
+
+/// Builds a branch with the accepted fixes of a review and opens a PR for it
+///
+/// The branch is named after the reviewed commit and rebuilt from it on every run,
+/// so rerunning force-pushes the branch and updates the PR that is already open.
+pub struct FixPr<'a> {
+    github: &'a GitHubClient,
+    root: PathBuf,
+    remote: String,
+    base: Option<String>,
+}
+
+impl<'a> FixPr<'a> {
+    pub fn new(github: &'a GitHubClient) -> Self {
+        Self {
+            github,
+            root: PathBuf::from("."),
+            remote: "origin".to_string(),
+            base: None,
+        }
+    }
+
+    /// Local checkout to build the branch from (default: the current directory)
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Git remote to push the branch to (default: `origin`)
+    pub fn with_remote(mut self, remote: impl Into<String>) -> Self {
+        self.remote = remote.into();
+        self
+    }
+
+    /// Branch to open the PR against (default: the reviewed branch, or `main`)
+    pub fn with_base(mut self, base: Option<String>) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Push the fix branch and open or update its PR, marking the fixes applied
+    pub async fn open(&self, review: &mut Review) -> Result<FixPrOutcome> {
+        let (owner, repo) = review
+            .repo
+            .split_once('/')
+            .with_context(|| format!("Invalid repo format: {}", review.repo))?;
+        let branch = fix_branch(review);
+
+        // A separate worktree leaves the local checkout alone
+        let worktree = std::env::temp_dir().join(format!("ai-review-fixes-{}", review.id));
+        self.remove_worktree(&worktree);
+        git(
+            &self.root,
+            [
+                OsStr::new("worktree"),
+                OsStr::new("add"),
+                OsStr::new("--force"),
+                OsStr::new("-B"),
+                OsStr::new(&branch),
+                worktree.as_os_str(),
+                OsStr::new(&review.commit_sha),
+            ],
+        )?;
+        let built = self.build_branch(review, &branch, &worktree);
+        self.remove_worktree(&worktree);
+        let report = built?;
+
+        let base = self
+            .base
+            .clone()
+            .or_else(|| review.branch.clone())
+            .unwrap_or_else(|| "main".to_string());
+        let (pr, created) = self
+            .github
+            .create_or_update_pr(
+                owner,
+                repo,
+                &branch,
+                &base,
+                &fix_pr_title(review),
+                &fix_pr_body(review, &report),
+            )
+            .await?;
+
+        review.mark_applied(&report.applied, Utc::now());
+
+        Ok(FixPrOutcome {
+            branch,
+            pr,
+            created,
+            report,
+        })
+    }
+
+    fn build_branch(&self, review: &Review, branch: &str, worktree: &Path) -> Result<ApplyReport> {
+        let report = reapply_fixes(worktree, review)?;
+        if report.applied.is_empty() {
+            anyhow::bail!("No accepted fixes apply to {}", target_label(review));
+        }
+
+        let sha = commit_fixes(
+            worktree,
+            &report.files,
+            &commit_message(review, &report.applied),
+        )?;
+        git(
+            worktree,
+            [
+                "push",
+                "--force",
+                &self.remote,
+                &format!("HEAD:refs/heads/{}", branch),
+            ],
+        )?;
+        info!(branch, sha, "Pushed fix branch");
+
+        Ok(report)
+    }
+
+    fn remove_worktree(&self, worktree: &Path) {
+        if worktree.exists() {
+            let removed = git(
+                &self.root,
+                [
+                    OsStr::new("worktree"),
+                    OsStr::new("remove"),
+                    OsStr::new("--force"),
+                    worktree.as_os_str(),
+                ],
+            );
+            if let Err(e) = removed {
+                debug!(error = %e, "Failed to remove fix worktree");
+            }
+        }
+        let _ = git(&self.root, ["worktree", "prune"]);
+    }
+}
+
+/// Branch holding the fixes for a review, e.g. `ai-review/fixes-abc1234`
+pub fn fix_branch(review: &Review) -> String {
+    format!(
+        "ai-review/fixes-{}",
+        &review.commit_sha[..7.min(review.commit_sha.len())]
+    )
+}
+
+fn fix_pr_title(review: &Review) -> String {
+    format!("Apply AI review fixes for {}", target_label(review))
+}
+
+/// PR body linking each fix to its suggestion and the reason it was accepted
+pub fn fix_pr_body(review: &Review, report: &ApplyReport) -> String {
+    let mut md = String::new();
+    writeln!(
+        md,
+        "Applies the fixes accepted in the AI review of {} (review `{}`).\n",
+        target_label(review),
+        review.id
+    )
+    .unwrap();
+
+    md.push_str("### Fixes\n\n");
+    for id in &report.applied {
+        let Some(item) = review.suggestions.iter().find(|s| &s.suggestion.id == id) else {
+            continue;
+        };
+        let s = &item.suggestion;
+        writeln!(
+            md,
+            "- **{}** `{}` (lines {}-{}): {}",
+            s.id,
+            s.location.file,
+            s.location.line_start,
+            s.location.line_end,
+            s.description.lines().next().unwrap_or_default()
+        )
+        .unwrap();
+        if let Some(decision) = &item.decision {
+            match &decision.reason {
+                Some(reason) => writeln!(md, "  - Accepted by {}: {}", decision.decided_by, reason),
+                None => writeln!(md, "  - Accepted by {}", decision.decided_by),
+            }
+            .unwrap();
+        }
+    }
+
+    if !report.conflicts.is_empty() || !report.manual.is_empty() {
+        md.push_str("\n### Not included\n\n");
+        for conflict in &report.conflicts {
+            writeln!(
+                md,
+                "- **{}** `{}`: {}",
+                conflict.suggestion_id, conflict.file, conflict.reason
+            )
+            .unwrap();
+        }
+        for id in &report.manual {
+            writeln!(md, "- **{}**: no structured fix, apply by hand", id).unwrap();
+        }
+    }
+
+    md
+}
+
+/// Run git in `dir`, returning its trimmed stdout
+fn git<I, S>(dir: &Path, args: I) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args: Vec<S> = args.into_iter().collect();
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(&args)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        let command: Vec<_> = args.iter().map(|a| a.as_ref().to_string_lossy()).collect();
+        anyhow::bail!(
+            "git {} failed: {}",
+            command.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        DecisionRecord, HumanDecision, Replacement, ReviewContext, ReviewStatus, Severity,
+        SuggestionItem,
+    };
+    use serde_json::json;
+    use std::fs;
+    use tempfile::tempdir;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn pull_request(number: u64) -> serde_json::Value {
+        json!({
+            "url": format!("https://api.github.com/repos/owner/repo/pulls/{}", number),
+            "id": number,
+            "number": number,
+            "html_url": format!("https://github.com/owner/repo/pull/{}", number),
+            "head": { "ref": "ai-review/fixes", "sha": "def456" },
+            "base": { "ref": "main", "sha": "abc123" }
+        })
+    }
+
+    fn accepted(id: &str, replacement: Replacement) -> SuggestionItem {
+        let mut item = SuggestionItem::test(id, "src/lib.rs")
+            .with_severity(Severity::High)
+            .with_lines(replacement.line_start, replacement.line_end)
+            .with_description("Off by one");
+        item.suggestion.replacement = Some(replacement);
+        SuggestionItem {
+            decision: Some(DecisionRecord {
+                suggestion_id: id.to_string(),
+                decision: HumanDecision::Accepted,
+                reason: Some("Confirmed in staging".to_string()),
+                decided_by: "alice".to_string(),
+                decided_at: Utc::now(),
+                deferred_until: None,
+                verified: false,
+            }),
+            ..item
+        }
+    }
+
+    fn run(dir: &Path, args: &[&str]) -> String {
+        git(dir, args).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_open_fix_pr() {
+        let dir = tempdir().unwrap();
+        let remote = dir.path().join("remote.git");
+        let local = dir.path().join("local");
+        fs::create_dir_all(local.join("src")).unwrap();
+        run(
+            dir.path(),
+            &["init", "--bare", "-q", remote.to_str().unwrap()],
+        );
+        run(&local, &["init", "-q", "-b", "main"]);
+        run(&local, &["config", "user.name", "CI"]);
+        run(&local, &["config", "user.email", "ci@example.com"]);
+        run(
+            &local,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        );
+        fs::write(
+            local.join("src/lib.rs"),
+            "fn last(v: &[u8]) -> u8 {\n    v[v.len()]\n}\n",
+        )
+        .unwrap();
+        run(&local, &["add", "."]);
+        run(&local, &["commit", "-q", "-m", "Initial"]);
+        let sha = run(&local, &["rev-parse", "HEAD"]);
+
+        let mut review = Review::new(ReviewContext {
+            pr_number: None,
+            repo: "owner/repo".to_string(),
+            branch: Some("main".to_string()),
+            commit_sha: sha.clone(),
+            base_sha: None,
+        });
+        review.suggestions.push(accepted(
+            "S001",
+            Replacement {
+                line_start: 2,
+                line_end: 2,
+                old_text: "    v[v.len()]".to_string(),
+                new_text: "    v[v.len() - 1]".to_string(),
+            },
+        ));
+        review.status = ReviewStatus::Decided;
+
+        let server = MockServer::start().await;
+        let github = GitHubClient::with_base_url("token", &server.uri()).unwrap();
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls"))
+            .and(body_string_contains(
+                "Accepted by alice: Confirmed in staging",
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(pull_request(9)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let fix_pr = FixPr::new(&github).with_root(&local);
+        let outcome = fix_pr.open(&mut review).await.unwrap();
+        assert!(outcome.created);
+        assert_eq!(outcome.pr.number, 9);
+        assert_eq!(outcome.branch, format!("ai-review/fixes-{}", &sha[..7]));
+        assert_eq!(review.status, ReviewStatus::Applied);
+        assert!(review.suggestions[0].applied_at.is_some());
+
+        let pushed = run(
+            &remote,
+            &["show", &format!("{}:src/lib.rs", outcome.branch)],
+        );
+        assert!(pushed.contains("v[v.len() - 1]"));
+        // The local checkout is untouched
+        let local_file = fs::read_to_string(local.join("src/lib.rs")).unwrap();
+        assert!(local_file.contains("v[v.len()]"));
+
+        // Rerunning rebuilds the branch and updates the open PR
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([pull_request(9)])))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/pulls/9"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pull_request(9)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let outcome = fix_pr.open(&mut review).await.unwrap();
+        assert!(!outcome.created);
+        assert_eq!(outcome.report.applied, vec!["S001"]);
+        let commits = run(&remote, &["rev-list", "--count", &outcome.branch]);
+        assert_eq!(commits, "2");
+    }
+
+    #[test]
+    fn test_fix_pr_body() {
+        let mut review = Review::new(ReviewContext {
+            pr_number: None,
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc1234def".to_string(),
+            base_sha: None,
+        });
+        review.suggestions.push(accepted(
+            "S001",
+            Replacement {
+                line_start: 3,
+                line_end: 4,
+                old_text: "a".to_string(),
+                new_text: "b".to_string(),
+            },
+        ));
+        let report = ApplyReport {
+            applied: vec!["S001".to_string()],
+            manual: vec!["S002".to_string()],
+            ..Default::default()
+        };
+
+        let body = fix_pr_body(&review, &report);
+        assert!(body.starts_with("Applies the fixes accepted in the AI review of commit abc1234"));
+        assert!(body.contains("- **S001** `src/lib.rs` (lines 3-4): Off by one\n"));
+        assert!(body.contains("  - Accepted by alice: Confirmed in staging\n"));
+        assert!(body.contains("- **S002**: no structured fix, apply by hand"));
+        assert_eq!(
+            fix_pr_title(&review),
+            "Apply AI review fixes for commit abc1234"
+        );
+    }
+}
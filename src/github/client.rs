@@ -18,6 +18,18 @@ impl GitHubClient {
         Ok(Self { client })
     }
 
+    /// Create a client for a GitHub Enterprise or test server at `base_url`
+    pub fn with_base_url(token: &str, base_url: &str) -> Result<Self> {
+        let client = Octocrab::builder()
+            .personal_token(token.to_string())
+            .base_uri(base_url)
+            .context("Invalid GitHub base URL")?
+            .build()
+            .context("Failed to create GitHub client")?;
+
+        Ok(Self { client })
+    }
+
     /// Post a comment on a PR
     pub async fn post_comment(
         &self,
@@ -121,6 +133,24 @@ impl GitHubClient {
         }
     }
 
+    /// Get file contents at a specific commit, or `None` if the file doesn't exist there
+    pub async fn find_file_contents(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        commit_sha: &str,
+    ) -> Result<Option<String>> {
+        match self.get_file_contents(owner, repo, path, commit_sha).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if is_not_found(&e) => {
+                debug!(owner, repo, path, "File not found");
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     /// List files changed in a PR
     pub async fn list_pr_files(
         &self,
@@ -150,6 +180,76 @@ impl GitHubClient {
         Ok(result)
     }
 
+    /// Find the open PR from `head_branch` of the same repository, if any
+    pub async fn find_open_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequestRef>> {
+        let page = self
+            .client
+            .pulls(owner, repo)
+            .list()
+            .state(octocrab::params::State::Open)
+            .head(format!("{}:{}", owner, head_branch))
+            .send()
+            .await
+            .context("Failed to list pull requests")?;
+
+        Ok(page.items.into_iter().next().map(PullRequestRef::from))
+    }
+
+    /// Open a PR from `head_branch` into `base_branch`, or update the title and body of
+    /// the one already open
+    ///
+    /// Returns the PR and whether it was newly created.
+    pub async fn create_or_update_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        head_branch: &str,
+        base_branch: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<(PullRequestRef, bool)> {
+        if let Some(existing) = self.find_open_pr(owner, repo, head_branch).await? {
+            info!(
+                owner,
+                repo,
+                number = existing.number,
+                "Updating pull request"
+            );
+
+            let pr = self
+                .client
+                .pulls(owner, repo)
+                .update(existing.number)
+                .title(title)
+                .body(body)
+                .send()
+                .await
+                .context("Failed to update pull request")?;
+
+            return Ok((pr.into(), false));
+        }
+
+        info!(owner, repo, head_branch, "Opening pull request");
+
+        let pr = self
+            .client
+            .pulls(owner, repo)
+            .create(title, head_branch, base_branch)
+            .body(body)
+            .send()
+            .await
+            .context("Failed to create pull request")?;
+
+        debug!(number = pr.number, "Pull request opened");
+
+        Ok((pr.into(), true))
+    }
+
     fn get_token(&self) -> Result<String> {
         // This is a workaround since octocrab doesn't expose the token
         // In a real implementation, we'd store the token separately
@@ -167,8 +267,31 @@ pub struct PrFile {
     pub changes: u64,
 }
 
+/// Number and web URL of a pull request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestRef {
+    pub number: u64,
+    pub url: Option<String>,
+}
+
+impl From<octocrab::models::pulls::PullRequest> for PullRequestRef {
+    fn from(pr: octocrab::models::pulls::PullRequest) -> Self {
+        Self {
+            number: pr.number,
+            url: pr.html_url.map(|url| url.to_string()),
+        }
+    }
+}
+
+/// Whether a GitHub API error is a 404, e.g. for a missing file
+fn is_not_found(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<octocrab::Error>(),
+        Some(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404
+    )
+}
+
 /// Parse owner and repo from a repo string like "owner/repo"
-#[allow(dead_code)]
 pub fn parse_repo(repo: &str) -> Result<(&str, &str)> {
     let parts: Vec<&str> = repo.split('/').collect();
     if parts.len() != 2 {
@@ -180,6 +303,92 @@ pub fn parse_repo(repo: &str) -> Result<(&str, &str)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn pull_request(number: u64) -> serde_json::Value {
+        json!({
+            "url": format!("https://api.github.com/repos/owner/repo/pulls/{}", number),
+            "id": number * 100,
+            "number": number,
+            "html_url": format!("https://github.com/owner/repo/pull/{}", number),
+            "head": { "ref": "ai-review/fixes-abc1234", "sha": "def456" },
+            "base": { "ref": "main", "sha": "abc123" }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_create_or_update_pr() {
+        let server = MockServer::start().await;
+        let client = GitHubClient::with_base_url("token", &server.uri()).unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls"))
+            .and(query_param("head", "owner:ai-review/fixes-abc1234"))
+            .and(query_param("state", "open"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls"))
+            .and(body_partial_json(json!({
+                "head": "ai-review/fixes-abc1234",
+                "base": "main",
+                "title": "Fixes",
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(pull_request(7)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (pr, created) = client
+            .create_or_update_pr(
+                "owner",
+                "repo",
+                "ai-review/fixes-abc1234",
+                "main",
+                "Fixes",
+                "Body",
+            )
+            .await
+            .unwrap();
+        assert!(created);
+        assert_eq!(pr.number, 7);
+        assert_eq!(
+            pr.url.as_deref(),
+            Some("https://github.com/owner/repo/pull/7")
+        );
+
+        // Once open, a rerun updates the same PR
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([pull_request(7)])))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/pulls/7"))
+            .and(body_partial_json(json!({ "body": "New body" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pull_request(7)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (pr, created) = client
+            .create_or_update_pr(
+                "owner",
+                "repo",
+                "ai-review/fixes-abc1234",
+                "main",
+                "Fixes",
+                "New body",
+            )
+            .await
+            .unwrap();
+        assert!(!created);
+        assert_eq!(pr.number, 7);
+    }
 
     #[test]
     fn test_parse_repo() {
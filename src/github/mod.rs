@@ -1,5 +1,5 @@
 mod client;
 mod diff;
 
-pub use client::GitHubClient;
+pub use client::{parse_repo, GitHubClient, PullRequestRef};
 pub use diff::extract_diff;
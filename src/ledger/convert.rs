@@ -0,0 +1,66 @@
+// Conversion helpers shared by the SQL ledger backends
+
+use crate::models::{HumanDecision, ReviewStatus, Severity, SuggestionType};
+
+pub(super) fn status_to_str(status: ReviewStatus) -> &'static str {
+    match status {
+        ReviewStatus::Pending => "pending",
+        ReviewStatus::Decided => "decided",
+        ReviewStatus::Applied => "applied",
+        ReviewStatus::Stale => "stale",
+    }
+}
+
+pub(super) fn str_to_status(s: &str) -> ReviewStatus {
+    match s {
+        "pending" => ReviewStatus::Pending,
+        "decided" => ReviewStatus::Decided,
+        "applied" => ReviewStatus::Applied,
+        "stale" => ReviewStatus::Stale,
+        _ => ReviewStatus::Pending,
+    }
+}
+
+pub(super) fn suggestion_type_to_str(t: &SuggestionType) -> &str {
+    t.as_str()
+}
+
+pub(super) fn str_to_suggestion_type(s: &str) -> SuggestionType {
+    s.parse().unwrap_or(SuggestionType::Logic)
+}
+
+pub(super) fn severity_to_str(s: Severity) -> &'static str {
+    match s {
+        Severity::Critical => "critical",
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
+    }
+}
+
+pub(super) fn str_to_severity(s: &str) -> Severity {
+    match s {
+        "critical" => Severity::Critical,
+        "high" => Severity::High,
+        "medium" => Severity::Medium,
+        "low" => Severity::Low,
+        _ => Severity::Low,
+    }
+}
+
+pub(super) fn decision_to_str(d: HumanDecision) -> &'static str {
+    match d {
+        HumanDecision::Accepted => "accepted",
+        HumanDecision::Rejected => "rejected",
+        HumanDecision::Deferred => "deferred",
+    }
+}
+
+pub(super) fn str_to_decision(s: &str) -> HumanDecision {
+    match s {
+        "accepted" => HumanDecision::Accepted,
+        "rejected" => HumanDecision::Rejected,
+        "deferred" => HumanDecision::Deferred,
+        _ => HumanDecision::Deferred,
+    }
+}
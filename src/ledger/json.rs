@@ -1,12 +1,14 @@
+use std::collections::HashSet;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use super::Ledger;
-use crate::models::{Review, ReviewStatus};
+use crate::models::{CachedFile, DecisionEvent, LlmInteraction, Review, ReviewStatus};
 
 /// JSON file-based ledger for MVP persistence
 pub struct JsonLedger {
@@ -28,6 +30,51 @@ impl JsonLedger {
         self.base_path.join(format!("{}.json", id))
     }
 
+    /// Sidecar file holding the LLM calls of a review
+    fn audit_path(&self, review_id: &Uuid) -> PathBuf {
+        self.base_path
+            .join("audit")
+            .join(format!("{}.json", review_id))
+    }
+
+    /// Append-only JSONL history of a review's decision changes
+    fn decisions_path(&self, review_id: &Uuid) -> PathBuf {
+        self.base_path
+            .join("decisions")
+            .join(format!("{}.jsonl", review_id))
+    }
+
+    fn append_decision_events(&self, review_id: &Uuid, events: &[DecisionEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let path = self.decisions_path(review_id);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| {
+                format!("Failed to create decisions directory: {}", dir.display())
+            })?;
+        }
+
+        let mut lines = String::new();
+        for event in events {
+            lines.push_str(&serde_json::to_string(event)?);
+            lines.push('\n');
+        }
+
+        // One write per save, so concurrent appends don't interleave within a line
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .with_context(|| format!("Failed to append decision history: {}", path.display()))
+    }
+
+    fn cache_path(&self, key: &str) -> PathBuf {
+        self.base_path.join("cache").join(format!("{}.json", key))
+    }
+
     fn index_path(&self) -> PathBuf {
         self.base_path.join("index.json")
     }
@@ -47,10 +94,138 @@ impl JsonLedger {
     fn save_index(&self, index: &ReviewIndex) -> Result<()> {
         let path = self.index_path();
         let content = serde_json::to_string_pretty(index)?;
-        fs::write(&path, content)
+        write_atomic(&path, &content)
             .with_context(|| format!("Failed to write index: {}", path.display()))?;
         Ok(())
     }
+
+    /// Take the advisory lock guarding read-modify-write cycles of the index
+    ///
+    /// The lock is released when the returned file is dropped.
+    fn lock_index(&self) -> Result<fs::File> {
+        let path = self.base_path.join("index.lock");
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file: {}", path.display()))?;
+
+        file.lock()
+            .with_context(|| format!("Failed to lock index: {}", path.display()))?;
+
+        Ok(file)
+    }
+
+    /// Rebuild index.json by scanning the review files on disk
+    pub fn fsck(&self) -> Result<FsckReport> {
+        let _lock = self.lock_index()?;
+
+        let old_ids: HashSet<Uuid> = match self.load_index() {
+            Ok(index) => index.reviews.iter().map(|r| r.id).collect(),
+            Err(e) => {
+                warn!(error = %e, "Existing index is unreadable, rebuilding from scratch");
+                HashSet::new()
+            }
+        };
+
+        let mut report = FsckReport::default();
+        let mut reviews = Vec::new();
+
+        for entry in fs::read_dir(&self.base_path)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+
+            // Leftovers from writes interrupted before the rename. Reviews and the index
+            // are only written under the lock we hold, so none of these is in flight.
+            if name.contains(".tmp.") {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                report.temp_files_removed += 1;
+                continue;
+            }
+
+            if name == "index.json" || path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            let parsed = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Review>(&content).ok());
+
+            match parsed {
+                Some(review) => reviews.push(review),
+                None => {
+                    warn!(path = %path.display(), "Unreadable review file");
+                    report.corrupt_files.push(path);
+                }
+            }
+        }
+
+        reviews.sort_by_key(|r| r.created_at);
+
+        let new_ids: HashSet<Uuid> = reviews.iter().map(|r| r.id).collect();
+        report.indexed = reviews.len();
+        report.added = new_ids.difference(&old_ids).count();
+        report.removed = old_ids.difference(&new_ids).count();
+
+        let index = ReviewIndex {
+            reviews: reviews.iter().map(ReviewIndexEntry::from).collect(),
+        };
+        self.save_index(&index)?;
+
+        info!(
+            indexed = report.indexed,
+            added = report.added,
+            removed = report.removed,
+            "Rebuilt ledger index"
+        );
+
+        Ok(report)
+    }
+}
+
+/// Result of rebuilding the JSON ledger index
+#[derive(Debug, Default)]
+pub struct FsckReport {
+    /// Reviews in the rebuilt index
+    pub indexed: usize,
+    /// Review files that were missing from the old index
+    pub added: usize,
+    /// Old index entries without a review file
+    pub removed: usize,
+    /// Review files that could not be parsed
+    pub corrupt_files: Vec<PathBuf>,
+    /// Interrupted temporary files that were deleted
+    pub temp_files_removed: usize,
+}
+
+/// Write a file so readers see either the old or the new content, never a partial write
+///
+/// Writes to the ledger root must hold the index lock, since `fsck` deletes the temporary
+/// files it finds there.
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .context("Invalid ledger file name")?;
+    let tmp_path = path.with_file_name(format!("{}.tmp.{}", file_name, Uuid::new_v4()));
+
+    let result = (|| {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    result.map_err(Into::into)
 }
 
 #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
@@ -67,28 +242,40 @@ struct ReviewIndexEntry {
     status: ReviewStatus,
 }
 
+impl From<&Review> for ReviewIndexEntry {
+    fn from(review: &Review) -> Self {
+        Self {
+            id: review.id,
+            repo: review.repo.clone(),
+            pr_number: review.pr_number,
+            commit_sha: review.commit_sha.clone(),
+            status: review.status,
+        }
+    }
+}
+
 impl Ledger for JsonLedger {
     fn save(&self, review: &Review) -> Result<()> {
+        // Hold the lock from reading the stored review until the index is updated, so
+        // concurrent saves of one review each diff against what the other one wrote
+        let _lock = self.lock_index()?;
+        let previous = self.load(&review.id)?;
+        let events = review.decision_changes(previous.as_ref().map_or(&[], |p| &p.suggestions));
+
         let path = self.review_path(&review.id);
         let content = serde_json::to_string_pretty(review)?;
 
-        fs::write(&path, content)
+        write_atomic(&path, &content)
             .with_context(|| format!("Failed to write review: {}", path.display()))?;
+        self.append_decision_events(&review.id, &events)?;
 
-        // Update index
         let mut index = self.load_index()?;
 
         // Remove existing entry if present
         index.reviews.retain(|r| r.id != review.id);
 
         // Add new entry
-        index.reviews.push(ReviewIndexEntry {
-            id: review.id,
-            repo: review.repo.clone(),
-            pr_number: review.pr_number,
-            commit_sha: review.commit_sha.clone(),
-            status: review.status,
-        });
+        index.reviews.push(ReviewIndexEntry::from(review));
 
         self.save_index(&index)?;
 
@@ -112,17 +299,26 @@ impl Ledger for JsonLedger {
     }
 
     fn load_by_pr(&self, repo: &str, pr_number: u64) -> Result<Option<Review>> {
+        Ok(self.list_by_pr(repo, pr_number)?.pop())
+    }
+
+    fn list_by_pr(&self, repo: &str, pr_number: u64) -> Result<Vec<Review>> {
         let index = self.load_index()?;
 
-        let entry = index
+        let mut reviews = Vec::new();
+        for entry in index
             .reviews
             .iter()
-            .find(|r| r.repo == repo && r.pr_number == Some(pr_number));
-
-        match entry {
-            Some(e) => self.load(&e.id),
-            None => Ok(None),
+            .filter(|r| r.repo == repo && r.pr_number == Some(pr_number))
+        {
+            if let Some(review) = self.load(&entry.id)? {
+                reviews.push(review);
+            }
         }
+
+        reviews.sort_by_key(|r| r.created_at);
+
+        Ok(reviews)
     }
 
     fn load_by_commit(&self, repo: &str, commit_sha: &str) -> Result<Option<Review>> {
@@ -139,6 +335,21 @@ impl Ledger for JsonLedger {
         }
     }
 
+    fn list_by_commit_prefix(&self, repo: Option<&str>, prefix: &str) -> Result<Vec<Review>> {
+        let index = self.load_index()?;
+
+        let mut reviews = Vec::new();
+        for entry in index.reviews.iter().filter(|r| {
+            repo.is_none_or(|repo| r.repo == repo) && r.commit_sha.starts_with(prefix)
+        }) {
+            if let Some(review) = self.load(&entry.id)? {
+                reviews.push(review);
+            }
+        }
+
+        Ok(reviews)
+    }
+
     fn list_pending(&self) -> Result<Vec<Review>> {
         let index = self.load_index()?;
 
@@ -164,14 +375,141 @@ impl Ledger for JsonLedger {
 
         Ok(reviews)
     }
+
+    fn list_all(&self) -> Result<Vec<Review>> {
+        let index = self.load_index()?;
+
+        let mut reviews = Vec::new();
+        for entry in &index.reviews {
+            if let Some(review) = self.load(&entry.id)? {
+                reviews.push(review);
+            }
+        }
+
+        reviews.sort_by_key(|r| r.created_at);
+
+        Ok(reviews)
+    }
+
+    fn save_interactions(&self, review_id: &Uuid, interactions: &[LlmInteraction]) -> Result<()> {
+        let path = self.audit_path(review_id);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create audit directory: {}", dir.display()))?;
+        }
+
+        let content = serde_json::to_string_pretty(interactions)?;
+        write_atomic(&path, &content)
+            .with_context(|| format!("Failed to write audit log: {}", path.display()))?;
+
+        debug!(id = %review_id, count = interactions.len(), "Saved LLM interactions");
+
+        Ok(())
+    }
+
+    fn load_interactions(&self, review_id: &Uuid) -> Result<Vec<LlmInteraction>> {
+        let path = self.audit_path(review_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read audit log: {}", path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse audit log: {}", path.display()))
+    }
+
+    fn list_interactions(&self) -> Result<Vec<LlmInteraction>> {
+        let dir = self.base_path.join("audit");
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut interactions = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read audit log: {}", path.display()))?;
+            let mut logged: Vec<LlmInteraction> = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse audit log: {}", path.display()))?;
+            interactions.append(&mut logged);
+        }
+
+        interactions.sort_by_key(|i| i.created_at);
+
+        Ok(interactions)
+    }
+
+    fn load_decision_events(&self, review_id: &Uuid) -> Result<Vec<DecisionEvent>> {
+        let path = self.decisions_path(review_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read decision history: {}", path.display()))?;
+
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).with_context(|| {
+                    format!("Failed to parse decision history: {}", path.display())
+                })
+            })
+            .collect()
+    }
+
+    fn load_cached(&self, key: &str) -> Result<Option<CachedFile>> {
+        let path = self.cache_path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read cache entry: {}", path.display()))?;
+
+        // A damaged entry only costs a fresh review of the file
+        match serde_json::from_str(&content) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Ignoring unreadable cache entry");
+                Ok(None)
+            }
+        }
+    }
+
+    fn save_cached(&self, entry: &CachedFile) -> Result<()> {
+        let path = self.cache_path(&entry.key);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        }
+
+        let content = serde_json::to_string_pretty(entry)?;
+        write_atomic(&path, &content)
+            .with_context(|| format!("Failed to write cache entry: {}", path.display()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::ReviewContext;
+    use crate::models::{DecisionRecord, HumanDecision, ReviewContext, Severity, SuggestionItem};
     use tempfile::tempdir;
 
+    fn decidable_item() -> SuggestionItem {
+        SuggestionItem::test("S001", "src/lib.rs")
+            .with_severity(Severity::High)
+            .with_lines(1, 2)
+            .with_description("Off by one")
+    }
+
     #[test]
     fn test_save_and_load() {
         let dir = tempdir().unwrap();
@@ -211,6 +549,41 @@ mod tests {
         assert_eq!(loaded.id, review.id);
     }
 
+    #[test]
+    fn test_load_by_pr_returns_latest() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+
+        let mut first = Review::new(ReviewContext {
+            pr_number: Some(456),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "aaa111".to_string(),
+            base_sha: None,
+        });
+        first.created_at -= chrono::Duration::hours(1);
+        let mut second = Review::new(ReviewContext {
+            pr_number: Some(456),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "bbb222".to_string(),
+            base_sha: None,
+        });
+        second.previous_review_id = Some(first.id);
+
+        ledger.save(&second).unwrap();
+        // Re-saving the older review must not make it the latest
+        ledger.save(&first).unwrap();
+
+        let loaded = ledger.load_by_pr("owner/repo", 456).unwrap().unwrap();
+        assert_eq!(loaded.id, second.id);
+        assert_eq!(loaded.previous_review_id, Some(first.id));
+
+        let history = ledger.list_by_pr("owner/repo", 456).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].id, first.id);
+    }
+
     #[test]
     fn test_load_by_commit() {
         let dir = tempdir().unwrap();
@@ -230,4 +603,231 @@ mod tests {
         assert_eq!(loaded.id, review.id);
         assert_eq!(loaded.pr_number, None);
     }
+
+    #[test]
+    fn test_concurrent_saves_keep_all_entries() {
+        let dir = tempdir().unwrap();
+        let threads = 8;
+        let per_thread = 25;
+
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                let path = dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    // Separate instances, as separate CI jobs would have
+                    let ledger = JsonLedger::new(&path).unwrap();
+                    for i in 0..per_thread {
+                        let review = Review::new(ReviewContext {
+                            pr_number: Some(t * 100 + i),
+                            repo: "owner/repo".to_string(),
+                            branch: None,
+                            commit_sha: format!("sha-{}-{}", t, i),
+                            base_sha: None,
+                        });
+                        ledger.save(&review).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+        let index = ledger.load_index().unwrap();
+        assert_eq!(index.reviews.len(), (threads * per_thread) as usize);
+        assert_eq!(
+            ledger.list_pending().unwrap().len(),
+            (threads * per_thread) as usize
+        );
+    }
+
+    #[test]
+    fn test_concurrent_decisions_keep_history() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+        review.suggestions.push(decidable_item());
+        ledger.save(&review).unwrap();
+
+        let threads = 8;
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                let path = dir.path().to_path_buf();
+                let mut review = review.clone();
+                std::thread::spawn(move || {
+                    let ledger = JsonLedger::new(&path).unwrap();
+                    review.suggestions[0].decision = Some(DecisionRecord {
+                        suggestion_id: "S001".to_string(),
+                        decision: HumanDecision::Accepted,
+                        reason: None,
+                        decided_by: format!("user-{}", t),
+                        decided_at: chrono::Utc::now(),
+                        deferred_until: None,
+                        verified: false,
+                    });
+                    ledger.save(&review).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Every save saw the decision the one before it wrote
+        let events = ledger.load_decision_events(&review.id).unwrap();
+        assert_eq!(events.len(), threads);
+        assert!(events[0].previous.is_none());
+        for pair in events.windows(2) {
+            assert_eq!(pair[1].previous, pair[0].decision);
+        }
+        let saved = ledger.load(&review.id).unwrap().unwrap();
+        assert_eq!(saved.suggestions[0].decision, events[threads - 1].decision);
+    }
+
+    #[test]
+    fn test_fsck_rebuilds_index() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            let review = Review::new(ReviewContext {
+                pr_number: Some(i),
+                repo: "owner/repo".to_string(),
+                branch: None,
+                commit_sha: format!("sha{}", i),
+                base_sha: None,
+            });
+            ledger.save(&review).unwrap();
+            ids.push(review.id);
+        }
+
+        // Lose the index, leave a truncated write and a stray temp file behind
+        fs::write(ledger.index_path(), "{\"reviews\": [").unwrap();
+        fs::write(dir.path().join("broken.json"), "{\"id\":").unwrap();
+        fs::write(dir.path().join("index.json.tmp.1234"), "{}").unwrap();
+
+        let report = ledger.fsck().unwrap();
+        assert_eq!(report.indexed, 3);
+        assert_eq!(report.added, 3);
+        assert_eq!(report.removed, 0);
+        assert_eq!(report.corrupt_files.len(), 1);
+        assert_eq!(report.temp_files_removed, 1);
+
+        for (i, id) in ids.iter().enumerate() {
+            let loaded = ledger.load_by_pr("owner/repo", i as u64).unwrap().unwrap();
+            assert_eq!(&loaded.id, id);
+        }
+
+        // Dropping a review file removes its entry on the next run
+        fs::remove_file(ledger.review_path(&ids[0])).unwrap();
+        let report = ledger.fsck().unwrap();
+        assert_eq!(report.indexed, 2);
+        assert_eq!(report.removed, 1);
+    }
+
+    #[test]
+    fn test_decision_log() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+        review.suggestions.push(decidable_item());
+        ledger.save(&review).unwrap();
+        assert!(!ledger.decisions_path(&review.id).exists());
+
+        for decision in [HumanDecision::Deferred, HumanDecision::Accepted] {
+            review
+                .record_decision(DecisionRecord {
+                    suggestion_id: "S001".to_string(),
+                    decision,
+                    reason: None,
+                    decided_by: "alice".to_string(),
+                    decided_at: chrono::Utc::now(),
+                    deferred_until: None,
+                    verified: false,
+                })
+                .unwrap();
+            ledger.save(&review).unwrap();
+        }
+        ledger.save(&review).unwrap();
+
+        let events = ledger.load_decision_events(&review.id).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].review_id, review.id);
+        assert!(events[0].previous.is_none());
+        assert_eq!(
+            events[1].previous.as_ref().map(|d| d.decision),
+            Some(HumanDecision::Deferred)
+        );
+        assert!(ledger
+            .load_decision_events(&Uuid::new_v4())
+            .unwrap()
+            .is_empty());
+
+        // The decisions directory is not mistaken for a review file
+        assert_eq!(ledger.fsck().unwrap().indexed, 1);
+    }
+
+    #[test]
+    fn test_interaction_sidecar() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+
+        let review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+        ledger.save(&review).unwrap();
+
+        let interaction = LlmInteraction {
+            id: Uuid::new_v4(),
+            review_id: Some(review.id),
+            provider: "anthropic".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            created_at: chrono::Utc::now(),
+            latency_ms: 2100,
+            request: serde_json::json!({ "messages": [] }),
+            response: serde_json::json!({ "content": [] }),
+            prompt_tokens: Some(900),
+            completion_tokens: Some(150),
+            cost_usd: Some(0.00495),
+        };
+        ledger
+            .save_interactions(&review.id, std::slice::from_ref(&interaction))
+            .unwrap();
+
+        assert!(ledger.audit_path(&review.id).exists());
+        let loaded = ledger.load_interactions(&review.id).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].id, interaction.id);
+        assert_eq!(loaded[0].prompt_tokens, Some(900));
+        assert_eq!(ledger.list_interactions().unwrap().len(), 1);
+        assert!(ledger
+            .load_interactions(&Uuid::new_v4())
+            .unwrap()
+            .is_empty());
+
+        // The audit directory is not mistaken for a review file
+        let report = ledger.fsck().unwrap();
+        assert_eq!(report.indexed, 1);
+        assert!(report.corrupt_files.is_empty());
+    }
 }
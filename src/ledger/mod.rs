@@ -1,11 +1,20 @@
+mod convert;
 pub mod json;
 pub mod postgres;
+pub mod sqlite;
+pub mod transfer;
 
-pub use json::JsonLedger;
+pub use json::{FsckReport, JsonLedger};
 pub use postgres::{PostgresLedger, RepoStats};
+pub use sqlite::SqliteLedger;
+pub use transfer::{import_reviews, read_jsonl, write_jsonl, TransferConflict, TransferReport};
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 use anyhow::Result;
-use crate::models::Review;
+use crate::models::{CachedFile, DecisionEvent, LlmInteraction, Review};
 
 /// Trait for review persistence backends (sync version for JSON)
 pub trait Ledger: Send + Sync {
@@ -15,15 +24,208 @@ pub trait Ledger: Send + Sync {
     /// Load a review by ID
     fn load(&self, id: &uuid::Uuid) -> Result<Option<Review>>;
 
-    /// Load a review by PR number
+    /// Load the latest review of a PR
     fn load_by_pr(&self, repo: &str, pr_number: u64) -> Result<Option<Review>>;
 
+    /// List all reviews of a PR, oldest first
+    fn list_by_pr(&self, repo: &str, pr_number: u64) -> Result<Vec<Review>>;
+
     /// Load a review by commit SHA
     fn load_by_commit(&self, repo: &str, commit_sha: &str) -> Result<Option<Review>>;
 
+    /// List reviews whose commit SHA starts with the given prefix
+    fn list_by_commit_prefix(&self, repo: Option<&str>, prefix: &str) -> Result<Vec<Review>>;
+
     /// List all pending reviews
     fn list_pending(&self) -> Result<Vec<Review>>;
 
     /// List all reviews for a repository
     fn list_by_repo(&self, repo: &str) -> Result<Vec<Review>>;
+
+    /// List every review in the ledger, oldest first
+    fn list_all(&self) -> Result<Vec<Review>>;
+
+    /// Record the LLM calls made for a review, replacing any recorded before
+    fn save_interactions(
+        &self,
+        review_id: &uuid::Uuid,
+        interactions: &[LlmInteraction],
+    ) -> Result<()>;
+
+    /// Load the LLM calls made for a review, oldest first
+    fn load_interactions(&self, review_id: &uuid::Uuid) -> Result<Vec<LlmInteraction>>;
+
+    /// List every recorded LLM call, oldest first
+    fn list_interactions(&self) -> Result<Vec<LlmInteraction>>;
+
+    /// Load the decision changes recorded for a review, oldest first
+    ///
+    /// `save` appends an event for every decision that differs from the stored one.
+    fn load_decision_events(&self, review_id: &uuid::Uuid) -> Result<Vec<DecisionEvent>>;
+
+    /// Load cached findings for a file diff by cache key
+    fn load_cached(&self, key: &str) -> Result<Option<CachedFile>>;
+
+    /// Store findings for a file diff, replacing any entry with the same key
+    fn save_cached(&self, entry: &CachedFile) -> Result<()>;
+}
+
+impl<L: Ledger + ?Sized> Ledger for Box<L> {
+    fn save(&self, review: &Review) -> Result<()> {
+        (**self).save(review)
+    }
+
+    fn load(&self, id: &uuid::Uuid) -> Result<Option<Review>> {
+        (**self).load(id)
+    }
+
+    fn load_by_pr(&self, repo: &str, pr_number: u64) -> Result<Option<Review>> {
+        (**self).load_by_pr(repo, pr_number)
+    }
+
+    fn list_by_pr(&self, repo: &str, pr_number: u64) -> Result<Vec<Review>> {
+        (**self).list_by_pr(repo, pr_number)
+    }
+
+    fn load_by_commit(&self, repo: &str, commit_sha: &str) -> Result<Option<Review>> {
+        (**self).load_by_commit(repo, commit_sha)
+    }
+
+    fn list_by_commit_prefix(&self, repo: Option<&str>, prefix: &str) -> Result<Vec<Review>> {
+        (**self).list_by_commit_prefix(repo, prefix)
+    }
+
+    fn list_pending(&self) -> Result<Vec<Review>> {
+        (**self).list_pending()
+    }
+
+    fn list_by_repo(&self, repo: &str) -> Result<Vec<Review>> {
+        (**self).list_by_repo(repo)
+    }
+
+    fn list_all(&self) -> Result<Vec<Review>> {
+        (**self).list_all()
+    }
+
+    fn save_interactions(
+        &self,
+        review_id: &uuid::Uuid,
+        interactions: &[LlmInteraction],
+    ) -> Result<()> {
+        (**self).save_interactions(review_id, interactions)
+    }
+
+    fn load_interactions(&self, review_id: &uuid::Uuid) -> Result<Vec<LlmInteraction>> {
+        (**self).load_interactions(review_id)
+    }
+
+    fn list_interactions(&self) -> Result<Vec<LlmInteraction>> {
+        (**self).list_interactions()
+    }
+
+    fn load_decision_events(&self, review_id: &uuid::Uuid) -> Result<Vec<DecisionEvent>> {
+        (**self).load_decision_events(review_id)
+    }
+
+    fn load_cached(&self, key: &str) -> Result<Option<CachedFile>> {
+        (**self).load_cached(key)
+    }
+
+    fn save_cached(&self, entry: &CachedFile) -> Result<()> {
+        (**self).save_cached(entry)
+    }
+}
+
+/// Where a ledger lives, as given on the command line
+///
+/// `sqlite://path` selects the SQLite backend, `postgres://...` a database URL and
+/// `json:path` or a plain path the JSON ledger directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerLocation {
+    Json(PathBuf),
+    Sqlite(PathBuf),
+    Postgres(String),
+}
+
+impl FromStr for LedgerLocation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("sqlite://") {
+            if path.is_empty() {
+                anyhow::bail!("SQLite ledger location needs a path: sqlite://<path>");
+            }
+            return Ok(LedgerLocation::Sqlite(PathBuf::from(path)));
+        }
+
+        if s.starts_with("postgres://") || s.starts_with("postgresql://") {
+            return Ok(LedgerLocation::Postgres(s.to_string()));
+        }
+
+        let path = s.strip_prefix("json:").unwrap_or(s);
+        if path.is_empty() {
+            anyhow::bail!("Ledger location cannot be empty");
+        }
+
+        Ok(LedgerLocation::Json(PathBuf::from(path)))
+    }
+}
+
+impl fmt::Display for LedgerLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerLocation::Json(path) => write!(f, "{}", path.display()),
+            LedgerLocation::Sqlite(path) => write!(f, "sqlite://{}", path.display()),
+            LedgerLocation::Postgres(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+impl LedgerLocation {
+    /// Open the synchronous ledger at this location
+    pub fn open(&self) -> Result<Box<dyn Ledger>> {
+        match self {
+            LedgerLocation::Json(path) => Ok(Box::new(JsonLedger::new(path)?)),
+            LedgerLocation::Sqlite(path) => Ok(Box::new(SqliteLedger::open(path)?)),
+            LedgerLocation::Postgres(_) => anyhow::bail!(
+                "The PostgreSQL ledger is not supported by the CLI yet; use a JSON path or sqlite://<path>"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ledger_location() {
+        assert_eq!(
+            "sqlite://.ai-review/ledger.db"
+                .parse::<LedgerLocation>()
+                .unwrap(),
+            LedgerLocation::Sqlite(PathBuf::from(".ai-review/ledger.db"))
+        );
+        assert_eq!(
+            "sqlite:///var/lib/lgtm.db"
+                .parse::<LedgerLocation>()
+                .unwrap(),
+            LedgerLocation::Sqlite(PathBuf::from("/var/lib/lgtm.db"))
+        );
+        assert_eq!(
+            ".ai-review/ledger".parse::<LedgerLocation>().unwrap(),
+            LedgerLocation::Json(PathBuf::from(".ai-review/ledger"))
+        );
+        assert_eq!(
+            "json:ledger".parse::<LedgerLocation>().unwrap(),
+            LedgerLocation::Json(PathBuf::from("ledger"))
+        );
+        assert!(matches!(
+            "postgres://localhost/lgtm"
+                .parse::<LedgerLocation>()
+                .unwrap(),
+            LedgerLocation::Postgres(_)
+        ));
+        assert!("sqlite://".parse::<LedgerLocation>().is_err());
+    }
 }
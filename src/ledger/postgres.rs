@@ -4,9 +4,13 @@ use sqlx::{PgPool, Row};
 use tracing::{debug, info};
 use uuid::Uuid;
 
+use super::convert::{
+    decision_to_str, severity_to_str, status_to_str, str_to_decision, str_to_severity,
+    str_to_status, str_to_suggestion_type, suggestion_type_to_str,
+};
 use crate::models::{
-    DecisionRecord, HumanDecision, Location, Review, ReviewStatus, Severity, Suggestion,
-    SuggestionItem, SuggestionType,
+    CachedFile, Consensus, DecisionEvent, DecisionRecord, LlmInteraction, Location, OutputQuality,
+    Review, Suggestion, SuggestionItem,
 };
 
 /// PostgreSQL-backed ledger for production persistence
@@ -42,15 +46,22 @@ impl PostgresLedger {
 
     /// Save a review to the database
     pub async fn save(&self, review: &Review) -> Result<()> {
+        let previous = self.load(&review.id).await?;
+        let events = review.decision_changes(previous.as_ref().map_or(&[], |p| &p.suggestions));
+
         let mut tx = self.pool.begin().await?;
 
         // Upsert the review
         sqlx::query(
             r#"
-            INSERT INTO reviews (id, pr_number, repo, branch, commit_sha, created_at, status)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO reviews (
+                id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             ON CONFLICT (id) DO UPDATE SET
-                status = EXCLUDED.status
+                status = EXCLUDED.status,
+                previous_review_id = EXCLUDED.previous_review_id
             "#,
         )
         .bind(review.id)
@@ -60,6 +71,12 @@ impl PostgresLedger {
         .bind(&review.commit_sha)
         .bind(review.created_at)
         .bind(status_to_str(review.status))
+        .bind(review.previous_review_id)
+        .bind(review.output_quality.coerced_fields as i32)
+        .bind(review.output_quality.dropped_fields as i32)
+        .bind(review.output_quality.dropped_suggestions as i32)
+        .bind(review.output_quality.repair_rounds as i32)
+        .bind(&review.prompt_hash)
         .execute(&mut *tx)
         .await
         .context("Failed to save review")?;
@@ -80,15 +97,18 @@ impl PostgresLedger {
                 INSERT INTO suggestions (
                     review_id, external_id, suggestion_type, severity,
                     file_path, line_start, line_end, description, proposed_fix,
-                    human_decision, human_reason, decided_by, decided_at
+                    human_decision, human_reason, decided_by, decided_at, deferred_until,
+                    flagged_by, ensemble_size, resolved_in, tags, confidence, replacement, applied_at,
+                    owners, decision_verified
                 ) VALUES (
-                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                    $19, $20::jsonb, $21, $22, $23
                 )
                 "#,
             )
             .bind(review.id)
             .bind(&s.id)
-            .bind(suggestion_type_to_str(s.suggestion_type))
+            .bind(suggestion_type_to_str(&s.suggestion_type))
             .bind(severity_to_str(s.severity))
             .bind(&s.location.file)
             .bind(s.location.line_start as i32)
@@ -99,11 +119,43 @@ impl PostgresLedger {
             .bind(d.as_ref().and_then(|d| d.reason.as_ref()))
             .bind(d.as_ref().map(|d| &d.decided_by))
             .bind(d.as_ref().map(|d| d.decided_at))
+            .bind(d.as_ref().and_then(|d| d.deferred_until))
+            .bind(s.consensus.as_ref().map(|c| &c.flagged_by))
+            .bind(s.consensus.as_ref().map(|c| c.reviewers as i32))
+            .bind(&item.resolved_in)
+            .bind(&s.tags)
+            .bind(s.confidence)
+            .bind(
+                s.replacement
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+            )
+            .bind(item.applied_at)
+            .bind(&item.owners)
+            .bind(d.as_ref().is_some_and(|d| d.verified))
             .execute(&mut *tx)
             .await
             .context("Failed to save suggestion")?;
         }
 
+        for event in &events {
+            sqlx::query(
+                r#"
+                INSERT INTO decision_events (review_id, suggestion_id, decision, previous, recorded_at)
+                VALUES ($1, $2, $3::jsonb, $4::jsonb, $5)
+                "#,
+            )
+            .bind(event.review_id)
+            .bind(&event.suggestion_id)
+            .bind(event.decision.as_ref().map(serde_json::to_string).transpose()?)
+            .bind(event.previous.as_ref().map(serde_json::to_string).transpose()?)
+            .bind(event.recorded_at)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to record decision event")?;
+        }
+
         tx.commit().await?;
 
         debug!(id = %review.id, "Saved review to database");
@@ -115,7 +167,8 @@ impl PostgresLedger {
     pub async fn load(&self, id: &Uuid) -> Result<Option<Review>> {
         let row = sqlx::query(
             r#"
-            SELECT id, pr_number, repo, branch, commit_sha, created_at, status
+            SELECT id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                   coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
             FROM reviews WHERE id = $1
             "#,
         )
@@ -136,7 +189,8 @@ impl PostgresLedger {
     pub async fn load_by_pr(&self, repo: &str, pr_number: u64) -> Result<Option<Review>> {
         let row = sqlx::query(
             r#"
-            SELECT id, pr_number, repo, branch, commit_sha, created_at, status
+            SELECT id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                   coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
             FROM reviews
             WHERE repo = $1 AND pr_number = $2
             ORDER BY created_at DESC
@@ -157,11 +211,37 @@ impl PostgresLedger {
         }
     }
 
+    /// List all reviews of a PR, oldest first
+    pub async fn list_by_pr(&self, repo: &str, pr_number: u64) -> Result<Vec<Review>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                   coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
+            FROM reviews
+            WHERE repo = $1 AND pr_number = $2
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(repo)
+        .bind(pr_number as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut reviews = Vec::new();
+        for row in rows {
+            let review = self.build_review_from_row(&row).await?;
+            reviews.push(review);
+        }
+
+        Ok(reviews)
+    }
+
     /// Load a review by commit SHA
     pub async fn load_by_commit(&self, repo: &str, commit_sha: &str) -> Result<Option<Review>> {
         let row = sqlx::query(
             r#"
-            SELECT id, pr_number, repo, branch, commit_sha, created_at, status
+            SELECT id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                   coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
             FROM reviews
             WHERE repo = $1 AND commit_sha = $2
             ORDER BY created_at DESC
@@ -182,11 +262,41 @@ impl PostgresLedger {
         }
     }
 
+    /// List reviews whose commit SHA starts with the given prefix
+    pub async fn list_by_commit_prefix(
+        &self,
+        repo: Option<&str>,
+        prefix: &str,
+    ) -> Result<Vec<Review>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                   coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
+            FROM reviews
+            WHERE ($1::TEXT IS NULL OR repo = $1) AND commit_sha LIKE $2 || '%'
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(repo)
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut reviews = Vec::new();
+        for row in rows {
+            let review = self.build_review_from_row(&row).await?;
+            reviews.push(review);
+        }
+
+        Ok(reviews)
+    }
+
     /// List all pending reviews
     pub async fn list_pending(&self) -> Result<Vec<Review>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, pr_number, repo, branch, commit_sha, created_at, status
+            SELECT id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                   coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
             FROM reviews
             WHERE status = 'pending'
             ORDER BY created_at DESC
@@ -208,7 +318,8 @@ impl PostgresLedger {
     pub async fn list_by_repo(&self, repo: &str) -> Result<Vec<Review>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, pr_number, repo, branch, commit_sha, created_at, status
+            SELECT id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                   coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
             FROM reviews
             WHERE repo = $1
             ORDER BY created_at DESC
@@ -227,6 +338,28 @@ impl PostgresLedger {
         Ok(reviews)
     }
 
+    /// List every review, oldest first
+    pub async fn list_all(&self) -> Result<Vec<Review>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                   coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
+            FROM reviews
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut reviews = Vec::new();
+        for row in rows {
+            let review = self.build_review_from_row(&row).await?;
+            reviews.push(review);
+        }
+
+        Ok(reviews)
+    }
+
     /// Mark old reviews as stale
     pub async fn mark_stale(&self, repo: &str, pr_number: u64, except_id: &Uuid) -> Result<u64> {
         let result = sqlx::query(
@@ -245,6 +378,171 @@ impl PostgresLedger {
         Ok(result.rows_affected())
     }
 
+    /// Record the LLM calls made for a review, replacing any recorded before
+    pub async fn save_interactions(
+        &self,
+        review_id: &Uuid,
+        interactions: &[LlmInteraction],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM llm_interactions WHERE review_id = $1")
+            .bind(review_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for i in interactions {
+            sqlx::query(
+                r#"
+                INSERT INTO llm_interactions (
+                    id, review_id, provider, model, created_at, latency_ms,
+                    request, response, prompt_tokens, completion_tokens, cost_usd
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7::jsonb, $8::jsonb, $9, $10, $11)
+                "#,
+            )
+            .bind(i.id)
+            .bind(review_id)
+            .bind(&i.provider)
+            .bind(&i.model)
+            .bind(i.created_at)
+            .bind(i.latency_ms as i64)
+            .bind(serde_json::to_string(&i.request)?)
+            .bind(serde_json::to_string(&i.response)?)
+            .bind(i.prompt_tokens.map(|n| n as i32))
+            .bind(i.completion_tokens.map(|n| n as i32))
+            .bind(i.cost_usd)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to save LLM interaction")?;
+        }
+
+        tx.commit().await?;
+
+        debug!(id = %review_id, count = interactions.len(), "Saved LLM interactions");
+
+        Ok(())
+    }
+
+    /// Load the LLM calls made for a review, oldest first
+    pub async fn load_interactions(&self, review_id: &Uuid) -> Result<Vec<LlmInteraction>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, review_id, provider, model, created_at, latency_ms,
+                   request::text AS request, response::text AS response,
+                   prompt_tokens, completion_tokens, cost_usd
+            FROM llm_interactions
+            WHERE review_id = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(review_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(interaction_from_row).collect()
+    }
+
+    /// Load the decision changes recorded for a review, oldest first
+    pub async fn load_decision_events(&self, review_id: &Uuid) -> Result<Vec<DecisionEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT suggestion_id, decision::text AS decision, previous::text AS previous,
+                   recorded_at
+            FROM decision_events
+            WHERE review_id = $1
+            ORDER BY id
+            "#,
+        )
+        .bind(review_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(DecisionEvent {
+                    review_id: *review_id,
+                    suggestion_id: row.get("suggestion_id"),
+                    decision: row
+                        .get::<Option<String>, _>("decision")
+                        .map(|d| serde_json::from_str(&d))
+                        .transpose()?,
+                    previous: row
+                        .get::<Option<String>, _>("previous")
+                        .map(|d| serde_json::from_str(&d))
+                        .transpose()?,
+                    recorded_at: row.get("recorded_at"),
+                })
+            })
+            .collect()
+    }
+
+    /// List every recorded LLM call, oldest first
+    pub async fn list_interactions(&self) -> Result<Vec<LlmInteraction>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, review_id, provider, model, created_at, latency_ms,
+                   request::text AS request, response::text AS response,
+                   prompt_tokens, completion_tokens, cost_usd
+            FROM llm_interactions
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(interaction_from_row).collect()
+    }
+
+    /// Load cached findings for a file diff by cache key
+    pub async fn load_cached(&self, key: &str) -> Result<Option<CachedFile>> {
+        let row = sqlx::query(
+            r#"
+            SELECT key, file_path, suggestions::text AS suggestions, created_at
+            FROM review_cache
+            WHERE key = $1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let suggestions: String = row.get("suggestions");
+
+        Ok(Some(CachedFile {
+            key: row.get("key"),
+            file: row.get("file_path"),
+            suggestions: serde_json::from_str(&suggestions)
+                .context("Failed to parse cached suggestions")?,
+            created_at: row.get("created_at"),
+        }))
+    }
+
+    /// Store findings for a file diff, replacing any entry with the same key
+    pub async fn save_cached(&self, entry: &CachedFile) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO review_cache (key, file_path, suggestions, created_at)
+            VALUES ($1, $2, $3::jsonb, $4)
+            ON CONFLICT (key) DO UPDATE SET
+                file_path = EXCLUDED.file_path,
+                suggestions = EXCLUDED.suggestions,
+                created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(&entry.key)
+        .bind(&entry.file)
+        .bind(serde_json::to_string(&entry.suggestions)?)
+        .bind(entry.created_at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save cache entry")?;
+
+        Ok(())
+    }
+
     /// Get statistics for a repository
     pub async fn get_stats(&self, repo: &str) -> Result<RepoStats> {
         let row = sqlx::query(
@@ -292,13 +590,16 @@ impl PostgresLedger {
         let commit_sha: String = row.get("commit_sha");
         let created_at: chrono::DateTime<chrono::Utc> = row.get("created_at");
         let status: String = row.get("status");
+        let previous_review_id: Option<Uuid> = row.get("previous_review_id");
 
         // Load suggestions for this review
         let suggestion_rows = sqlx::query(
             r#"
             SELECT
                 external_id, suggestion_type, severity, file_path, line_start, line_end,
-                description, proposed_fix, human_decision, human_reason, decided_by, decided_at
+                description, proposed_fix, human_decision, human_reason, decided_by, decided_at,
+                deferred_until, flagged_by, ensemble_size, resolved_in, tags, confidence,
+                replacement::text AS replacement, applied_at, owners, decision_verified
             FROM suggestions
             WHERE review_id = $1
             ORDER BY external_id
@@ -321,6 +622,21 @@ impl PostgresLedger {
                 },
                 description: srow.get("description"),
                 proposed_fix: srow.get("proposed_fix"),
+                consensus: srow
+                    .get::<Option<i32>, _>("ensemble_size")
+                    .map(|reviewers| Consensus {
+                        flagged_by: srow
+                            .get::<Option<Vec<String>>, _>("flagged_by")
+                            .unwrap_or_default(),
+                        reviewers: reviewers as u32,
+                    }),
+                tags: srow.get("tags"),
+                confidence: srow.get("confidence"),
+                replacement: srow
+                    .get::<Option<String>, _>("replacement")
+                    .map(|json| serde_json::from_str(&json))
+                    .transpose()
+                    .context("Failed to parse stored replacement")?,
             };
 
             let decision = match srow.get::<Option<String>, _>("human_decision") {
@@ -332,6 +648,8 @@ impl PostgresLedger {
                     decided_at: srow
                         .get::<Option<chrono::DateTime<chrono::Utc>>, _>("decided_at")
                         .unwrap_or_else(chrono::Utc::now),
+                    deferred_until: srow.get("deferred_until"),
+                    verified: srow.get("decision_verified"),
                 }),
                 None => None,
             };
@@ -339,6 +657,9 @@ impl PostgresLedger {
             suggestions.push(SuggestionItem {
                 suggestion,
                 decision,
+                resolved_in: srow.get("resolved_in"),
+                applied_at: srow.get("applied_at"),
+                owners: srow.get("owners"),
             });
         }
 
@@ -351,12 +672,41 @@ impl PostgresLedger {
             created_at,
             status: str_to_status(&status),
             suggestions,
+            previous_review_id,
+            output_quality: OutputQuality {
+                coerced_fields: row.get::<i32, _>("coerced_fields") as u32,
+                dropped_fields: row.get::<i32, _>("dropped_fields") as u32,
+                dropped_suggestions: row.get::<i32, _>("dropped_suggestions") as u32,
+                repair_rounds: row.get::<i32, _>("repair_rounds") as u32,
+            },
+            prompt_hash: row.get("prompt_hash"),
         })
     }
 }
 
+fn interaction_from_row(row: &sqlx::postgres::PgRow) -> Result<LlmInteraction> {
+    let request: String = row.get("request");
+    let response: String = row.get("response");
+
+    Ok(LlmInteraction {
+        id: row.get("id"),
+        review_id: Some(row.get("review_id")),
+        provider: row.get("provider"),
+        model: row.get("model"),
+        created_at: row.get("created_at"),
+        latency_ms: row.get::<i64, _>("latency_ms") as u64,
+        request: serde_json::from_str(&request).context("Failed to parse stored request")?,
+        response: serde_json::from_str(&response).context("Failed to parse stored response")?,
+        prompt_tokens: row.get::<Option<i32>, _>("prompt_tokens").map(|n| n as u32),
+        completion_tokens: row
+            .get::<Option<i32>, _>("completion_tokens")
+            .map(|n| n as u32),
+        cost_usd: row.get("cost_usd"),
+    })
+}
+
 /// Repository statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct RepoStats {
     pub pending_reviews: u64,
     pub decided_reviews: u64,
@@ -364,80 +714,3 @@ pub struct RepoStats {
     pub pending_suggestions: u64,
     pub critical_pending: u64,
 }
-
-// Conversion helpers
-fn status_to_str(status: ReviewStatus) -> &'static str {
-    match status {
-        ReviewStatus::Pending => "pending",
-        ReviewStatus::Decided => "decided",
-        ReviewStatus::Applied => "applied",
-        ReviewStatus::Stale => "stale",
-    }
-}
-
-fn str_to_status(s: &str) -> ReviewStatus {
-    match s {
-        "pending" => ReviewStatus::Pending,
-        "decided" => ReviewStatus::Decided,
-        "applied" => ReviewStatus::Applied,
-        "stale" => ReviewStatus::Stale,
-        _ => ReviewStatus::Pending,
-    }
-}
-
-fn suggestion_type_to_str(t: SuggestionType) -> &'static str {
-    match t {
-        SuggestionType::Security => "security",
-        SuggestionType::Performance => "performance",
-        SuggestionType::Style => "style",
-        SuggestionType::Logic => "logic",
-        SuggestionType::Documentation => "documentation",
-    }
-}
-
-fn str_to_suggestion_type(s: &str) -> SuggestionType {
-    match s {
-        "security" => SuggestionType::Security,
-        "performance" => SuggestionType::Performance,
-        "style" => SuggestionType::Style,
-        "logic" => SuggestionType::Logic,
-        "documentation" => SuggestionType::Documentation,
-        _ => SuggestionType::Logic,
-    }
-}
-
-fn severity_to_str(s: Severity) -> &'static str {
-    match s {
-        Severity::Critical => "critical",
-        Severity::High => "high",
-        Severity::Medium => "medium",
-        Severity::Low => "low",
-    }
-}
-
-fn str_to_severity(s: &str) -> Severity {
-    match s {
-        "critical" => Severity::Critical,
-        "high" => Severity::High,
-        "medium" => Severity::Medium,
-        "low" => Severity::Low,
-        _ => Severity::Low,
-    }
-}
-
-fn decision_to_str(d: HumanDecision) -> &'static str {
-    match d {
-        HumanDecision::Accepted => "accepted",
-        HumanDecision::Rejected => "rejected",
-        HumanDecision::Deferred => "deferred",
-    }
-}
-
-fn str_to_decision(s: &str) -> HumanDecision {
-    match s {
-        "accepted" => HumanDecision::Accepted,
-        "rejected" => HumanDecision::Rejected,
-        "deferred" => HumanDecision::Deferred,
-        _ => HumanDecision::Deferred,
-    }
-}
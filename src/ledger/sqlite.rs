@@ -0,0 +1,912 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use super::convert::{
+    decision_to_str, severity_to_str, status_to_str, str_to_decision, str_to_severity,
+    str_to_status, str_to_suggestion_type, suggestion_type_to_str,
+};
+use super::{Ledger, RepoStats};
+use crate::models::{
+    CachedFile, Consensus, DecisionEvent, DecisionRecord, LlmInteraction, Location, OutputQuality,
+    Review, Suggestion, SuggestionItem,
+};
+
+/// Embedded migrations, applied in order and tracked with `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    include_str!("../../migrations/sqlite/001_initial_schema.sql"),
+    include_str!("../../migrations/sqlite/002_consensus.sql"),
+    include_str!("../../migrations/sqlite/003_output_quality.sql"),
+    include_str!("../../migrations/sqlite/004_llm_interactions.sql"),
+    include_str!("../../migrations/sqlite/005_review_cache.sql"),
+    include_str!("../../migrations/sqlite/006_resolved_in.sql"),
+    include_str!("../../migrations/sqlite/007_prompt_hash.sql"),
+    include_str!("../../migrations/sqlite/008_custom_categories.sql"),
+    include_str!("../../migrations/sqlite/009_confidence.sql"),
+    include_str!("../../migrations/sqlite/010_replacements.sql"),
+    include_str!("../../migrations/sqlite/011_owners.sql"),
+    include_str!("../../migrations/sqlite/012_decision_events.sql"),
+];
+
+const REVIEW_COLUMNS: &str = "id, pr_number, repo, branch, commit_sha, created_at, status, \
+     previous_review_id, coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, \
+     prompt_hash";
+
+const INTERACTION_COLUMNS: &str = "id, review_id, provider, model, created_at, latency_ms, \
+     request, response, prompt_tokens, completion_tokens, cost_usd";
+
+/// SQLite-backed ledger for small repos that don't need a database server
+pub struct SqliteLedger {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteLedger {
+    /// Open (or create) a SQLite ledger file and run pending migrations
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create ledger directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open SQLite ledger: {}", path.display()))?;
+
+        info!(path = %path.display(), "Opened SQLite ledger");
+
+        Self::from_connection(conn)
+    }
+
+    /// Open a throwaway in-memory ledger
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        // Wait for concurrent writers instead of failing immediately
+        conn.busy_timeout(std::time::Duration::from_secs(10))?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        let ledger = Self {
+            conn: Mutex::new(conn),
+        };
+        ledger.migrate()?;
+
+        Ok(ledger)
+    }
+
+    /// Run database migrations
+    fn migrate(&self) -> Result<()> {
+        let mut conn = self.lock();
+        let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        for (i, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(sql)
+                .with_context(|| format!("Failed to run SQLite migration {}", i + 1))?;
+            tx.pragma_update(None, "user_version", i + 1)?;
+            tx.commit()?;
+
+            info!(version = i + 1, "Applied SQLite migration");
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get statistics for a repository
+    pub fn get_stats(&self, repo: &str) -> Result<RepoStats> {
+        let conn = self.lock();
+
+        let (pending_reviews, decided_reviews, total_reviews) = conn.query_row(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE status = 'pending'),
+                COUNT(*) FILTER (WHERE status = 'decided'),
+                COUNT(*)
+            FROM reviews
+            WHERE repo = ?1
+            "#,
+            params![repo],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            },
+        )?;
+
+        let (pending_suggestions, critical_pending) = conn.query_row(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE s.human_decision IS NULL),
+                COUNT(*) FILTER (WHERE s.severity = 'critical' AND s.human_decision IS NULL)
+            FROM suggestions s
+            JOIN reviews r ON s.review_id = r.id
+            WHERE r.repo = ?1 AND r.status = 'pending'
+            "#,
+            params![repo],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+        )?;
+
+        Ok(RepoStats {
+            pending_reviews: pending_reviews as u64,
+            decided_reviews: decided_reviews as u64,
+            total_reviews: total_reviews as u64,
+            pending_suggestions: pending_suggestions as u64,
+            critical_pending: critical_pending as u64,
+        })
+    }
+
+    /// Run a review query and load the suggestions for every row
+    fn query_reviews(
+        &self,
+        where_clause: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<Review>> {
+        let conn = self.lock();
+        let sql = format!("SELECT {} FROM reviews {}", REVIEW_COLUMNS, where_clause);
+
+        let mut stmt = conn.prepare(&sql)?;
+        let mut reviews = stmt
+            .query_map(params, review_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        for review in &mut reviews {
+            review.suggestions = load_suggestions(&conn, &review.id)?;
+        }
+
+        Ok(reviews)
+    }
+
+    fn query_interactions(
+        &self,
+        where_clause: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<LlmInteraction>> {
+        let conn = self.lock();
+        let sql = format!(
+            "SELECT {} FROM llm_interactions {} ORDER BY created_at ASC",
+            INTERACTION_COLUMNS, where_clause
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let interactions = stmt
+            .query_map(params, interaction_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(interactions)
+    }
+}
+
+impl Ledger for SqliteLedger {
+    fn save(&self, review: &Review) -> Result<()> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+
+        let events = review.decision_changes(&load_suggestions(&tx, &review.id)?);
+
+        // Upsert the review
+        tx.execute(
+            r#"
+            INSERT INTO reviews (
+                id, pr_number, repo, branch, commit_sha, created_at, status, previous_review_id,
+                coerced_fields, dropped_fields, dropped_suggestions, repair_rounds, prompt_hash
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            ON CONFLICT (id) DO UPDATE SET
+                status = excluded.status,
+                previous_review_id = excluded.previous_review_id
+            "#,
+            params![
+                review.id.to_string(),
+                review.pr_number.map(|n| n as i64),
+                review.repo,
+                review.branch,
+                review.commit_sha,
+                timestamp_to_str(&review.created_at),
+                status_to_str(review.status),
+                review.previous_review_id.map(|id| id.to_string()),
+                review.output_quality.coerced_fields,
+                review.output_quality.dropped_fields,
+                review.output_quality.dropped_suggestions,
+                review.output_quality.repair_rounds,
+                review.prompt_hash,
+            ],
+        )
+        .context("Failed to save review")?;
+
+        // Delete existing suggestions (will be re-inserted)
+        tx.execute(
+            "DELETE FROM suggestions WHERE review_id = ?1",
+            params![review.id.to_string()],
+        )?;
+
+        for item in &review.suggestions {
+            let s = &item.suggestion;
+            let d = item.decision.as_ref();
+
+            tx.execute(
+                r#"
+                INSERT INTO suggestions (
+                    review_id, external_id, suggestion_type, severity,
+                    file_path, line_start, line_end, description, proposed_fix,
+                    human_decision, human_reason, decided_by, decided_at, deferred_until,
+                    flagged_by, ensemble_size, resolved_in, tags, confidence, replacement, applied_at,
+                    owners, decision_verified
+                ) VALUES (
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+                    ?19, ?20, ?21, ?22, ?23
+                )
+                "#,
+                params![
+                    review.id.to_string(),
+                    s.id,
+                    suggestion_type_to_str(&s.suggestion_type),
+                    severity_to_str(s.severity),
+                    s.location.file,
+                    s.location.line_start,
+                    s.location.line_end,
+                    s.description,
+                    s.proposed_fix,
+                    d.map(|d| decision_to_str(d.decision)),
+                    d.and_then(|d| d.reason.as_ref()),
+                    d.map(|d| &d.decided_by),
+                    d.map(|d| timestamp_to_str(&d.decided_at)),
+                    d.and_then(|d| d.deferred_until)
+                        .map(|date| date.to_string()),
+                    s.consensus
+                        .as_ref()
+                        .map(|c| serde_json::to_string(&c.flagged_by))
+                        .transpose()?,
+                    s.consensus.as_ref().map(|c| c.reviewers),
+                    item.resolved_in,
+                    serde_json::to_string(&s.tags)?,
+                    s.confidence,
+                    s.replacement
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()?,
+                    item.applied_at.as_ref().map(timestamp_to_str),
+                    serde_json::to_string(&item.owners)?,
+                    d.is_some_and(|d| d.verified),
+                ],
+            )
+            .context("Failed to save suggestion")?;
+        }
+
+        for event in &events {
+            tx.execute(
+                r#"
+                INSERT INTO decision_events (review_id, suggestion_id, decision, previous, recorded_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+                params![
+                    event.review_id.to_string(),
+                    event.suggestion_id,
+                    event.decision.as_ref().map(serde_json::to_string).transpose()?,
+                    event.previous.as_ref().map(serde_json::to_string).transpose()?,
+                    timestamp_to_str(&event.recorded_at),
+                ],
+            )
+            .context("Failed to record decision event")?;
+        }
+
+        tx.commit()?;
+
+        debug!(id = %review.id, "Saved review to SQLite ledger");
+
+        Ok(())
+    }
+
+    fn load(&self, id: &Uuid) -> Result<Option<Review>> {
+        Ok(self
+            .query_reviews("WHERE id = ?1", params![id.to_string()])?
+            .pop())
+    }
+
+    fn load_by_pr(&self, repo: &str, pr_number: u64) -> Result<Option<Review>> {
+        Ok(self.list_by_pr(repo, pr_number)?.pop())
+    }
+
+    fn list_by_pr(&self, repo: &str, pr_number: u64) -> Result<Vec<Review>> {
+        self.query_reviews(
+            "WHERE repo = ?1 AND pr_number = ?2 ORDER BY created_at ASC",
+            params![repo, pr_number as i64],
+        )
+    }
+
+    fn load_by_commit(&self, repo: &str, commit_sha: &str) -> Result<Option<Review>> {
+        Ok(self
+            .query_reviews(
+                "WHERE repo = ?1 AND commit_sha = ?2 ORDER BY created_at DESC LIMIT 1",
+                params![repo, commit_sha],
+            )?
+            .pop())
+    }
+
+    fn list_by_commit_prefix(&self, repo: Option<&str>, prefix: &str) -> Result<Vec<Review>> {
+        self.query_reviews(
+            "WHERE (?1 IS NULL OR repo = ?1) AND substr(commit_sha, 1, length(?2)) = ?2 \
+             ORDER BY created_at DESC",
+            params![repo, prefix],
+        )
+    }
+
+    fn list_pending(&self) -> Result<Vec<Review>> {
+        self.query_reviews("WHERE status = 'pending' ORDER BY created_at DESC", [])
+    }
+
+    fn list_by_repo(&self, repo: &str) -> Result<Vec<Review>> {
+        self.query_reviews("WHERE repo = ?1 ORDER BY created_at DESC", params![repo])
+    }
+
+    fn list_all(&self) -> Result<Vec<Review>> {
+        self.query_reviews("ORDER BY created_at ASC", [])
+    }
+
+    fn save_interactions(&self, review_id: &Uuid, interactions: &[LlmInteraction]) -> Result<()> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM llm_interactions WHERE review_id = ?1",
+            params![review_id.to_string()],
+        )?;
+
+        for i in interactions {
+            tx.execute(
+                r#"
+                INSERT INTO llm_interactions (
+                    id, review_id, provider, model, created_at, latency_ms,
+                    request, response, prompt_tokens, completion_tokens, cost_usd
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                "#,
+                params![
+                    i.id.to_string(),
+                    review_id.to_string(),
+                    i.provider,
+                    i.model,
+                    timestamp_to_str(&i.created_at),
+                    i.latency_ms as i64,
+                    serde_json::to_string(&i.request)?,
+                    serde_json::to_string(&i.response)?,
+                    i.prompt_tokens,
+                    i.completion_tokens,
+                    i.cost_usd,
+                ],
+            )
+            .context("Failed to save LLM interaction")?;
+        }
+
+        tx.commit()?;
+
+        debug!(id = %review_id, count = interactions.len(), "Saved LLM interactions");
+
+        Ok(())
+    }
+
+    fn load_interactions(&self, review_id: &Uuid) -> Result<Vec<LlmInteraction>> {
+        self.query_interactions("WHERE review_id = ?1", params![review_id.to_string()])
+    }
+
+    fn load_decision_events(&self, review_id: &Uuid) -> Result<Vec<DecisionEvent>> {
+        let conn = self.lock();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT suggestion_id, decision, previous, recorded_at
+            FROM decision_events
+            WHERE review_id = ?1
+            ORDER BY id
+            "#,
+        )?;
+
+        let rows = stmt
+            .query_map(params![review_id.to_string()], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    parse_timestamp(&row.get::<_, String>(3)?)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(suggestion_id, decision, previous, recorded_at)| {
+                Ok(DecisionEvent {
+                    review_id: *review_id,
+                    suggestion_id,
+                    decision: decision.map(|d| serde_json::from_str(&d)).transpose()?,
+                    previous: previous.map(|d| serde_json::from_str(&d)).transpose()?,
+                    recorded_at,
+                })
+            })
+            .collect()
+    }
+
+    fn list_interactions(&self) -> Result<Vec<LlmInteraction>> {
+        self.query_interactions("", [])
+    }
+
+    fn load_cached(&self, key: &str) -> Result<Option<CachedFile>> {
+        let conn = self.lock();
+        let row = conn
+            .query_row(
+                "SELECT key, file_path, suggestions, created_at FROM review_cache WHERE key = ?1",
+                params![key],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        let Some((key, file, suggestions, created_at)) = row else {
+            return Ok(None);
+        };
+
+        Ok(Some(CachedFile {
+            key,
+            file,
+            suggestions: serde_json::from_str(&suggestions)
+                .context("Failed to parse cached suggestions")?,
+            created_at: parse_timestamp(&created_at)?,
+        }))
+    }
+
+    fn save_cached(&self, entry: &CachedFile) -> Result<()> {
+        self.lock()
+            .execute(
+                r#"
+                INSERT INTO review_cache (key, file_path, suggestions, created_at)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (key) DO UPDATE SET
+                    file_path = excluded.file_path,
+                    suggestions = excluded.suggestions,
+                    created_at = excluded.created_at
+                "#,
+                params![
+                    entry.key,
+                    entry.file,
+                    serde_json::to_string(&entry.suggestions)?,
+                    timestamp_to_str(&entry.created_at),
+                ],
+            )
+            .context("Failed to save cache entry")?;
+
+        Ok(())
+    }
+}
+
+fn review_from_row(row: &Row) -> rusqlite::Result<Review> {
+    let id: String = row.get("id")?;
+    let created_at: String = row.get("created_at")?;
+    let status: String = row.get("status")?;
+    let previous_review_id: Option<String> = row.get("previous_review_id")?;
+
+    Ok(Review {
+        id: parse_uuid(&id)?,
+        pr_number: row.get::<_, Option<i64>>("pr_number")?.map(|n| n as u64),
+        repo: row.get("repo")?,
+        branch: row.get("branch")?,
+        commit_sha: row.get("commit_sha")?,
+        created_at: parse_timestamp(&created_at)?,
+        status: str_to_status(&status),
+        suggestions: Vec::new(),
+        previous_review_id: previous_review_id.as_deref().map(parse_uuid).transpose()?,
+        output_quality: OutputQuality {
+            coerced_fields: row.get("coerced_fields")?,
+            dropped_fields: row.get("dropped_fields")?,
+            dropped_suggestions: row.get("dropped_suggestions")?,
+            repair_rounds: row.get("repair_rounds")?,
+        },
+        prompt_hash: row.get("prompt_hash")?,
+    })
+}
+
+fn interaction_from_row(row: &Row) -> rusqlite::Result<LlmInteraction> {
+    let id: String = row.get("id")?;
+    let review_id: String = row.get("review_id")?;
+    let created_at: String = row.get("created_at")?;
+
+    Ok(LlmInteraction {
+        id: parse_uuid(&id)?,
+        review_id: Some(parse_uuid(&review_id)?),
+        provider: row.get("provider")?,
+        model: row.get("model")?,
+        created_at: parse_timestamp(&created_at)?,
+        latency_ms: row.get::<_, i64>("latency_ms")? as u64,
+        request: parse_json(&row.get::<_, String>("request")?)?,
+        response: parse_json(&row.get::<_, String>("response")?)?,
+        prompt_tokens: row.get("prompt_tokens")?,
+        completion_tokens: row.get("completion_tokens")?,
+        cost_usd: row.get("cost_usd")?,
+    })
+}
+
+fn load_suggestions(conn: &Connection, review_id: &Uuid) -> Result<Vec<SuggestionItem>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT
+            external_id, suggestion_type, severity, file_path, line_start, line_end,
+            description, proposed_fix, human_decision, human_reason, decided_by, decided_at,
+            deferred_until, flagged_by, ensemble_size, resolved_in, tags, confidence, replacement,
+            applied_at, owners, decision_verified
+        FROM suggestions
+        WHERE review_id = ?1
+        ORDER BY external_id
+        "#,
+    )?;
+
+    let items = stmt
+        .query_map(params![review_id.to_string()], |srow| {
+            let suggestion = Suggestion {
+                id: srow.get("external_id")?,
+                suggestion_type: str_to_suggestion_type(&srow.get::<_, String>("suggestion_type")?),
+                severity: str_to_severity(&srow.get::<_, String>("severity")?),
+                location: Location {
+                    file: srow.get("file_path")?,
+                    line_start: srow.get("line_start")?,
+                    line_end: srow.get("line_end")?,
+                },
+                description: srow.get("description")?,
+                proposed_fix: srow.get("proposed_fix")?,
+                consensus: consensus_from_row(srow)?,
+                tags: serde_json::from_value(parse_json(&srow.get::<_, String>("tags")?)?)
+                    .unwrap_or_default(),
+                confidence: srow.get("confidence")?,
+                replacement: srow
+                    .get::<_, Option<String>>("replacement")?
+                    .map(|json| parse_json(&json))
+                    .transpose()?
+                    .and_then(|value| serde_json::from_value(value).ok()),
+            };
+
+            let decision = match srow.get::<_, Option<String>>("human_decision")? {
+                Some(dec) => {
+                    let decided_at: Option<String> = srow.get("decided_at")?;
+                    let deferred_until: Option<String> = srow.get("deferred_until")?;
+                    Some(DecisionRecord {
+                        suggestion_id: suggestion.id.clone(),
+                        decision: str_to_decision(&dec),
+                        reason: srow.get("human_reason")?,
+                        decided_by: srow
+                            .get::<_, Option<String>>("decided_by")?
+                            .unwrap_or_default(),
+                        decided_at: match decided_at {
+                            Some(ts) => parse_timestamp(&ts)?,
+                            None => Utc::now(),
+                        },
+                        deferred_until: deferred_until
+                            .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+                        verified: srow.get("decision_verified")?,
+                    })
+                }
+                None => None,
+            };
+
+            Ok(SuggestionItem {
+                suggestion,
+                decision,
+                resolved_in: srow.get("resolved_in")?,
+                applied_at: srow
+                    .get::<_, Option<String>>("applied_at")?
+                    .map(|ts| parse_timestamp(&ts))
+                    .transpose()?,
+                owners: serde_json::from_value(parse_json(&srow.get::<_, String>("owners")?)?)
+                    .unwrap_or_default(),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(items)
+}
+
+fn consensus_from_row(row: &Row) -> rusqlite::Result<Option<Consensus>> {
+    let Some(reviewers) = row.get::<_, Option<u32>>("ensemble_size")? else {
+        return Ok(None);
+    };
+    let flagged_by: Option<String> = row.get("flagged_by")?;
+    let flagged_by = match flagged_by {
+        Some(json) => serde_json::from_str(&json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        None => Vec::new(),
+    };
+
+    Ok(Some(Consensus {
+        flagged_by,
+        reviewers,
+    }))
+}
+
+/// Fixed-width RFC 3339 so timestamps sort correctly as text
+fn timestamp_to_str(ts: &DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn parse_timestamp(s: &str) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })
+}
+
+fn parse_json(s: &str) -> rusqlite::Result<serde_json::Value> {
+    serde_json::from_str(s).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn parse_uuid(s: &str) -> rusqlite::Result<Uuid> {
+    Uuid::parse_str(s).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        HumanDecision, Replacement, ReviewContext, ReviewStatus, Severity, SuggestionType,
+    };
+    use tempfile::tempdir;
+
+    fn make_review(pr: Option<u64>, sha: &str) -> Review {
+        let mut review = Review::new(ReviewContext {
+            pr_number: pr,
+            repo: "owner/repo".to_string(),
+            branch: Some("feature".to_string()),
+            commit_sha: sha.to_string(),
+            base_sha: None,
+        });
+
+        let mut item = SuggestionItem::test("S001", "src/main.rs")
+            .with_type(SuggestionType::Security)
+            .with_severity(Severity::Critical)
+            .with_lines(10, 15)
+            .with_description("SQL injection");
+        item.suggestion.proposed_fix = Some("Use parameters".to_string());
+        review.suggestions.push(item);
+
+        review
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempdir().unwrap();
+        let ledger = SqliteLedger::open(dir.path().join("ledger.db")).unwrap();
+
+        let mut review = make_review(Some(12), "abc123");
+        review
+            .record_decision(DecisionRecord {
+                suggestion_id: "S001".to_string(),
+                decision: HumanDecision::Deferred,
+                reason: Some("Next sprint".to_string()),
+                decided_by: "alice".to_string(),
+                decided_at: Utc::now(),
+                deferred_until: NaiveDate::from_ymd_opt(2025, 3, 1),
+                verified: false,
+            })
+            .unwrap();
+        review.suggestions[0].suggestion.consensus = Some(Consensus {
+            flagged_by: vec!["gpt".to_string(), "claude".to_string()],
+            reviewers: 3,
+        });
+        review.output_quality.coerced_fields = 2;
+        review.output_quality.repair_rounds = 1;
+        let mut custom = review.suggestions[0].clone();
+        custom.suggestion.id = "S002".to_string();
+        custom.suggestion.suggestion_type = SuggestionType::Custom("api-compat".to_string());
+        custom.suggestion.tags = vec!["breaking".to_string(), "public-api".to_string()];
+        custom.suggestion.confidence = Some(0.75);
+        custom.suggestion.replacement = Some(Replacement {
+            line_start: 3,
+            line_end: 4,
+            old_text: "pub fn get(id: u32)".to_string(),
+            new_text: "pub fn get(id: u64)".to_string(),
+        });
+        custom.applied_at = Some(review.created_at);
+        custom.owners = vec!["@alice".to_string(), "@org/web".to_string()];
+        custom.decision = None;
+        review.suggestions.push(custom);
+        ledger.save(&review).unwrap();
+
+        // Re-open to make sure the data is on disk and migrations are idempotent
+        drop(ledger);
+        let ledger = SqliteLedger::open(dir.path().join("ledger.db")).unwrap();
+
+        let loaded = ledger.load(&review.id).unwrap().unwrap();
+        assert_eq!(loaded.pr_number, Some(12));
+        assert_eq!(loaded.status, ReviewStatus::Decided);
+        // Stored with microsecond precision, like PostgreSQL
+        assert_eq!(
+            loaded.created_at.timestamp_micros(),
+            review.created_at.timestamp_micros()
+        );
+        let decision = loaded.suggestions[0].decision.as_ref().unwrap();
+        assert_eq!(decision.decision, HumanDecision::Deferred);
+        assert_eq!(decision.decided_by, "alice");
+        assert_eq!(decision.deferred_until, NaiveDate::from_ymd_opt(2025, 3, 1));
+        assert_eq!(
+            loaded.suggestions[0].suggestion.consensus,
+            review.suggestions[0].suggestion.consensus
+        );
+        assert_eq!(loaded.output_quality, review.output_quality);
+        assert_eq!(
+            loaded.suggestions[1].suggestion.suggestion_type,
+            SuggestionType::Custom("api-compat".to_string())
+        );
+        assert_eq!(
+            loaded.suggestions[1].suggestion.tags,
+            ["breaking", "public-api"]
+        );
+        assert!(loaded.suggestions[0].suggestion.tags.is_empty());
+        assert_eq!(loaded.suggestions[1].suggestion.confidence, Some(0.75));
+        assert_eq!(loaded.suggestions[0].suggestion.confidence, None);
+        assert_eq!(
+            loaded.suggestions[1].suggestion.replacement,
+            review.suggestions[1].suggestion.replacement
+        );
+        assert_eq!(
+            loaded.suggestions[1]
+                .applied_at
+                .map(|ts| ts.timestamp_micros()),
+            Some(review.created_at.timestamp_micros())
+        );
+        assert!(loaded.suggestions[0].applied_at.is_none());
+        assert_eq!(loaded.suggestions[1].owners, ["@alice", "@org/web"]);
+        assert!(loaded.suggestions[0].owners.is_empty());
+    }
+
+    #[test]
+    fn test_queries() {
+        let ledger = SqliteLedger::open_in_memory().unwrap();
+
+        let mut first = make_review(Some(5), "aaa111");
+        first.created_at -= chrono::Duration::hours(1);
+        let mut second = make_review(Some(5), "aab222");
+        second.previous_review_id = Some(first.id);
+        let commit = make_review(None, "ccc333");
+
+        ledger.save(&first).unwrap();
+        ledger.save(&second).unwrap();
+        ledger.save(&commit).unwrap();
+
+        assert_eq!(
+            ledger.load_by_pr("owner/repo", 5).unwrap().unwrap().id,
+            second.id
+        );
+        let history = ledger.list_by_pr("owner/repo", 5).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].id, first.id);
+        assert_eq!(history[1].previous_review_id, Some(first.id));
+
+        let loaded = ledger
+            .load_by_commit("owner/repo", "ccc333")
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.id, commit.id);
+        assert_eq!(loaded.pr_number, None);
+
+        assert_eq!(ledger.list_by_commit_prefix(None, "aa").unwrap().len(), 2);
+        assert_eq!(
+            ledger
+                .list_by_commit_prefix(Some("other/repo"), "aa")
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(ledger.list_pending().unwrap().len(), 3);
+        assert_eq!(ledger.list_by_repo("owner/repo").unwrap().len(), 3);
+
+        let stats = ledger.get_stats("owner/repo").unwrap();
+        assert_eq!(stats.total_reviews, 3);
+        assert_eq!(stats.pending_suggestions, 3);
+        assert_eq!(stats.critical_pending, 3);
+    }
+
+    #[test]
+    fn test_interactions_round_trip() {
+        let ledger = SqliteLedger::open_in_memory().unwrap();
+        let review = make_review(Some(1), "aaa111");
+        ledger.save(&review).unwrap();
+
+        let interaction = |tokens: u32| LlmInteraction {
+            id: Uuid::new_v4(),
+            review_id: Some(review.id),
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            created_at: Utc::now(),
+            latency_ms: 1500,
+            request: serde_json::json!({ "model": "gpt-4o" }),
+            response: serde_json::json!({ "choices": [] }),
+            prompt_tokens: Some(tokens),
+            completion_tokens: None,
+            cost_usd: Some(0.01),
+        };
+        ledger
+            .save_interactions(&review.id, &[interaction(100), interaction(200)])
+            .unwrap();
+
+        let loaded = ledger.load_interactions(&review.id).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].review_id, Some(review.id));
+        assert_eq!(loaded[0].prompt_tokens, Some(100));
+        assert_eq!(loaded[0].completion_tokens, None);
+        assert_eq!(loaded[1].request["model"], "gpt-4o");
+
+        // Saving again replaces the previous log
+        ledger
+            .save_interactions(&review.id, &[interaction(300)])
+            .unwrap();
+        assert_eq!(ledger.list_interactions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_decision_events() {
+        let ledger = SqliteLedger::open_in_memory().unwrap();
+        let mut review = make_review(Some(1), "aaa111");
+        ledger.save(&review).unwrap();
+
+        let decide = |decision, by: &str| DecisionRecord {
+            suggestion_id: "S001".to_string(),
+            decision,
+            reason: None,
+            decided_by: by.to_string(),
+            decided_at: Utc::now(),
+            deferred_until: None,
+            verified: true,
+        };
+        review
+            .record_decision(decide(HumanDecision::Rejected, "alice"))
+            .unwrap();
+        ledger.save(&review).unwrap();
+        // Saving an unchanged review records nothing new
+        ledger.save(&review).unwrap();
+        review
+            .record_decision(decide(HumanDecision::Accepted, "bob"))
+            .unwrap();
+        ledger.save(&review).unwrap();
+
+        let events = ledger.load_decision_events(&review.id).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].previous.is_none());
+        assert_eq!(events[0].decision.as_ref().unwrap().decided_by, "alice");
+        assert!(events[0].decision.as_ref().unwrap().verified);
+        assert_eq!(
+            events[1].previous.as_ref().unwrap().decision,
+            HumanDecision::Rejected
+        );
+        assert_eq!(
+            events[1].decision.as_ref().unwrap().decision,
+            HumanDecision::Accepted
+        );
+
+        let loaded = ledger.load(&review.id).unwrap().unwrap();
+        assert!(loaded.suggestions[0].decision.as_ref().unwrap().verified);
+
+        // The history is append-only
+        let conn = ledger.conn.lock().unwrap();
+        assert!(conn.execute("DELETE FROM decision_events", []).is_err());
+        assert!(conn
+            .execute("UPDATE decision_events SET suggestion_id = 'S002'", [])
+            .is_err());
+    }
+}
@@ -0,0 +1,302 @@
+use std::io::{BufRead, Write};
+
+use anyhow::{Context, Result};
+use chrono::SubsecRound;
+use uuid::Uuid;
+
+use super::Ledger;
+use crate::models::{DecisionRecord, Review, ReviewStatus};
+
+/// A review or decision that differs between source and destination
+///
+/// The destination copy is always kept; conflicts are reported for a human to resolve.
+#[derive(Debug, Clone)]
+pub struct TransferConflict {
+    pub review_id: Uuid,
+    pub suggestion_id: Option<String>,
+    pub detail: String,
+}
+
+/// Outcome of moving reviews into a ledger
+#[derive(Debug, Default)]
+pub struct TransferReport {
+    /// Reviews that did not exist in the destination
+    pub imported: usize,
+    /// Existing reviews that gained decisions or status from the source
+    pub updated: usize,
+    /// Existing reviews that were already up to date
+    pub unchanged: usize,
+    pub conflicts: Vec<TransferConflict>,
+}
+
+impl TransferReport {
+    /// Merge an incoming review into the destination's copy
+    ///
+    /// Returns the review to save, or `None` if the destination is already up to date.
+    /// Decisions only flow into suggestions that are undecided in the destination, so
+    /// re-running a transfer never overwrites anything.
+    pub fn merge(&mut self, existing: Option<Review>, incoming: Review) -> Option<Review> {
+        let Some(mut merged) = existing else {
+            self.imported += 1;
+            return Some(incoming);
+        };
+
+        let mut ids: Vec<&str> = merged
+            .suggestions
+            .iter()
+            .map(|s| s.suggestion.id.as_str())
+            .collect();
+        let mut incoming_ids: Vec<&str> = incoming
+            .suggestions
+            .iter()
+            .map(|s| s.suggestion.id.as_str())
+            .collect();
+        ids.sort_unstable();
+        incoming_ids.sort_unstable();
+
+        if ids != incoming_ids {
+            self.conflicts.push(TransferConflict {
+                review_id: incoming.id,
+                suggestion_id: None,
+                detail: "reviews have different suggestions".to_string(),
+            });
+            return None;
+        }
+
+        let mut changed = false;
+
+        for item in &mut merged.suggestions {
+            let Some(theirs) = incoming
+                .suggestions
+                .iter()
+                .find(|s| s.suggestion.id == item.suggestion.id)
+                .and_then(|s| s.decision.as_ref())
+            else {
+                continue;
+            };
+
+            match &item.decision {
+                None => {
+                    item.decision = Some(theirs.clone());
+                    changed = true;
+                }
+                Some(ours) if !same_decision(ours, theirs) => {
+                    self.conflicts.push(TransferConflict {
+                        review_id: incoming.id,
+                        suggestion_id: Some(item.suggestion.id.clone()),
+                        detail: format!(
+                            "{:?} by {} in destination, {:?} by {} in source",
+                            ours.decision, ours.decided_by, theirs.decision, theirs.decided_by
+                        ),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        if merged.status == ReviewStatus::Pending && incoming.status != ReviewStatus::Pending {
+            merged.status = incoming.status;
+            changed = true;
+        }
+
+        if merged.previous_review_id.is_none() && incoming.previous_review_id.is_some() {
+            merged.previous_review_id = incoming.previous_review_id;
+            changed = true;
+        }
+
+        if changed {
+            self.updated += 1;
+            Some(merged)
+        } else {
+            self.unchanged += 1;
+            None
+        }
+    }
+}
+
+/// Compare decisions ignoring sub-microsecond timestamp precision lost by SQL backends
+fn same_decision(a: &DecisionRecord, b: &DecisionRecord) -> bool {
+    a.decision == b.decision
+        && a.reason == b.reason
+        && a.decided_by == b.decided_by
+        && a.deferred_until == b.deferred_until
+        && a.decided_at.trunc_subsecs(6) == b.decided_at.trunc_subsecs(6)
+}
+
+/// Write reviews as JSON Lines, one review per line
+pub fn write_jsonl<W: Write>(reviews: &[Review], mut out: W) -> Result<usize> {
+    for review in reviews {
+        serde_json::to_writer(&mut out, review)?;
+        writeln!(out)?;
+    }
+    out.flush()?;
+
+    Ok(reviews.len())
+}
+
+/// Read reviews from JSON Lines, skipping blank lines
+pub fn read_jsonl<R: BufRead>(input: R) -> Result<Vec<Review>> {
+    let mut reviews = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let review = serde_json::from_str(&line)
+            .with_context(|| format!("Failed to parse review on line {}", i + 1))?;
+        reviews.push(review);
+    }
+
+    Ok(reviews)
+}
+
+/// Merge reviews into a ledger, oldest first so review links resolve
+pub fn import_reviews<L: Ledger>(ledger: &L, mut reviews: Vec<Review>) -> Result<TransferReport> {
+    reviews.sort_by_key(|r| r.created_at);
+
+    let mut report = TransferReport::default();
+    for review in reviews {
+        let existing = ledger.load(&review.id)?;
+        if let Some(review) = report.merge(existing, review) {
+            ledger.save(&review)?;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::{JsonLedger, SqliteLedger};
+    use crate::models::{HumanDecision, ReviewContext, SuggestionItem};
+    use tempfile::tempdir;
+
+    fn make_review(sha: &str) -> Review {
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: sha.to_string(),
+            base_sha: None,
+        });
+
+        for id in ["S001", "S002"] {
+            review.suggestions.push(
+                SuggestionItem::test(id, "src/lib.rs")
+                    .with_lines(1, 2)
+                    .with_description(format!("Finding {}", id)),
+            );
+        }
+
+        review
+    }
+
+    fn decision(id: &str, decision: HumanDecision, by: &str) -> DecisionRecord {
+        DecisionRecord {
+            suggestion_id: id.to_string(),
+            decision,
+            reason: None,
+            decided_by: by.to_string(),
+            decided_at: chrono::Utc::now(),
+            deferred_until: None,
+            verified: false,
+        }
+    }
+
+    #[test]
+    fn test_copy_json_to_sqlite_is_idempotent() {
+        let dir = tempdir().unwrap();
+        let json = JsonLedger::new(dir.path()).unwrap();
+
+        let first = make_review("aaa111");
+        let mut second = make_review("bbb222");
+        second.previous_review_id = Some(first.id);
+        second
+            .record_decision(decision("S001", HumanDecision::Rejected, "alice"))
+            .unwrap();
+        json.save(&first).unwrap();
+        json.save(&second).unwrap();
+
+        let sqlite = SqliteLedger::open_in_memory().unwrap();
+
+        let report = import_reviews(&sqlite, json.list_all().unwrap()).unwrap();
+        assert_eq!(report.imported, 2);
+        assert!(report.conflicts.is_empty());
+
+        let copied = sqlite.load(&second.id).unwrap().unwrap();
+        assert_eq!(copied.previous_review_id, Some(first.id));
+        let record = copied.suggestions[0].decision.as_ref().unwrap();
+        assert_eq!(record.decision, HumanDecision::Rejected);
+        assert_eq!(record.decided_by, "alice");
+
+        let report = import_reviews(&sqlite, json.list_all().unwrap()).unwrap();
+        assert_eq!(report.imported, 0);
+        assert_eq!(report.updated, 0);
+        assert_eq!(report.unchanged, 2);
+        assert!(report.conflicts.is_empty());
+
+        // A destination copy that lost its link to the earlier review gets it back
+        let mut unlinked = copied.clone();
+        unlinked.previous_review_id = None;
+        sqlite.save(&unlinked).unwrap();
+        let report = import_reviews(&sqlite, json.list_all().unwrap()).unwrap();
+        assert_eq!(report.updated, 1);
+        let copied = sqlite.load(&second.id).unwrap().unwrap();
+        assert_eq!(copied.previous_review_id, Some(first.id));
+    }
+
+    #[test]
+    fn test_merge_fills_gaps_and_reports_conflicts() {
+        let mut ours = make_review("aaa111");
+        ours.record_decision(decision("S001", HumanDecision::Accepted, "alice"))
+            .unwrap();
+
+        let mut theirs = ours.clone();
+        theirs.suggestions[0].decision = Some(decision("S001", HumanDecision::Rejected, "bob"));
+        theirs.suggestions[1].decision = Some(decision("S002", HumanDecision::Deferred, "bob"));
+
+        let mut report = TransferReport::default();
+        let merged = report.merge(Some(ours), theirs).unwrap();
+
+        assert_eq!(report.updated, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].suggestion_id.as_deref(), Some("S001"));
+        assert_eq!(
+            merged.suggestions[0].decision.as_ref().unwrap().decision,
+            HumanDecision::Accepted
+        );
+        assert_eq!(
+            merged.suggestions[1].decision.as_ref().unwrap().decision,
+            HumanDecision::Deferred
+        );
+    }
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let mut review = make_review("aaa111");
+        review
+            .record_decision(decision("S002", HumanDecision::Accepted, "alice"))
+            .unwrap();
+
+        let mut buf = Vec::new();
+        assert_eq!(
+            write_jsonl(std::slice::from_ref(&review), &mut buf).unwrap(),
+            1
+        );
+
+        let text = String::from_utf8(buf).unwrap();
+        assert_eq!(text.lines().count(), 1);
+
+        let reviews = read_jsonl(format!("\n{}\n", text).as_bytes()).unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].id, review.id);
+        assert_eq!(reviews[0].suggestions[1].suggestion.id, "S002");
+        assert!(reviews[0].suggestions[1].decision.is_some());
+
+        let err = read_jsonl("{not json}\n".as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+}
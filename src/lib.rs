@@ -1,19 +1,49 @@
 pub mod adapters;
+pub mod apply;
+pub mod audit;
+pub mod bulk;
+pub mod cache;
 pub mod config;
+pub mod feedback;
+pub mod fix_pr;
 pub mod github;
 pub mod ledger;
 pub mod models;
 pub mod notifications;
 pub mod orchestrator;
+pub mod owners;
+pub mod policy;
+pub mod reconcile;
+pub mod repos;
 pub mod rules;
+pub mod stats;
 pub mod suppressions;
+pub mod target;
+pub mod triage;
 
-pub use adapters::CodexAdapter;
+pub use adapters::{
+    reviewer_from_config, AnthropicAdapter, CodexAdapter, EnsembleReviewer, ReviewOutput, Reviewer,
+    ReviewerError,
+};
 pub use config::Config;
+pub use feedback::FeedbackReport;
 pub use github::GitHubClient;
-pub use ledger::{JsonLedger, Ledger, PostgresLedger, RepoStats};
+pub use ledger::{
+    import_reviews, read_jsonl, write_jsonl, FsckReport, JsonLedger, Ledger, LedgerLocation,
+    PostgresLedger, RepoStats, SqliteLedger, TransferReport,
+};
 pub use models::*;
 pub use notifications::NotificationService;
-pub use orchestrator::{generate_summary, Orchestrator};
+pub use orchestrator::{generate_pr_comment, generate_summary, Orchestrator};
+pub use owners::Owners;
+pub use policy::{Identity, Permissions};
+pub use reconcile::{
+    parse_decisions, parse_review_id, reconcile_review, review_markdown_path,
+    write_review_markdown, ReconcileReport,
+};
+pub use repos::{RepoConfig, RepoConfigs, RepoPattern};
 pub use rules::RulesEngine;
+pub use stats::ReviewStats;
 pub use suppressions::Rejections;
+pub use target::ReviewTarget;
+pub use triage::{is_interactive_terminal, KeyPrompter, LinePrompter, Triage};
@@ -1,14 +1,26 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
+use ai_review::apply::{apply_fixes, commit_fixes, commit_message};
+use ai_review::audit::format_interactions;
+use ai_review::bulk::{BulkFilter, BulkPlan};
+use ai_review::fix_pr::FixPr;
 use ai_review::{
-    generate_summary, CodexAdapter, GitHubClient, JsonLedger, Ledger,
-    Orchestrator, Rejections, ReviewContext,
+    generate_pr_comment, generate_summary, import_reviews, is_interactive_terminal,
+    parse_decisions, parse_review_id, read_jsonl, reconcile_review, reviewer_from_config,
+    write_jsonl, write_review_markdown, DecisionRecord, FeedbackReport, GitHubClient,
+    HumanDecision, Identity, JsonLedger, KeyPrompter, Ledger, LedgerLocation, LinePrompter,
+    LlmInteraction, Orchestrator, Permissions, PostgresLedger, Rejections, RepoConfig, RepoConfigs,
+    RepoPattern, Review, ReviewContext, ReviewStats, ReviewTarget, Severity, SuggestionItem,
+    SuggestionType, TransferReport, Triage,
 };
 
 #[derive(Parser)]
@@ -18,9 +30,18 @@ struct Cli {
     #[command(subcommand)]
     command: Commands,
 
-    /// Path to ledger directory
-    #[arg(long, default_value = ".ai-review/ledger")]
-    ledger_path: PathBuf,
+    /// Ledger location: a JSON ledger directory or sqlite://<path>
+    #[arg(long, alias = "ledger-path", default_value = ".ai-review/ledger")]
+    ledger: LedgerLocation,
+
+    /// Path to review markdown directory
+    #[arg(long, default_value = "lgtm-reviews")]
+    reviews_dir: PathBuf,
+
+    /// Read .ai-review/config.yml and CODEOWNERS of each repository through the GitHub API
+    /// instead of the working directory, for a ledger shared by many repositories
+    #[arg(long, global = true)]
+    repo_config: bool,
 }
 
 #[derive(Subcommand)]
@@ -58,13 +79,43 @@ enum Commands {
         /// Fetch diff from GitHub API instead of file
         #[arg(long)]
         fetch_diff: bool,
+
+        /// Send every file to the model, ignoring findings cached for identical file diffs
+        #[arg(long)]
+        no_cache: bool,
     },
 
-    /// List pending reviews
-    Pending,
+    /// List pending reviews, grouped by repository
+    Pending {
+        /// Repository (owner/repo) or glob such as acme/*; all repositories if not provided
+        #[arg(long)]
+        repo: Option<RepoPattern>,
+
+        /// Only count suggestions you own (see --user)
+        #[arg(long, conflicts_with = "owner")]
+        mine: bool,
+
+        /// Only count suggestions owned by this user or team
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Your username (default: $USER; the GitHub actor in GitHub Actions)
+        #[arg(long)]
+        user: Option<String>,
+    },
 
     /// Show review details
     Show {
+        /// Review to show: #<pr>, commit SHA (or unique prefix), or review ID
+        target: ReviewTarget,
+
+        /// Repository (owner/repo), required for PR numbers
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// List all reviews of a PR over time
+    History {
         /// PR number
         pr: u64,
 
@@ -73,35 +124,269 @@ enum Commands {
         repo: String,
     },
 
-    /// Make a decision on a suggestion
+    /// Show the LLM requests made for a review, with token usage and cost
+    Audit {
+        /// Review to audit: #<pr>, commit SHA (or unique prefix), or review ID
+        target: ReviewTarget,
+
+        /// Repository (owner/repo), required for PR numbers
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Print the full redacted request and response bodies
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// Make a decision on a suggestion, or on a filtered set of pending suggestions
     Decide {
-        /// PR number
-        pr: u64,
+        /// Review to decide on: #<pr>, commit SHA (or unique prefix), or review ID
+        target: ReviewTarget,
 
-        /// Repository (owner/repo)
+        /// Repository (owner/repo), required for PR numbers
         #[arg(long)]
-        repo: String,
+        repo: Option<String>,
 
         /// Suggestion ID (e.g., S001)
+        #[arg(
+            long,
+            required_unless_present_any = ["all", "accept_all_recommended", "severity", "suggestion_type", "file"],
+            conflicts_with_all = ["all", "accept_all_recommended", "severity", "suggestion_type", "file"]
+        )]
+        suggestion: Option<String>,
+
+        /// Decide every pending suggestion
+        #[arg(long, conflicts_with_all = ["accept_all_recommended", "severity", "suggestion_type", "file"])]
+        all: bool,
+
+        /// Accept every pending suggestion the reviewers are confident about: one that meets
+        /// review.min_confidence and, in an ensemble, was reported by most reviewers
+        #[arg(long, conflicts_with_all = ["accept", "reject", "defer"])]
+        accept_all_recommended: bool,
+
+        /// Only decide pending suggestions with this severity
         #[arg(long)]
-        suggestion: String,
+        severity: Option<Severity>,
+
+        /// Only decide pending suggestions of this type
+        #[arg(long = "type")]
+        suggestion_type: Option<SuggestionType>,
+
+        /// Only decide pending suggestions in files matching this glob
+        #[arg(long)]
+        file: Option<String>,
 
         /// Accept the suggestion
-        #[arg(long, conflicts_with = "reject")]
+        #[arg(long, conflicts_with_all = ["reject", "defer"])]
         accept: bool,
 
         /// Reject the suggestion
-        #[arg(long, conflicts_with = "accept")]
+        #[arg(long, conflicts_with_all = ["accept", "defer"])]
         reject: bool,
 
+        /// Defer the suggestion
+        #[arg(long, conflicts_with_all = ["accept", "reject"])]
+        defer: bool,
+
+        /// Date to defer until (YYYY-MM-DD)
+        #[arg(long, requires = "defer")]
+        until: Option<NaiveDate>,
+
         /// Reason for decision
         #[arg(long)]
         reason: Option<String>,
 
-        /// Your username
-        #[arg(long, env = "USER")]
-        user: String,
+        /// Your username (default: $USER; the GitHub actor in GitHub Actions)
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Record bulk decisions without stopping at the preview
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Interactively triage pending suggestions
+    Triage {
+        /// Repository (owner/repo) or glob such as acme/*
+        #[arg(long)]
+        repo: Option<RepoPattern>,
+
+        /// PR number
+        #[arg(long, requires = "repo")]
+        pr: Option<u64>,
+
+        /// Your username (default: $USER; the GitHub actor in GitHub Actions)
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Only triage suggestions you own
+        #[arg(long, conflicts_with = "owner")]
+        mine: bool,
+
+        /// Only triage suggestions owned by this user or team
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Use line-based prompts even on a capable terminal
+        #[arg(long)]
+        plain: bool,
+    },
+
+    /// Apply the structured fixes of accepted suggestions to the working tree
+    Apply {
+        /// Review to apply: #<pr>, commit SHA (or unique prefix), or review ID
+        target: ReviewTarget,
+
+        /// Repository (owner/repo), required for PR numbers
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Show which fixes would apply without changing any files
+        #[arg(long, conflicts_with = "commit")]
+        dry_run: bool,
+
+        /// Commit the changed files with git
+        #[arg(long)]
+        commit: bool,
+    },
+
+    /// Push accepted fixes to a branch and open (or update) a pull request for them
+    FixPr {
+        /// Review to fix: #<pr>, commit SHA (or unique prefix), or review ID
+        target: ReviewTarget,
+
+        /// Repository (owner/repo), required for PR numbers
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Branch to open the PR against (default: the reviewed branch, or main)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Git remote to push the fix branch to
+        #[arg(long, default_value = "origin")]
+        remote: String,
+    },
+
+    /// Show acceptance rates, decision times and trends
+    Stats {
+        /// Repository (owner/repo) or glob such as acme/*; all repositories if not provided
+        #[arg(long)]
+        repo: Option<RepoPattern>,
+
+        /// Only include reviews created on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = StatsFormat::Table)]
+        format: StatsFormat,
+    },
+
+    /// Analyse rejected suggestions and suggest config changes
+    Feedback {
+        /// Repository (owner/repo) or glob such as acme/*; all repositories if not provided
+        #[arg(long)]
+        repo: Option<RepoPattern>,
+
+        /// Only include reviews created on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Minimum accept/reject decisions before a category is considered
+        #[arg(long, default_value_t = 5)]
+        min_decisions: u64,
+
+        /// Rejection rate at which a category is flagged
+        #[arg(long, default_value_t = 0.7)]
+        threshold: f64,
+
+        /// Write the suggested config patch to this file
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import hand-edited decisions from review markdown into the ledger
+    Reconcile {
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: String,
+    },
+
+    /// Ledger maintenance
+    Ledger {
+        #[command(subcommand)]
+        command: LedgerCommands,
+    },
+
+    /// Decision history
+    Decisions {
+        #[command(subcommand)]
+        command: DecisionsCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum DecisionsCommands {
+    /// Show every decision change recorded for a review, oldest first
+    Log {
+        /// Review to show: #<pr>, commit SHA (or unique prefix), or review ID
+        target: ReviewTarget,
+
+        /// Repository (owner/repo), required for PR numbers
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Only show changes to this suggestion
+        #[arg(long)]
+        suggestion: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum LedgerCommands {
+    /// Rebuild index.json by scanning the review files
+    Fsck,
+
+    /// Write every review in the ledger to a file or stdout
+    Export {
+        /// Export format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+        format: ExportFormat,
+
+        /// Output file (stdout if not provided)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Merge reviews from an export into the ledger
+    Import {
+        /// JSONL file to import ("-" for stdin)
+        input: PathBuf,
     },
+
+    /// Copy every review from one ledger into another
+    Copy {
+        /// Source ledger (json:<path>, sqlite://<path> or postgres://...)
+        #[arg(long)]
+        from: LedgerLocation,
+
+        /// Destination ledger
+        #[arg(long)]
+        to: LedgerLocation,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StatsFormat {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// One JSON review per line
+    Jsonl,
 }
 
 #[tokio::main]
@@ -109,6 +394,8 @@ async fn main() -> Result<()> {
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env().add_directive("ai_review=info".parse()?))
+        // Keep stdout clean for `stats --format json` and `ledger export`
+        .with_writer(std::io::stderr)
         .init();
 
     let cli = Cli::parse();
@@ -123,9 +410,10 @@ async fn main() -> Result<()> {
             output,
             post_comment,
             fetch_diff,
+            no_cache,
         } => {
             run_review(
-                cli.ledger_path,
+                &cli.ledger,
                 pr,
                 repo,
                 sha,
@@ -134,26 +422,199 @@ async fn main() -> Result<()> {
                 output,
                 post_comment,
                 fetch_diff,
+                no_cache,
+                cli.repo_config,
             )
             .await?;
         }
-        Commands::Pending => {
-            list_pending(cli.ledger_path)?;
+        Commands::Pending {
+            repo,
+            mine,
+            owner,
+            user,
+        } => {
+            let owner = if mine {
+                Some(Identity::resolve(user.as_deref())?.user)
+            } else {
+                owner
+            };
+            list_pending(
+                &cli.ledger,
+                repo.as_ref(),
+                owner.as_deref(),
+                cli.repo_config,
+            )
+            .await?;
+        }
+        Commands::Show { target, repo } => {
+            show_review(&cli.ledger, &target, repo.as_deref())?;
         }
-        Commands::Show { pr, repo } => {
-            show_review(cli.ledger_path, &repo, pr)?;
+        Commands::History { pr, repo } => {
+            show_history(&cli.ledger, &repo, pr)?;
+        }
+        Commands::Audit { target, repo, full } => {
+            show_audit(&cli.ledger, &target, repo.as_deref(), full).await?;
         }
         Commands::Decide {
-            pr,
+            target,
             repo,
             suggestion,
+            all,
+            accept_all_recommended,
+            severity,
+            suggestion_type,
+            file,
             accept,
             reject,
+            defer,
+            until,
             reason,
             user,
+            yes,
+        } => {
+            let decision = if accept || accept_all_recommended {
+                HumanDecision::Accepted
+            } else if reject {
+                HumanDecision::Rejected
+            } else if defer {
+                HumanDecision::Deferred
+            } else {
+                anyhow::bail!("Must specify --accept, --reject or --defer");
+            };
+
+            let selection = match suggestion {
+                Some(id) => Selection::Single(id),
+                None => Selection::Bulk(BulkFilter::new(
+                    all,
+                    accept_all_recommended,
+                    severity,
+                    suggestion_type,
+                    file,
+                )?),
+            };
+
+            make_decision(
+                &cli.ledger,
+                &cli.reviews_dir,
+                &target,
+                repo.as_deref(),
+                selection,
+                decision,
+                until,
+                reason,
+                &Identity::resolve(user.as_deref())?,
+                yes,
+                cli.repo_config,
+            )
+            .await?;
+        }
+        Commands::Triage {
+            repo,
+            pr,
+            user,
+            mine,
+            owner,
+            plain,
         } => {
-            make_decision(cli.ledger_path, &repo, pr, &suggestion, accept, reject, reason, &user)?;
+            let identity = Identity::resolve(user.as_deref())?;
+            let owner = if mine {
+                Some(identity.user.clone())
+            } else {
+                owner
+            };
+            triage(
+                &cli.ledger,
+                &cli.reviews_dir,
+                repo.as_ref(),
+                pr,
+                identity,
+                owner,
+                plain,
+                cli.repo_config,
+            )
+            .await?;
+        }
+        Commands::Apply {
+            target,
+            repo,
+            dry_run,
+            commit,
+        } => {
+            apply(
+                &cli.ledger,
+                &cli.reviews_dir,
+                &target,
+                repo.as_deref(),
+                dry_run,
+                commit,
+            )?;
+        }
+        Commands::FixPr {
+            target,
+            repo,
+            base,
+            remote,
+        } => {
+            fix_pr(
+                &cli.ledger,
+                &cli.reviews_dir,
+                &target,
+                repo.as_deref(),
+                base,
+                &remote,
+            )
+            .await?;
+        }
+        Commands::Stats {
+            repo,
+            since,
+            format,
+        } => {
+            show_stats(&cli.ledger, repo.as_ref(), since, format).await?;
+        }
+        Commands::Feedback {
+            repo,
+            since,
+            min_decisions,
+            threshold,
+            output,
+        } => {
+            feedback(
+                &cli.ledger,
+                repo.as_ref(),
+                since,
+                min_decisions,
+                threshold,
+                output,
+            )
+            .await?;
+        }
+        Commands::Reconcile { repo } => {
+            reconcile(&cli.ledger, &cli.reviews_dir, &repo)?;
         }
+        Commands::Ledger { command } => match command {
+            LedgerCommands::Fsck => {
+                ledger_fsck(&cli.ledger)?;
+            }
+            LedgerCommands::Export { format, output } => {
+                ledger_export(&cli.ledger, format, output).await?;
+            }
+            LedgerCommands::Import { input } => {
+                ledger_import(&cli.ledger, &input).await?;
+            }
+            LedgerCommands::Copy { from, to } => {
+                ledger_copy(&from, &to).await?;
+            }
+        },
+        Commands::Decisions { command } => match command {
+            DecisionsCommands::Log {
+                target,
+                repo,
+                suggestion,
+            } => {
+                show_decision_log(&cli.ledger, &target, repo.as_deref(), suggestion.as_deref())?;
+            }
+        },
     }
 
     Ok(())
@@ -161,7 +622,7 @@ async fn main() -> Result<()> {
 
 #[allow(clippy::too_many_arguments)]
 async fn run_review(
-    ledger_path: PathBuf,
+    ledger: &LedgerLocation,
     pr: Option<u64>,
     repo: String,
     sha: String,
@@ -170,6 +631,8 @@ async fn run_review(
     output: PathBuf,
     post_comment: bool,
     fetch_diff: bool,
+    no_cache: bool,
+    repo_config: bool,
 ) -> Result<()> {
     // Validate that PR is provided if post_comment or fetch_diff is requested
     if post_comment && pr.is_none() {
@@ -179,29 +642,44 @@ async fn run_review(
         anyhow::bail!("--fetch-diff requires --pr to be specified");
     }
 
-    let openai_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
-
-    let codex = CodexAdapter::new(openai_key);
-    let ledger = JsonLedger::new(&ledger_path)?;
-
-    // Load rejected findings from previous reviews
-    let rejections = Rejections::load_default()
-        .context("Failed to load rejections")?;
-    info!(count = rejections.items.len(), "Loaded rejected findings");
-
-    let orchestrator = Orchestrator::new(codex, ledger);
-
     // Parse owner/repo
     let (owner, repo_name) = parse_repo(&repo)?;
 
     // Get GitHub client if needed
-    let github = if post_comment || fetch_diff {
+    let github = if post_comment || fetch_diff || repo_config {
         let token = std::env::var("GITHUB_TOKEN").context("GITHUB_TOKEN not set")?;
         Some(GitHubClient::new(&token)?)
     } else {
         None
     };
 
+    let RepoConfig { config, owners } = if repo_config {
+        RepoConfig::fetch(github.as_ref().unwrap(), &repo, &sha).await?
+    } else {
+        RepoConfig::load(Path::new("."))?
+    };
+
+    let reviewer = reviewer_from_config(&config)?;
+    let ledger = ledger.open()?;
+
+    // Load rejected findings from previous reviews; the working directory's review markdown
+    // belongs to another repository when its configuration comes from GitHub
+    let rejections = if repo_config {
+        Rejections::from_reviews(&ledger.list_by_repo(&repo)?)
+    } else {
+        Rejections::load_default().context("Failed to load rejections")?
+    };
+    info!(count = rejections.items.len(), "Loaded rejected findings");
+
+    let mut orchestrator = Orchestrator::new(reviewer, ledger)
+        .with_config(config.clone())
+        .with_owners(owners)
+        .with_cache(!no_cache);
+    // Interdiffs come from the local git history, so there's none for other repositories
+    if !repo_config {
+        orchestrator = orchestrator.with_interdiff(ai_review::github::extract_diff);
+    }
+
     // Read diff
     let diff = if fetch_diff {
         let gh = github.as_ref().unwrap();
@@ -246,7 +724,8 @@ async fn run_review(
     if post_comment {
         let gh = github.as_ref().unwrap();
         let pr_num = pr.unwrap(); // Safe due to validation above
-        let comment_id = gh.post_comment(owner, repo_name, pr_num, &summary).await?;
+        let comment = generate_pr_comment(&review, &config);
+        let comment_id = gh.post_comment(owner, repo_name, pr_num, &comment).await?;
         info!(comment_id, "Posted review comment to PR");
         println!("Posted review comment (ID: {})", comment_id);
     }
@@ -266,39 +745,92 @@ fn parse_repo(repo: &str) -> Result<(&str, &str)> {
     Ok((parts[0], parts[1]))
 }
 
-fn list_pending(ledger_path: PathBuf) -> Result<()> {
-    let ledger = JsonLedger::new(&ledger_path)?;
-    let reviews = ledger.list_pending()?;
+/// Owners and decision policy for `reviews`: each repository's own, fetched from GitHub
+/// with `--repo-config`, otherwise those of the current checkout
+async fn repo_configs<'a>(
+    reviews: impl IntoIterator<Item = &'a Review>,
+    fetch: bool,
+) -> Result<RepoConfigs> {
+    if !fetch {
+        return RepoConfigs::local(Path::new("."));
+    }
+    let token = std::env::var("GITHUB_TOKEN").context("GITHUB_TOKEN not set")?;
+    RepoConfigs::fetch(&GitHubClient::new(&token)?, reviews).await
+}
+
+async fn list_pending(
+    ledger: &LedgerLocation,
+    repo: Option<&RepoPattern>,
+    owner: Option<&str>,
+    repo_config: bool,
+) -> Result<()> {
+    let ledger = ledger.open()?;
+    let pending: Vec<Review> = ledger
+        .list_pending()?
+        .into_iter()
+        .filter(|review| repo.is_none_or(|repo| repo.matches(&review.repo)))
+        .collect();
+    let configs = repo_configs(&pending, repo_config && owner.is_some()).await?;
+    let reviews: Vec<(Review, usize)> = pending
+        .into_iter()
+        .map(|review| {
+            let owners = &configs.get(&review.repo).owners;
+            let pending_count = review
+                .pending_suggestions()
+                .iter()
+                .filter(|item| owner.is_none_or(|o| owners.is_owner(o, &item.owners)))
+                .count();
+            (review, pending_count)
+        })
+        .filter(|(_, pending_count)| owner.is_none() || *pending_count > 0)
+        .collect();
 
     if reviews.is_empty() {
-        println!("No pending reviews.");
+        match owner {
+            Some(owner) => println!("No pending suggestions owned by {}.", owner),
+            None => println!("No pending reviews."),
+        }
         return Ok(());
     }
 
+    let mut by_repo: BTreeMap<&str, Vec<(&Review, usize)>> = BTreeMap::new();
+    for (review, pending_count) in &reviews {
+        by_repo
+            .entry(review.repo.as_str())
+            .or_default()
+            .push((review, *pending_count));
+    }
+
     println!("Pending Reviews:\n");
-    for review in reviews {
-        let pending_count = review.pending_suggestions().len();
-        let target = match review.pr_number {
-            Some(pr) => format!("PR #{}", pr),
-            None => format!("commit {}", &review.commit_sha[..7.min(review.commit_sha.len())]),
-        };
+    for (repo, reviews) in by_repo {
+        let total: usize = reviews.iter().map(|(_, count)| count).sum();
         println!(
-            "  {} in {} - {} pending suggestions",
-            target, review.repo, pending_count
+            "{} - {} reviews, {} pending suggestions\n",
+            repo,
+            reviews.len(),
+            total
         );
-        println!("    ID: {}", review.id);
-        println!("    Commit: {}", review.commit_sha);
-        println!();
+        for (review, pending_count) in reviews {
+            let target = match review.pr_number {
+                Some(pr) => format!("PR #{}", pr),
+                None => format!(
+                    "commit {}",
+                    &review.commit_sha[..7.min(review.commit_sha.len())]
+                ),
+            };
+            println!("  {} - {} pending suggestions", target, pending_count);
+            println!("    ID: {}", review.id);
+            println!("    Commit: {}", review.commit_sha);
+            println!();
+        }
     }
 
     Ok(())
 }
 
-fn show_review(ledger_path: PathBuf, repo: &str, pr: u64) -> Result<()> {
-    let ledger = JsonLedger::new(&ledger_path)?;
-    let review = ledger
-        .load_by_pr(repo, pr)?
-        .with_context(|| format!("No review found for PR #{} in {}", pr, repo))?;
+fn show_review(ledger: &LedgerLocation, target: &ReviewTarget, repo: Option<&str>) -> Result<()> {
+    let ledger = ledger.open()?;
+    let review = target.resolve(&ledger, repo)?;
 
     let summary = generate_summary(&review);
     println!("{}", summary);
@@ -306,58 +838,648 @@ fn show_review(ledger_path: PathBuf, repo: &str, pr: u64) -> Result<()> {
     Ok(())
 }
 
+async fn show_audit(
+    ledger: &LedgerLocation,
+    target: &ReviewTarget,
+    repo: Option<&str>,
+    full: bool,
+) -> Result<()> {
+    let (review, interactions) = match ledger {
+        LedgerLocation::Postgres(url) => {
+            let ledger = PostgresLedger::new(url).await?;
+            let review = target.resolve_postgres(&ledger, repo).await?;
+            let interactions = ledger.load_interactions(&review.id).await?;
+            (review, interactions)
+        }
+        _ => {
+            let ledger = ledger.open()?;
+            let review = target.resolve(&ledger, repo)?;
+            let interactions = ledger.load_interactions(&review.id)?;
+            (review, interactions)
+        }
+    };
+
+    if interactions.is_empty() {
+        println!("No LLM interactions recorded for review {}", review.id);
+        return Ok(());
+    }
+
+    println!("LLM interactions for review {}:\n", review.id);
+    print!("{}", format_interactions(&interactions, full));
+
+    Ok(())
+}
+
+fn show_decision_log(
+    ledger: &LedgerLocation,
+    target: &ReviewTarget,
+    repo: Option<&str>,
+    suggestion: Option<&str>,
+) -> Result<()> {
+    let ledger = ledger.open()?;
+    let review = target.resolve(&ledger, repo)?;
+    let events: Vec<_> = ledger
+        .load_decision_events(&review.id)?
+        .into_iter()
+        .filter(|e| suggestion.is_none_or(|id| e.suggestion_id == id))
+        .collect();
+
+    if events.is_empty() {
+        println!("No decisions recorded for review {}", review.id);
+        return Ok(());
+    }
+
+    println!("Decision history for review {}:\n", review.id);
+    for event in &events {
+        let current = event
+            .decision
+            .as_ref()
+            .map_or_else(|| "cleared".to_string(), describe_decision);
+        print!(
+            "  {} {} {}",
+            event.recorded_at.format("%Y-%m-%d %H:%M:%S"),
+            event.suggestion_id,
+            current
+        );
+        if let Some(previous) = &event.previous {
+            print!(" (was {:?})", previous.decision);
+        }
+        println!();
+        if let Some(reason) = event.decision.as_ref().and_then(|d| d.reason.as_ref()) {
+            println!("      {}", reason);
+        }
+    }
+
+    Ok(())
+}
+
+fn describe_decision(record: &DecisionRecord) -> String {
+    format!(
+        "{:?} by {}{}",
+        record.decision,
+        record.decided_by,
+        if record.verified { " (verified)" } else { "" }
+    )
+}
+
+fn show_history(ledger: &LedgerLocation, repo: &str, pr: u64) -> Result<()> {
+    let ledger = ledger.open()?;
+    let reviews = ledger.list_by_pr(repo, pr)?;
+
+    if reviews.is_empty() {
+        println!("No reviews found for PR #{} in {}", pr, repo);
+        return Ok(());
+    }
+
+    println!("Review history for PR #{} in {}:\n", pr, repo);
+    for review in &reviews {
+        let count = |decision: HumanDecision| {
+            review
+                .suggestions
+                .iter()
+                .filter(|s| s.decision.as_ref().map(|d| d.decision) == Some(decision))
+                .count()
+        };
+
+        println!(
+            "  {} {} [{:?}] - {} suggestions ({} accepted, {} rejected, {} deferred, {} pending)",
+            review.created_at.format("%Y-%m-%d %H:%M"),
+            &review.commit_sha[..7.min(review.commit_sha.len())],
+            review.status,
+            review.suggestions.len(),
+            count(HumanDecision::Accepted),
+            count(HumanDecision::Rejected),
+            count(HumanDecision::Deferred),
+            review.pending_suggestions().len()
+        );
+        println!("    ID: {}", review.id);
+        if let Some(previous) = review.previous_review_id {
+            println!("    Previous: {}", previous);
+        }
+        if let Some(hash) = &review.prompt_hash {
+            println!("    Prompt: {}", hash);
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Which suggestions a `decide` invocation applies to
+enum Selection {
+    Single(String),
+    Bulk(BulkFilter),
+}
+
 #[allow(clippy::too_many_arguments)]
-fn make_decision(
-    ledger_path: PathBuf,
-    repo: &str,
-    pr: u64,
-    suggestion_id: &str,
-    accept: bool,
-    reject: bool,
+async fn make_decision(
+    ledger: &LedgerLocation,
+    reviews_dir: &Path,
+    target: &ReviewTarget,
+    repo: Option<&str>,
+    selection: Selection,
+    decision: HumanDecision,
+    until: Option<NaiveDate>,
     reason: Option<String>,
-    user: &str,
+    identity: &Identity,
+    yes: bool,
+    repo_config: bool,
+) -> Result<()> {
+    let ledger = ledger.open()?;
+    let mut review = target.resolve(&ledger, repo)?;
+    let configs = repo_configs([&review], repo_config).await?;
+    let config = configs.get(&review.repo);
+    let permissions = config.permissions();
+
+    let suggestion_ids: Vec<String> = match selection {
+        Selection::Single(id) => {
+            let item = review
+                .suggestions
+                .iter()
+                .find(|s| s.suggestion.id == id)
+                .with_context(|| format!("Suggestion {} not found", id))?;
+            permissions.check(identity, item, decision, reason.as_deref())?;
+            vec![id]
+        }
+        Selection::Bulk(filter) => {
+            let plan = BulkPlan::new(&review, &filter, &config.config, |item| {
+                permissions.check(identity, item, decision, reason.as_deref())
+            });
+            let ids = plan.preview(decision, yes, &mut std::io::stdout())?;
+            if ids.is_empty() {
+                return Ok(());
+            }
+            ids
+        }
+    };
+
+    for suggestion_id in &suggestion_ids {
+        review.record_decision(identity.record(suggestion_id, decision, reason.clone(), until))?;
+    }
+
+    ledger.save(&review)?;
+    let markdown_path = write_review_markdown(reviews_dir, &review)?;
+
+    for suggestion_id in &suggestion_ids {
+        println!(
+            "Recorded {:?} for suggestion {} by {}",
+            decision, suggestion_id, identity.user
+        );
+    }
+    println!("Updated {}", markdown_path.display());
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn triage(
+    ledger: &LedgerLocation,
+    reviews_dir: &Path,
+    repo: Option<&RepoPattern>,
+    pr: Option<u64>,
+    identity: Identity,
+    owner: Option<String>,
+    plain: bool,
+    repo_config: bool,
 ) -> Result<()> {
-    use ai_review::{DecisionRecord, HumanDecision};
+    let ledger = ledger.open()?;
 
-    let ledger = JsonLedger::new(&ledger_path)?;
-    let mut review = ledger
-        .load_by_pr(repo, pr)?
-        .with_context(|| format!("No review found for PR #{} in {}", pr, repo))?;
+    let reviews = match (repo, pr) {
+        (Some(repo), Some(pr)) => {
+            let repo = repo
+                .exact()
+                .context("--pr needs a single repository, not a pattern")?;
+            ledger.load_by_pr(repo, pr)?.into_iter().collect()
+        }
+        (repo, _) => ledger
+            .list_pending()?
+            .into_iter()
+            .filter(|r| repo.is_none_or(|repo| repo.matches(&r.repo)))
+            .collect::<Vec<_>>(),
+    };
+
+    let configs = repo_configs(&reviews, repo_config).await?;
+    let permissions: HashMap<String, Permissions> = reviews
+        .iter()
+        .map(|r| (r.repo.clone(), configs.get(&r.repo).permissions()))
+        .collect();
+    // Findings narrowed to an owner if asked; Triage also skips those the user can't decide on
+    let filter = move |review: &Review, item: &SuggestionItem| {
+        owner
+            .as_ref()
+            .is_none_or(|o| configs.get(&review.repo).owners.is_owner(o, &item.owners))
+    };
 
-    let decision = if accept {
-        HumanDecision::Accepted
-    } else if reject {
-        HumanDecision::Rejected
+    if reviews.iter().all(|r| {
+        !r.pending_suggestions().into_iter().any(|item| {
+            filter(r, item) && permissions[&r.repo].check_decider(&identity, item).is_ok()
+        })
+    }) {
+        println!("No pending suggestions.");
+        return Ok(());
+    }
+
+    let mut stdout = std::io::stdout();
+    let decided = if plain || !is_interactive_terminal() {
+        let prompter = LinePrompter::new(std::io::stdin().lock(), std::io::stdout());
+        Triage::new(prompter, identity, ".")
+            .with_reviews_dir(reviews_dir)
+            .with_repo_permissions(permissions)
+            .with_filter(filter)
+            .run(&ledger, reviews, &mut stdout)?
     } else {
-        anyhow::bail!("Must specify --accept or --reject");
+        Triage::new(KeyPrompter, identity, ".")
+            .with_reviews_dir(reviews_dir)
+            .with_repo_permissions(permissions)
+            .with_filter(filter)
+            .run(&ledger, reviews, &mut stdout)?
     };
 
-    // Find and update the suggestion
-    let item = review
-        .suggestions
-        .iter_mut()
-        .find(|s| s.suggestion.id == suggestion_id)
-        .with_context(|| format!("Suggestion {} not found", suggestion_id))?;
+    println!("Recorded {} decisions.", decided);
+
+    Ok(())
+}
+
+fn apply(
+    ledger: &LedgerLocation,
+    reviews_dir: &Path,
+    target: &ReviewTarget,
+    repo: Option<&str>,
+    dry_run: bool,
+    commit: bool,
+) -> Result<()> {
+    let ledger = ledger.open()?;
+    let mut review = target.resolve(&ledger, repo)?;
+
+    let report = apply_fixes(Path::new("."), &review, dry_run)?;
+
+    let verb = if dry_run { "Would apply" } else { "Applied" };
+    for id in &report.applied {
+        println!("{} fix for {}", verb, id);
+    }
+    for conflict in &report.conflicts {
+        println!(
+            "CONFLICT on {} in {}: {}",
+            conflict.suggestion_id, conflict.file, conflict.reason
+        );
+    }
+    for id in &report.manual {
+        println!("{} has no structured fix, apply it by hand", id);
+    }
+
+    if report.applied.is_empty() {
+        println!("No fixes applied.");
+    } else if !dry_run {
+        if commit {
+            let message = commit_message(&review, &report.applied);
+            let sha = commit_fixes(Path::new("."), &report.files, &message)?;
+            println!("Committed {} files as {}", report.files.len(), sha);
+        }
+
+        review.mark_applied(&report.applied, chrono::Utc::now());
+        ledger.save(&review)?;
+        let markdown_path = write_review_markdown(reviews_dir, &review)?;
+        println!("Updated {}", markdown_path.display());
+    }
 
-    item.decision = Some(DecisionRecord {
-        suggestion_id: suggestion_id.to_string(),
-        decision,
-        reason,
-        decided_by: user.to_string(),
-        decided_at: chrono::Utc::now(),
-    });
+    if !report.conflicts.is_empty() {
+        anyhow::bail!(
+            "{} fixes don't match the current files and need to be applied by hand",
+            report.conflicts.len()
+        );
+    }
 
-    // Update status if all decided
-    if review.is_fully_decided() {
-        review.status = ai_review::ReviewStatus::Decided;
+    Ok(())
+}
+
+async fn fix_pr(
+    ledger: &LedgerLocation,
+    reviews_dir: &Path,
+    target: &ReviewTarget,
+    repo: Option<&str>,
+    base: Option<String>,
+    remote: &str,
+) -> Result<()> {
+    let ledger = ledger.open()?;
+    let mut review = target.resolve(&ledger, repo)?;
+
+    let token = std::env::var("GITHUB_TOKEN").context("GITHUB_TOKEN not set")?;
+    let github = GitHubClient::new(&token)?;
+    let outcome = FixPr::new(&github)
+        .with_remote(remote)
+        .with_base(base)
+        .open(&mut review)
+        .await?;
+
+    for conflict in &outcome.report.conflicts {
+        println!(
+            "Skipped {} in {}: {}",
+            conflict.suggestion_id, conflict.file, conflict.reason
+        );
+    }
+    let verb = if outcome.created { "Opened" } else { "Updated" };
+    println!(
+        "{} PR #{} from {} with {} fixes",
+        verb,
+        outcome.pr.number,
+        outcome.branch,
+        outcome.report.applied.len()
+    );
+    if let Some(url) = &outcome.pr.url {
+        println!("{}", url);
     }
 
     ledger.save(&review)?;
+    let markdown_path = write_review_markdown(reviews_dir, &review)?;
+    println!("Updated {}", markdown_path.display());
+
+    Ok(())
+}
+
+async fn show_stats(
+    ledger: &LedgerLocation,
+    repo: Option<&RepoPattern>,
+    since: Option<NaiveDate>,
+    format: StatsFormat,
+) -> Result<()> {
+    let reviews = read_ledger(ledger).await?;
+    let interactions = read_interactions(ledger).await?;
+    let stats = ReviewStats::compute(&reviews, &interactions, repo, since);
+
+    match format {
+        StatsFormat::Table => print!("{}", stats.to_table()),
+        StatsFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+    }
+
+    Ok(())
+}
 
+async fn feedback(
+    ledger: &LedgerLocation,
+    repo: Option<&RepoPattern>,
+    since: Option<NaiveDate>,
+    min_decisions: u64,
+    threshold: f64,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let reviews: Vec<Review> = read_ledger(ledger)
+        .await?
+        .into_iter()
+        .filter(|r| repo.is_none_or(|repo| repo.matches(&r.repo)))
+        .filter(|r| since.is_none_or(|since| r.created_at.date_naive() >= since))
+        .collect();
+
+    let report = FeedbackReport::compute(&reviews, min_decisions, threshold);
+    print!("{}", report.to_table());
+
+    let Some(patch) = report.config_patch() else {
+        println!(
+            "\nNo categories at or above {:.0}% rejection.",
+            threshold * 100.0
+        );
+        return Ok(());
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(&path, &patch)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("\nSuggested config changes written to {}", path.display());
+        }
+        None => println!("\nSuggested config changes:\n\n{}", patch),
+    }
+
+    Ok(())
+}
+
+fn reconcile(ledger: &LedgerLocation, reviews_dir: &Path, repo: &str) -> Result<()> {
+    let ledger = ledger.open()?;
+
+    if !reviews_dir.exists() {
+        println!("No review markdown found in {}", reviews_dir.display());
+        return Ok(());
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(reviews_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    paths.sort();
+
+    let mut imported = 0;
+    let mut conflicts = 0;
+
+    for path in paths {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        // Prefer the embedded review ID, fall back to the commit SHA file name
+        let review = match parse_review_id(&content) {
+            Some(id) => ledger.load(&id)?,
+            None => match path.file_stem().and_then(|s| s.to_str()) {
+                Some(sha) => ledger.load_by_commit(repo, sha)?,
+                None => None,
+            },
+        };
+
+        let mut review = match review {
+            Some(review) => review,
+            None => {
+                println!("{}: no matching review in ledger, skipped", path.display());
+                continue;
+            }
+        };
+
+        let report = reconcile_review(&mut review, &parse_decisions(&content));
+
+        for id in &report.imported {
+            println!("{}: imported decision for {}", path.display(), id);
+        }
+        for id in &report.unknown {
+            println!("{}: unknown suggestion {}, ignored", path.display(), id);
+        }
+        for conflict in &report.conflicts {
+            println!(
+                "{}: CONFLICT on {} - ledger says {:?}, markdown says {:?}",
+                path.display(),
+                conflict.suggestion_id,
+                conflict.ledger,
+                conflict.markdown
+            );
+        }
+
+        if !report.imported.is_empty() {
+            if review.is_fully_decided() {
+                review.status = ai_review::ReviewStatus::Decided;
+            }
+            ledger.save(&review)?;
+        }
+
+        // Rewrite the markdown from the ledger unless a conflict needs a human
+        if report.conflicts.is_empty() && generate_summary(&review) != content {
+            write_review_markdown(reviews_dir, &review)?;
+            println!("{}: regenerated from ledger", path.display());
+        }
+
+        imported += report.imported.len();
+        conflicts += report.conflicts.len();
+    }
+
+    println!("\nImported {} decisions, {} conflicts", imported, conflicts);
+
+    if conflicts > 0 {
+        anyhow::bail!(
+            "{} conflicting decisions need to be resolved by hand",
+            conflicts
+        );
+    }
+
+    Ok(())
+}
+
+fn ledger_fsck(ledger: &LedgerLocation) -> Result<()> {
+    let LedgerLocation::Json(path) = ledger else {
+        anyhow::bail!("fsck only applies to the JSON ledger, not {}", ledger);
+    };
+    let ledger = JsonLedger::new(path)?;
+    let report = ledger.fsck()?;
+
+    println!("Rebuilt index with {} reviews", report.indexed);
+    println!(
+        "  {} entries added, {} removed",
+        report.added, report.removed
+    );
+    if report.temp_files_removed > 0 {
+        println!(
+            "  {} interrupted temporary files removed",
+            report.temp_files_removed
+        );
+    }
+    for path in &report.corrupt_files {
+        println!("  Unreadable review file: {}", path.display());
+    }
+
+    if !report.corrupt_files.is_empty() {
+        anyhow::bail!(
+            "{} review files could not be parsed",
+            report.corrupt_files.len()
+        );
+    }
+
+    Ok(())
+}
+
+async fn ledger_export(
+    ledger: &LedgerLocation,
+    format: ExportFormat,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let reviews = read_ledger(ledger).await?;
+
+    let count = match (format, output) {
+        (ExportFormat::Jsonl, Some(path)) => {
+            let file = fs::File::create(&path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            write_jsonl(&reviews, io::BufWriter::new(file))?
+        }
+        (ExportFormat::Jsonl, None) => write_jsonl(&reviews, io::stdout().lock())?,
+    };
+
+    eprintln!("Exported {} reviews from {}", count, ledger);
+
+    Ok(())
+}
+
+async fn ledger_import(ledger: &LedgerLocation, input: &Path) -> Result<()> {
+    let reviews = if input == Path::new("-") {
+        read_jsonl(io::stdin().lock())?
+    } else {
+        let file =
+            fs::File::open(input).with_context(|| format!("Failed to open {}", input.display()))?;
+        read_jsonl(BufReader::new(file))?
+    };
+
+    let report = write_ledger(ledger, reviews).await?;
+    print_transfer_report(&report)
+}
+
+async fn ledger_copy(from: &LedgerLocation, to: &LedgerLocation) -> Result<()> {
+    if from == to {
+        anyhow::bail!("Source and destination ledgers are the same");
+    }
+
+    let reviews = read_ledger(from).await?;
+    println!("Copying {} reviews from {} to {}", reviews.len(), from, to);
+
+    let report = write_ledger(to, reviews).await?;
+    print_transfer_report(&report)
+}
+
+/// Load every review from any ledger backend, including PostgreSQL
+async fn read_ledger(location: &LedgerLocation) -> Result<Vec<Review>> {
+    match location {
+        LedgerLocation::Postgres(url) => PostgresLedger::new(url).await?.list_all().await,
+        _ => location.open()?.list_all(),
+    }
+}
+
+/// Read every recorded LLM interaction from any ledger backend, including PostgreSQL
+async fn read_interactions(location: &LedgerLocation) -> Result<Vec<LlmInteraction>> {
+    match location {
+        LedgerLocation::Postgres(url) => PostgresLedger::new(url).await?.list_interactions().await,
+        _ => location.open()?.list_interactions(),
+    }
+}
+
+/// Merge reviews into any ledger backend, including PostgreSQL
+async fn write_ledger(
+    location: &LedgerLocation,
+    mut reviews: Vec<Review>,
+) -> Result<TransferReport> {
+    match location {
+        LedgerLocation::Postgres(url) => {
+            let ledger = PostgresLedger::new(url).await?;
+            ledger.migrate().await?;
+
+            // Oldest first so review links resolve
+            reviews.sort_by_key(|r| r.created_at);
+
+            let mut report = TransferReport::default();
+            for review in reviews {
+                let existing = ledger.load(&review.id).await?;
+                if let Some(review) = report.merge(existing, review) {
+                    ledger.save(&review).await?;
+                }
+            }
+
+            Ok(report)
+        }
+        _ => import_reviews(&location.open()?, reviews),
+    }
+}
+
+fn print_transfer_report(report: &TransferReport) -> Result<()> {
     println!(
-        "Recorded {:?} for suggestion {} by {}",
-        decision, suggestion_id, user
+        "{} reviews imported, {} updated, {} unchanged",
+        report.imported, report.updated, report.unchanged
     );
 
+    for conflict in &report.conflicts {
+        match &conflict.suggestion_id {
+            Some(id) => println!(
+                "  Conflict in {} {}: {}",
+                conflict.review_id, id, conflict.detail
+            ),
+            None => println!("  Conflict in {}: {}", conflict.review_id, conflict.detail),
+        }
+    }
+
+    if !report.conflicts.is_empty() {
+        anyhow::bail!(
+            "{} conflicts found; destination decisions were kept",
+            report.conflicts.len()
+        );
+    }
+
     Ok(())
 }
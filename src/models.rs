@@ -1,9 +1,12 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
 /// Severity level for a code review suggestion
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
     Critical,
@@ -13,14 +16,132 @@ pub enum Severity {
 }
 
 /// Type of code review suggestion
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Serialized as its lowercase name. `Custom` holds a category defined in
+/// `config.yml`, e.g. `concurrency` or `api-compat`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum SuggestionType {
     Security,
     Performance,
     Style,
     Logic,
     Documentation,
+    Custom(String),
+}
+
+impl Severity {
+    /// All severities, most severe first
+    pub const ALL: [Severity; 4] = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+    ];
+
+    /// Numeric rank for comparisons, higher is more severe
+    pub fn rank(self) -> u8 {
+        match self {
+            Severity::Low => 0,
+            Severity::Medium => 1,
+            Severity::High => 2,
+            Severity::Critical => 3,
+        }
+    }
+}
+
+impl SuggestionType {
+    /// The built-in types
+    pub const ALL: [SuggestionType; 5] = [
+        SuggestionType::Security,
+        SuggestionType::Performance,
+        SuggestionType::Logic,
+        SuggestionType::Style,
+        SuggestionType::Documentation,
+    ];
+
+    /// Lowercase name as stored and sent to the model
+    pub fn as_str(&self) -> &str {
+        match self {
+            SuggestionType::Security => "security",
+            SuggestionType::Performance => "performance",
+            SuggestionType::Style => "style",
+            SuggestionType::Logic => "logic",
+            SuggestionType::Documentation => "documentation",
+            SuggestionType::Custom(name) => name,
+        }
+    }
+
+    /// Whether `name` is usable as a category: lowercase words joined by hyphens
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.split('-').all(|word| {
+                !word.is_empty()
+                    && word
+                        .bytes()
+                        .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+            })
+    }
+}
+
+impl fmt::Display for SuggestionType {
+    /// Capitalized built-in names, custom categories as configured
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SuggestionType::Custom(name) => f.write_str(name),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl Serialize for SuggestionType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SuggestionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        if !SuggestionType::is_valid_name(&name) {
+            return Err(serde::de::Error::custom(format!(
+                "invalid suggestion type: {:?}",
+                name
+            )));
+        }
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "critical" => Ok(Severity::Critical),
+            "high" => Ok(Severity::High),
+            "medium" => Ok(Severity::Medium),
+            "low" => Ok(Severity::Low),
+            _ => anyhow::bail!("Unknown severity: {}", s),
+        }
+    }
+}
+
+impl FromStr for SuggestionType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "security" => Ok(SuggestionType::Security),
+            "performance" => Ok(SuggestionType::Performance),
+            "style" => Ok(SuggestionType::Style),
+            "logic" => Ok(SuggestionType::Logic),
+            "documentation" => Ok(SuggestionType::Documentation),
+            name if SuggestionType::is_valid_name(name) => {
+                Ok(SuggestionType::Custom(name.to_string()))
+            }
+            _ => anyhow::bail!("Unknown suggestion type: {}", s),
+        }
+    }
 }
 
 /// Location in source code
@@ -41,6 +162,83 @@ pub struct Suggestion {
     pub location: Location,
     pub description: String,
     pub proposed_fix: Option<String>,
+    /// Which ensemble reviewers reported this finding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consensus: Option<Consensus>,
+    /// Free-form labels from the reviewer, e.g. `sql` or `breaking-change`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Reviewer's confidence that the finding is real, from 0.0 to 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Exact edit implementing the fix, for `ai-review apply`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<Replacement>,
+}
+
+/// Structured fix: replace a line range of the finding's file, if it still reads `old_text`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replacement {
+    /// First replaced line, 1-based
+    pub line_start: u32,
+    /// Last replaced line, inclusive
+    pub line_end: u32,
+    /// Current text of those lines, without the final newline
+    pub old_text: String,
+    /// Text to put in their place
+    pub new_text: String,
+}
+
+/// Reviewer agreement on a finding from a multi-reviewer ensemble
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Consensus {
+    /// Names of the reviewers that reported the finding
+    pub flagged_by: Vec<String>,
+    /// Number of reviewers that completed the review
+    pub reviewers: u32,
+}
+
+impl Consensus {
+    /// Short agreement label, e.g. "flagged by 2/3 reviewers"
+    pub fn label(&self) -> String {
+        format!(
+            "flagged by {}/{} reviewers",
+            self.flagged_by.len(),
+            self.reviewers
+        )
+    }
+}
+
+impl Suggestion {
+    /// Whether two suggestions describe the same finding, e.g. across commits of a PR
+    ///
+    /// Line numbers shift between commits, so findings are matched on file, type
+    /// and description wording rather than location.
+    pub fn is_same_finding(&self, other: &Suggestion) -> bool {
+        self.location.file == other.location.file
+            && self.suggestion_type == other.suggestion_type
+            && description_similarity(&self.description, &other.description) >= 0.8
+    }
+}
+
+/// Word-set (Jaccard) similarity of two descriptions, from 0.0 to 1.0
+pub fn description_similarity(a: &str, b: &str) -> f64 {
+    fn words(s: &str) -> std::collections::HashSet<String> {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase())
+            .collect()
+    }
+
+    let a = words(a);
+    let b = words(b);
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let shared = a.intersection(&b).count();
+    let total = a.union(&b).count();
+    shared as f64 / total as f64
 }
 
 /// Human decision on a suggestion
@@ -53,13 +251,43 @@ pub enum HumanDecision {
 }
 
 /// Record of a human decision
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DecisionRecord {
     pub suggestion_id: String,
     pub decision: HumanDecision,
     pub reason: Option<String>,
     pub decided_by: String,
     pub decided_at: DateTime<Utc>,
+    /// Date until which a deferred suggestion is parked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deferred_until: Option<NaiveDate>,
+    /// `decided_by` is a GitHub username verified by CI rather than a claimed name
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub verified: bool,
+}
+
+impl DecisionRecord {
+    /// Same decision, ignoring timestamp precision lost in storage
+    fn same_as(&self, other: &DecisionRecord) -> bool {
+        self.decision == other.decision
+            && self.reason == other.reason
+            && self.decided_by == other.decided_by
+            && self.decided_at.timestamp_micros() == other.decided_at.timestamp_micros()
+            && self.deferred_until == other.deferred_until
+            && self.verified == other.verified
+    }
+}
+
+/// A change to a suggestion's decision, kept in the append-only decision log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionEvent {
+    pub review_id: Uuid,
+    pub suggestion_id: String,
+    /// Decision after the change; `None` if it was cleared
+    pub decision: Option<DecisionRecord>,
+    /// Decision it replaced
+    pub previous: Option<DecisionRecord>,
+    pub recorded_at: DateTime<Utc>,
 }
 
 /// Status of a review
@@ -84,6 +312,42 @@ pub struct Review {
     pub created_at: DateTime<Utc>,
     pub status: ReviewStatus,
     pub suggestions: Vec<SuggestionItem>,
+    /// Earlier review of the same PR that this one supersedes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_review_id: Option<Uuid>,
+    /// How well the model output matched the response schema
+    #[serde(default, skip_serializing_if = "OutputQuality::is_clean")]
+    pub output_quality: OutputQuality,
+    /// Hash of the system prompt the findings were produced with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_hash: Option<String>,
+}
+
+/// Counts of model output that had to be fixed up or thrown away
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputQuality {
+    /// Fields normalized into a valid value, e.g. severity "High" or a reversed line range
+    pub coerced_fields: u32,
+    /// Optional fields discarded because they had the wrong type
+    pub dropped_fields: u32,
+    /// Suggestions discarded because a required field was missing or out of range
+    pub dropped_suggestions: u32,
+    /// Extra requests made to repair unparseable output
+    pub repair_rounds: u32,
+}
+
+impl OutputQuality {
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Add the counts from another review or reviewer
+    pub fn add(&mut self, other: &OutputQuality) {
+        self.coerced_fields += other.coerced_fields;
+        self.dropped_fields += other.dropped_fields;
+        self.dropped_suggestions += other.dropped_suggestions;
+        self.repair_rounds += other.repair_rounds;
+    }
 }
 
 /// A suggestion with its decision
@@ -91,6 +355,121 @@ pub struct Review {
 pub struct SuggestionItem {
     pub suggestion: Suggestion,
     pub decision: Option<DecisionRecord>,
+    /// Commit whose changes touched the finding's lines after it was reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_in: Option<String>,
+    /// When the fix was written to the working tree by `ai-review apply`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applied_at: Option<DateTime<Utc>>,
+    /// Users and teams responsible for the finding's file, as written in CODEOWNERS
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+}
+
+impl SuggestionItem {
+    /// Neither decided nor resolved by a later push
+    pub fn is_pending(&self) -> bool {
+        self.decision.is_none() && self.resolved_in.is_none()
+    }
+
+    /// Accepted, carries a replacement and hasn't been applied yet
+    pub fn is_applicable(&self) -> bool {
+        self.applied_at.is_none()
+            && self.resolved_in.is_none()
+            && self.suggestion.replacement.is_some()
+            && self
+                .decision
+                .as_ref()
+                .is_some_and(|d| d.decision == HumanDecision::Accepted)
+    }
+}
+
+/// Builder for findings in tests
+#[cfg(test)]
+impl SuggestionItem {
+    /// A pending medium-severity logic finding on line 1 of `file`
+    pub(crate) fn test(id: &str, file: &str) -> Self {
+        Self {
+            suggestion: Suggestion {
+                id: id.to_string(),
+                suggestion_type: SuggestionType::Logic,
+                severity: Severity::Medium,
+                location: Location {
+                    file: file.to_string(),
+                    line_start: 1,
+                    line_end: 1,
+                },
+                description: "Finding".to_string(),
+                proposed_fix: None,
+                consensus: None,
+                tags: Vec::new(),
+                confidence: None,
+                replacement: None,
+            },
+            decision: None,
+            resolved_in: None,
+            applied_at: None,
+            owners: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_type(mut self, suggestion_type: SuggestionType) -> Self {
+        self.suggestion.suggestion_type = suggestion_type;
+        self
+    }
+
+    pub(crate) fn with_severity(mut self, severity: Severity) -> Self {
+        self.suggestion.severity = severity;
+        self
+    }
+
+    pub(crate) fn with_lines(mut self, line_start: u32, line_end: u32) -> Self {
+        self.suggestion.location.line_start = line_start;
+        self.suggestion.location.line_end = line_end;
+        self
+    }
+
+    pub(crate) fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.suggestion.description = description.into();
+        self
+    }
+
+    pub(crate) fn with_owners(mut self, owners: &[&str]) -> Self {
+        self.owners = owners.iter().map(|o| o.to_string()).collect();
+        self
+    }
+}
+
+/// One request/response exchange with an LLM provider, kept for auditing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmInteraction {
+    pub id: Uuid,
+    /// Review the exchange belongs to, set once the review is created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_id: Option<Uuid>,
+    pub provider: String,
+    pub model: String,
+    pub created_at: DateTime<Utc>,
+    /// Time until the response arrived, including retries
+    pub latency_ms: u64,
+    /// Request body with secrets redacted
+    pub request: serde_json::Value,
+    /// Response body with secrets redacted
+    pub response: serde_json::Value,
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+    /// Estimated cost in USD from the configured price table
+    pub cost_usd: Option<f64>,
+}
+
+/// Findings for one file of a diff, reused when the same file diff is reviewed again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFile {
+    /// Hash of the normalized file diff, reviewer and rejections, see `cache::cache_key`
+    pub key: String,
+    pub file: String,
+    pub suggestions: Vec<Suggestion>,
+    pub created_at: DateTime<Utc>,
 }
 
 /// Context for a review request
@@ -114,22 +493,80 @@ impl Review {
             created_at: Utc::now(),
             status: ReviewStatus::Pending,
             suggestions: Vec::new(),
+            previous_review_id: None,
+            output_quality: OutputQuality::default(),
+            prompt_hash: None,
         }
     }
 
     /// Check if all suggestions have been decided
     pub fn is_fully_decided(&self) -> bool {
+        self.suggestions.iter().all(|s| !s.is_pending())
+    }
+
+    /// Record a decision on a suggestion, marking the review decided once nothing is pending
+    pub fn record_decision(&mut self, record: DecisionRecord) -> anyhow::Result<()> {
+        let item = self
+            .suggestions
+            .iter_mut()
+            .find(|s| s.suggestion.id == record.suggestion_id)
+            .ok_or_else(|| anyhow::anyhow!("Suggestion {} not found", record.suggestion_id))?;
+
+        item.decision = Some(record);
+
+        if self.is_fully_decided() {
+            self.status = ReviewStatus::Decided;
+        }
+
+        Ok(())
+    }
+
+    /// Decision changes from the stored suggestions `previous` to this review
+    pub fn decision_changes(&self, previous: &[SuggestionItem]) -> Vec<DecisionEvent> {
+        let recorded_at = Utc::now();
+
         self.suggestions
             .iter()
-            .all(|s| s.decision.is_some())
+            .filter_map(|item| {
+                let before = previous
+                    .iter()
+                    .find(|p| p.suggestion.id == item.suggestion.id)
+                    .and_then(|p| p.decision.as_ref());
+                let unchanged = match (before, &item.decision) {
+                    (Some(a), Some(b)) => a.same_as(b),
+                    (None, None) => true,
+                    _ => false,
+                };
+                (!unchanged).then(|| DecisionEvent {
+                    review_id: self.id,
+                    suggestion_id: item.suggestion.id.clone(),
+                    decision: item.decision.clone(),
+                    previous: before.cloned(),
+                    recorded_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Mark fixes as applied, and the review once every accepted fix is in
+    pub fn mark_applied(&mut self, suggestion_ids: &[String], applied_at: DateTime<Utc>) {
+        for item in &mut self.suggestions {
+            if suggestion_ids.contains(&item.suggestion.id) {
+                item.applied_at = Some(applied_at);
+            }
+        }
+
+        if !suggestion_ids.is_empty()
+            && self.is_fully_decided()
+            && !self.suggestions.iter().any(|s| s.is_applicable())
+        {
+            self.status = ReviewStatus::Applied;
+        }
     }
 
     /// Get pending suggestions (no human decision yet)
     pub fn pending_suggestions(&self) -> Vec<&SuggestionItem> {
-        self.suggestions
-            .iter()
-            .filter(|s| s.decision.is_none())
-            .collect()
+        self.suggestions.iter().filter(|s| s.is_pending()).collect()
     }
 
     /// Get suggestions by severity
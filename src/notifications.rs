@@ -1,10 +1,12 @@
+use std::collections::BTreeMap;
+
 use anyhow::{Context, Result};
 use reqwest::Client;
 use serde::Serialize;
 use tracing::{debug, info, warn};
 
 use crate::config::SlackConfig;
-use crate::models::{Review, Severity};
+use crate::models::{Review, Severity, SuggestionItem};
 
 /// Notification service for sending alerts
 pub struct NotificationService {
@@ -15,10 +17,10 @@ pub struct NotificationService {
 impl NotificationService {
     pub fn new(slack_config: &SlackConfig) -> Self {
         let slack = if slack_config.enabled {
-            slack_config
-                .webhook_url
-                .as_ref()
-                .map(|url| SlackNotifier::new(url.clone(), slack_config.channel.clone()))
+            slack_config.webhook_url.as_ref().map(|url| {
+                SlackNotifier::new(url.clone(), slack_config.channel.clone())
+                    .with_mentions(slack_config.mentions.clone())
+            })
         } else {
             None
         };
@@ -73,6 +75,8 @@ impl NotificationService {
 struct SlackNotifier {
     webhook_url: String,
     channel: Option<String>,
+    /// Slack member IDs by owner name, e.g. `@alice` -> `U024BE7LH`
+    mentions: BTreeMap<String, String>,
 }
 
 #[derive(Serialize)]
@@ -104,7 +108,37 @@ impl SlackNotifier {
         Self {
             webhook_url,
             channel,
+            mentions: BTreeMap::new(),
+        }
+    }
+
+    fn with_mentions(mut self, mentions: BTreeMap<String, String>) -> Self {
+        self.mentions = mentions;
+        self
+    }
+
+    /// Mention an owner by Slack member ID if known, otherwise by name
+    fn mention(&self, owner: &str) -> String {
+        let name = owner.trim_start_matches('@');
+        self.mentions
+            .iter()
+            .find(|(key, _)| key.trim_start_matches('@').eq_ignore_ascii_case(name))
+            .map_or_else(|| owner.to_string(), |(_, id)| format!("<@{}>", id))
+    }
+
+    /// Mentions of every owner of `items`, in first-seen order
+    fn owner_mentions<'a>(&self, items: impl IntoIterator<Item = &'a SuggestionItem>) -> String {
+        let mut owners: Vec<&str> = Vec::new();
+        for owner in items.into_iter().flat_map(|item| &item.owners) {
+            if !owners.contains(&owner.as_str()) {
+                owners.push(owner);
+            }
         }
+        owners
+            .iter()
+            .map(|owner| self.mention(owner))
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 
     async fn send(&self, client: &Client, message: SlackMessage) -> Result<()> {
@@ -144,7 +178,7 @@ impl SlackNotifier {
             Some(pr) => format!("PR #{}", pr),
             None => format!("commit {}", &review.commit_sha[..7.min(review.commit_sha.len())]),
         };
-        let text = format!(
+        let mut text = format!(
             "🔍 New AI Code Review for {} in `{}`\n\
              📊 {} suggestions ({} critical, {} high)",
             target,
@@ -153,6 +187,10 @@ impl SlackNotifier {
             critical,
             high
         );
+        let owners = self.owner_mentions(review.pending_suggestions());
+        if !owners.is_empty() {
+            text.push_str(&format!("\n👥 Owners: {}", owners));
+        }
 
         let message = SlackMessage {
             channel: self.channel.clone(),
@@ -194,10 +232,14 @@ impl SlackNotifier {
             .iter()
             .filter(|s| s.suggestion.severity == Severity::Critical)
             .map(|s| {
-                format!(
+                let mut issue = format!(
                     "• `{}` in `{}`: {}",
                     s.suggestion.id, s.suggestion.location.file, s.suggestion.description
-                )
+                );
+                if !s.owners.is_empty() {
+                    issue.push_str(&format!(" ({})", self.owner_mentions([s])));
+                }
+                issue
             })
             .collect();
 
@@ -258,6 +300,7 @@ impl SlackNotifier {
 mod tests {
     use super::*;
     use crate::config::SlackConfig;
+    use crate::models::SuggestionItem;
 
     #[test]
     fn test_notification_service_disabled() {
@@ -278,9 +321,31 @@ mod tests {
             channel: Some("#reviews".to_string()),
             on_critical: true,
             on_new_review: true,
+            mentions: BTreeMap::new(),
         };
 
         let service = NotificationService::new(&config);
         assert!(service.slack.is_some());
     }
+
+    #[test]
+    fn test_owner_mentions() {
+        let mentions = BTreeMap::from([("alice".to_string(), "U024BE7LH".to_string())]);
+        let notifier = SlackNotifier::new("https://hooks.slack.com/test".to_string(), None)
+            .with_mentions(mentions);
+        let item = |owners: &[&str]| {
+            SuggestionItem::test("S001", "src/lib.rs")
+                .with_severity(Severity::High)
+                .with_description("Bug")
+                .with_owners(owners)
+        };
+
+        let items = [
+            item(&["@Alice", "@org/web"]),
+            item(&["@org/web"]),
+            item(&[]),
+        ];
+        assert_eq!(notifier.owner_mentions(&items), "<@U024BE7LH>, @org/web");
+        assert_eq!(notifier.owner_mentions(&items[2..]), "");
+    }
 }
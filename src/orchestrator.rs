@@ -1,25 +1,75 @@
+use std::collections::HashSet;
+
 use anyhow::{Context, Result};
-use tracing::info;
+use chrono::Utc;
+use tracing::{info, warn};
 
-use crate::adapters::CodexAdapter;
+use crate::adapters::{ReviewOutput, Reviewer};
+use crate::cache::{cache_key, split_diff, FileDiff};
+use crate::config::Config;
 use crate::ledger::Ledger;
-use crate::models::{Review, ReviewContext, ReviewStatus, SuggestionItem};
+use crate::models::{
+    CachedFile, DecisionRecord, Review, ReviewContext, ReviewStatus, Suggestion, SuggestionItem,
+};
+use crate::owners::Owners;
+use crate::rules::RulesEngine;
 use crate::suppressions::Rejections;
 
+/// Produces the diff between two commits, e.g. `github::extract_diff`
+pub type InterdiffFn = Box<dyn Fn(&str, &str) -> Result<String> + Send + Sync>;
+
 /// Orchestrates the AI review pipeline
 pub struct Orchestrator<L: Ledger> {
-    codex: CodexAdapter,
+    reviewer: Box<dyn Reviewer>,
     ledger: L,
+    config: Config,
+    cache: bool,
+    interdiff: Option<InterdiffFn>,
+    owners: Option<Owners>,
 }
 
 impl<L: Ledger> Orchestrator<L> {
-    pub fn new(codex: CodexAdapter, ledger: L) -> Self {
+    pub fn new(reviewer: impl Reviewer + 'static, ledger: L) -> Self {
         Self {
-            codex,
+            reviewer: Box::new(reviewer),
             ledger,
+            config: Config::default(),
+            cache: true,
+            interdiff: None,
+            owners: None,
         }
     }
 
+    /// Apply severity minimums and auto-rules from the repository config
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Reuse findings for file diffs that were reviewed before (on by default)
+    pub fn with_cache(mut self, cache: bool) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Review only the changes since the previously reviewed commit of a PR
+    ///
+    /// `interdiff` is called with the previous and new commit SHAs. Without it,
+    /// every push is reviewed in full.
+    pub fn with_interdiff(
+        mut self,
+        interdiff: impl Fn(&str, &str) -> Result<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.interdiff = Some(Box::new(interdiff));
+        self
+    }
+
+    /// Assign each finding to the owners of its file
+    pub fn with_owners(mut self, owners: Owners) -> Self {
+        self.owners = Some(owners);
+        self
+    }
+
     /// Run the review pipeline for a PR or commit
     pub async fn review(
         &self,
@@ -41,44 +91,158 @@ impl<L: Ledger> Orchestrator<L> {
             self.ledger.load_by_commit(&context.repo, &context.commit_sha)?
         };
 
-        if let Some(existing) = existing {
-            if existing.commit_sha == context.commit_sha {
+        let previous = match existing {
+            Some(existing) if existing.commit_sha == context.commit_sha => {
                 info!("Review already exists for this commit");
                 return Ok(existing);
             }
-            info!("New commit detected, creating new review");
-        }
+            Some(existing) => {
+                info!(previous = %existing.id, "New commit detected, creating new review");
+                Some(existing)
+            }
+            None => None,
+        };
 
         // Create new review
         let mut review = Review::new(context.clone());
+        review.previous_review_id = previous.as_ref().map(|p| p.id);
 
-        // Run Codex review
-        info!("Running Codex review");
-        let suggestions = self
-            .codex
-            .review(diff, &context, rejections)
-            .await
-            .context("Codex review failed")?;
+        // Run the AI review, on just the new changes when there's an earlier review
+        let interdiff = previous
+            .as_ref()
+            .and_then(|previous| self.interdiff_since(previous, &context.commit_sha));
+        let output = match (&previous, &interdiff) {
+            (Some(previous), Some(changes)) => {
+                let in_pr: HashSet<String> = split_diff(diff).into_iter().map(|f| f.path).collect();
+                let changed: Vec<&FileDiff> = changes
+                    .iter()
+                    .filter(|f| in_pr.is_empty() || in_pr.contains(&f.path))
+                    .collect();
+                info!(
+                    since = %previous.commit_sha,
+                    files = changed.len(),
+                    "Reviewing changes since the previous review"
+                );
+                if changed.is_empty() {
+                    ReviewOutput::default()
+                } else {
+                    let changed: String = changed.iter().map(|f| f.text.as_str()).collect();
+                    let context = ReviewContext {
+                        base_sha: Some(previous.commit_sha.clone()),
+                        ..context.clone()
+                    };
+                    self.run_reviewer(&changed, &context, rejections).await?
+                }
+            }
+            _ => self.run_reviewer(diff, &context, rejections).await?,
+        };
+
+        if !output.quality.is_clean() {
+            info!(
+                coerced_fields = output.quality.coerced_fields,
+                dropped_fields = output.quality.dropped_fields,
+                dropped_suggestions = output.quality.dropped_suggestions,
+                repair_rounds = output.quality.repair_rounds,
+                "Reviewer output needed fixing"
+            );
+        }
+        review.output_quality = output.quality;
+        review.prompt_hash = output.prompt_hash;
+        let mut suggestions = output.suggestions;
+
+        let mut interactions = output.interactions;
+        for interaction in &mut interactions {
+            interaction.review_id = Some(review.id);
+            interaction.cost_usd = self.config.models.estimate_cost(interaction);
+        }
+
+        let found = suggestions.len();
+        suggestions.retain(|s| self.config.meets_min_severity(s));
+        if suggestions.len() < found {
+            info!(
+                dropped = found - suggestions.len(),
+                "Dropped suggestions below the configured minimum severity"
+            );
+        }
 
         if suggestions.is_empty() {
-            info!("No issues found by Codex");
-            review.status = ReviewStatus::Decided;
-            self.ledger.save(&review)?;
-            return Ok(review);
+            info!("No issues found by reviewer");
+        } else {
+            info!(count = suggestions.len(), "Reviewer found issues");
         }
 
-        info!(count = suggestions.len(), "Codex found issues");
+        // Keep earlier findings, resolving the ones whose lines have since changed
+        if let (Some(previous), Some(changes)) = (&previous, &interdiff) {
+            let resolved = carry_forward_findings(previous, changes, &mut review);
+            info!(
+                carried = review.suggestions.len(),
+                resolved, "Carried forward findings from previous review"
+            );
+
+            // A finding raised again is the carried one, whether or not it was decided
+            let carried = review.suggestions.len();
+            suggestions.retain(|s| {
+                !review.suggestions[..carried]
+                    .iter()
+                    .any(|item| item.resolved_in.is_none() && item.suggestion.is_same_finding(s))
+            });
+            let mut next = review
+                .suggestions
+                .iter()
+                .filter_map(|item| item.suggestion.id.strip_prefix('S')?.parse::<u32>().ok())
+                .max()
+                .unwrap_or(0);
+            for suggestion in &mut suggestions {
+                next += 1;
+                suggestion.id = format!("S{:03}", next);
+            }
+        }
 
         // Store suggestions
         for suggestion in suggestions {
             review.suggestions.push(SuggestionItem {
                 suggestion,
                 decision: None,
+                resolved_in: None,
+                applied_at: None,
+                owners: Vec::new(),
             });
         }
 
+        if let Some(ref previous) = previous {
+            let carried = carry_forward_decisions(previous, &mut review);
+            if carried > 0 {
+                info!(carried, "Carried forward decisions from previous review");
+            }
+        }
+
+        if let Some(ref owners) = self.owners {
+            let assigned = owners.assign(&mut review);
+            if assigned > 0 {
+                info!(assigned, "Assigned owners to findings");
+            }
+        }
+
+        let auto_decided = RulesEngine::from_config(&self.config).apply(&mut review);
+        if auto_decided > 0 {
+            info!(auto_decided, "Applied auto-rules");
+        }
+
+        if review.is_fully_decided() {
+            review.status = ReviewStatus::Decided;
+        }
+
         // Save review
         self.ledger.save(&review)?;
+        self.ledger.save_interactions(&review.id, &interactions)?;
+
+        // The previous review is superseded by this one
+        if let Some(mut previous) = previous {
+            if previous.status == ReviewStatus::Pending {
+                previous.status = ReviewStatus::Stale;
+                self.ledger.save(&previous)?;
+            }
+        }
 
         info!(
             id = %review.id,
@@ -89,45 +253,318 @@ impl<L: Ledger> Orchestrator<L> {
         Ok(review)
     }
 
+    /// Diff between the previous review's commit and `commit_sha`, split per file
+    ///
+    /// Returns `None` when there's no interdiff source or the diff can't be computed
+    /// (e.g. the old commit was force-pushed away or isn't fetched), in which case
+    /// the full diff is reviewed.
+    fn interdiff_since(&self, previous: &Review, commit_sha: &str) -> Option<Vec<FileDiff>> {
+        let interdiff = self.interdiff.as_ref()?;
+        let text = match interdiff(&previous.commit_sha, commit_sha) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!(
+                    since = %previous.commit_sha,
+                    error = %format!("{:#}", e),
+                    "Couldn't diff against the previous review, reviewing the full diff"
+                );
+                return None;
+            }
+        };
+
+        let files = split_diff(&text);
+        if files.is_empty() && !text.trim().is_empty() {
+            warn!("Interdiff isn't a git diff, reviewing the full diff");
+            return None;
+        }
+        Some(files)
+    }
+
+    /// Review the diff, sending only the files without cached findings to the model
+    ///
+    /// Each file diff is looked up by its cache key; fresh findings are cached per
+    /// file, including files with no findings. Diffs that can't be split per file
+    /// are always reviewed in full, and output that lost suggestions to validation
+    /// isn't cached, since the lost ones can't be attributed to a file.
+    async fn run_reviewer(
+        &self,
+        diff: &str,
+        context: &ReviewContext,
+        rejections: Option<&Rejections>,
+    ) -> Result<ReviewOutput> {
+        let files = if self.cache {
+            split_diff(diff)
+        } else {
+            Vec::new()
+        };
+
+        let fingerprint = self.reviewer.fingerprint();
+        let mut cached = Vec::new();
+        let mut uncached = Vec::new();
+        for file in files {
+            let key = cache_key(&file, &fingerprint, rejections);
+            match self.ledger.load_cached(&key)? {
+                Some(entry) => cached.push(entry),
+                None => uncached.push((key, file)),
+            }
+        }
+
+        if cached.is_empty() {
+            info!(reviewer = self.reviewer.name(), "Running AI review");
+        } else {
+            info!(
+                reviewer = self.reviewer.name(),
+                cached = cached.len(),
+                uncached = uncached.len(),
+                "Reusing cached findings for unchanged files"
+            );
+        }
+
+        let mut output = if cached.is_empty() || !uncached.is_empty() {
+            let diff = if cached.is_empty() {
+                diff.to_string()
+            } else {
+                uncached
+                    .iter()
+                    .map(|(_, file)| file.text.as_str())
+                    .collect()
+            };
+            self.reviewer
+                .review(&diff, context, rejections)
+                .await
+                .with_context(|| format!("{} review failed", self.reviewer.name()))?
+        } else {
+            ReviewOutput::default()
+        };
+
+        if output.quality.dropped_suggestions > 0 && !uncached.is_empty() {
+            warn!(
+                dropped = output.quality.dropped_suggestions,
+                "Not caching findings of a review that dropped invalid suggestions"
+            );
+            uncached.clear();
+        }
+
+        for (key, file) in uncached {
+            self.ledger.save_cached(&CachedFile {
+                key,
+                suggestions: output
+                    .suggestions
+                    .iter()
+                    .filter(|s| s.location.file == file.path)
+                    .cloned()
+                    .collect(),
+                file: file.path,
+                created_at: Utc::now(),
+            })?;
+        }
+
+        if !cached.is_empty() {
+            let mut suggestions: Vec<_> = cached.into_iter().flat_map(|c| c.suggestions).collect();
+            suggestions.append(&mut output.suggestions);
+            for (i, suggestion) in suggestions.iter_mut().enumerate() {
+                suggestion.id = format!("S{:03}", i + 1);
+            }
+            output.suggestions = suggestions;
+        }
+
+        Ok(output)
+    }
+
     /// Get the ledger for direct access
     pub fn ledger(&self) -> &L {
         &self.ledger
     }
 }
 
+/// Copy decisions from a previous review onto the same findings in a new one
+///
+/// Returns the number of decisions carried forward.
+pub fn carry_forward_decisions(previous: &Review, review: &mut Review) -> usize {
+    let mut carried = 0;
+
+    for item in review
+        .suggestions
+        .iter_mut()
+        .filter(|item| item.is_pending())
+    {
+        let earlier = previous
+            .suggestions
+            .iter()
+            .find(|p| p.decision.is_some() && p.suggestion.is_same_finding(&item.suggestion));
+
+        if let Some(decision) = earlier.and_then(|p| p.decision.as_ref()) {
+            item.decision = Some(DecisionRecord {
+                suggestion_id: item.suggestion.id.clone(),
+                ..decision.clone()
+            });
+            carried += 1;
+        }
+    }
+
+    carried
+}
+
+/// Copy the previous review's findings onto a new one, adjusted for `changes`
+///
+/// Findings keep their IDs and decisions, with line numbers shifted past lines
+/// added or removed above them. Findings whose lines were modified are marked
+/// resolved in the new commit. Returns the number of newly resolved findings.
+pub fn carry_forward_findings(
+    previous: &Review,
+    changes: &[FileDiff],
+    review: &mut Review,
+) -> usize {
+    let edits: Vec<(&FileDiff, LineEdits)> = changes
+        .iter()
+        .map(|file| (file, LineEdits::parse(&file.text)))
+        .collect();
+    let mut resolved = 0;
+
+    for item in &previous.suggestions {
+        let mut item = item.clone();
+        let location = &mut item.suggestion.location;
+        let edited = edits
+            .iter()
+            .find(|(file, edits)| edits.old_path.as_deref().unwrap_or(&file.path) == location.file);
+
+        if let (None, Some((file, edits))) = (&item.resolved_in, edited) {
+            if edits.touches(location.line_start, location.line_end) {
+                item.resolved_in = Some(review.commit_sha.clone());
+                resolved += 1;
+            } else {
+                let shift = edits.shift(location.line_start);
+                location.line_start = location.line_start.saturating_add_signed(shift);
+                location.line_end = location.line_end.saturating_add_signed(shift);
+                location.file = file.path.clone();
+            }
+        }
+
+        review.suggestions.push(item);
+    }
+
+    resolved
+}
+
+/// Old-file line numbers added or removed by one file's diff
+#[derive(Debug, Default)]
+struct LineEdits {
+    /// Path before a rename, from the `--- a/` header
+    old_path: Option<String>,
+    /// Old lines that were removed or replaced
+    removed: Vec<u32>,
+    /// Old line each added line was inserted before
+    inserted: Vec<u32>,
+}
+
+impl LineEdits {
+    fn parse(text: &str) -> Self {
+        let mut edits = Self::default();
+        let mut old_line = 0;
+        let mut in_hunk = false;
+
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("@@ -") {
+                let range = rest.split(' ').next().unwrap_or_default();
+                let (start, count) = match range.split_once(',') {
+                    Some((start, count)) => {
+                        (start.parse().unwrap_or(0), count.parse().unwrap_or(1))
+                    }
+                    None => (range.parse().unwrap_or(0), 1),
+                };
+                // An empty old range starts at the line the hunk is inserted after
+                old_line = if count == 0 { start + 1 } else { start };
+                in_hunk = true;
+                continue;
+            }
+
+            if !in_hunk {
+                if let Some(path) = line.strip_prefix("--- a/") {
+                    edits.old_path = Some(path.trim_end().to_string());
+                }
+                continue;
+            }
+
+            match line.chars().next() {
+                Some('-') => {
+                    edits.removed.push(old_line);
+                    old_line += 1;
+                }
+                Some('+') => edits.inserted.push(old_line),
+                Some('\\') => {}
+                _ => old_line += 1,
+            }
+        }
+
+        edits
+    }
+
+    /// Whether any line in `start..=end` was removed or had lines inserted within it
+    fn touches(&self, start: u32, end: u32) -> bool {
+        self.removed.iter().any(|&n| start <= n && n <= end)
+            || self.inserted.iter().any(|&n| start < n && n <= end)
+    }
+
+    /// How far old line `line` moved in the new file
+    fn shift(&self, line: u32) -> i32 {
+        let inserted = self.inserted.iter().filter(|&&n| n <= line).count();
+        let removed = self.removed.iter().filter(|&&n| n < line).count();
+        inserted as i32 - removed as i32
+    }
+}
+
 /// Generate a markdown summary for PR comment
 pub fn generate_summary(review: &Review) -> String {
+    render_summary(review, |_| true)
+}
+
+/// Summary for the PR comment, leaving out findings below the configured minimum confidence
+pub fn generate_pr_comment(review: &Review, config: &Config) -> String {
+    render_summary(review, |s| config.meets_min_confidence(s))
+}
+
+fn render_summary(review: &Review, shown: impl Fn(&Suggestion) -> bool) -> String {
     let mut md = String::new();
 
     md.push_str("## AI Code Review Summary\n\n");
 
-    if review.suggestions.is_empty() {
+    let visible: Vec<&SuggestionItem> = review
+        .suggestions
+        .iter()
+        .filter(|item| shown(&item.suggestion))
+        .collect();
+    let hidden = review.suggestions.len() - visible.len();
+
+    if visible.is_empty() {
         md.push_str("No issues found.\n");
+        if hidden > 0 {
+            md.push_str(&hidden_note(hidden));
+        }
         return md;
     }
 
     // Group by severity
-    let critical: Vec<_> = review
-        .suggestions
+    let critical: Vec<_> = visible
         .iter()
+        .filter(|s| s.resolved_in.is_none())
         .filter(|s| s.suggestion.severity == crate::models::Severity::Critical)
         .collect();
 
-    let high: Vec<_> = review
-        .suggestions
+    let high: Vec<_> = visible
         .iter()
+        .filter(|s| s.resolved_in.is_none())
         .filter(|s| s.suggestion.severity == crate::models::Severity::High)
         .collect();
 
-    let medium: Vec<_> = review
-        .suggestions
+    let medium: Vec<_> = visible
         .iter()
+        .filter(|s| s.resolved_in.is_none())
         .filter(|s| s.suggestion.severity == crate::models::Severity::Medium)
         .collect();
 
-    let low: Vec<_> = review
-        .suggestions
+    let low: Vec<_> = visible
         .iter()
+        .filter(|s| s.resolved_in.is_none())
         .filter(|s| s.suggestion.severity == crate::models::Severity::Low)
         .collect();
 
@@ -140,10 +577,21 @@ pub fn generate_summary(review: &Review) -> String {
         low.len()
     ));
 
+    let resolved = visible.iter().filter(|s| s.resolved_in.is_some()).count();
+    if resolved > 0 {
+        md.push_str(&format!(
+            "{} earlier finding(s) resolved by later commits.\n\n",
+            resolved
+        ));
+    }
+    if hidden > 0 {
+        md.push_str(&hidden_note(hidden));
+    }
+
     // Details for each suggestion
     md.push_str("### Suggestions\n\n");
 
-    for item in &review.suggestions {
+    for item in &visible {
         let s = &item.suggestion;
         let severity_emoji = match s.severity {
             crate::models::Severity::Critical => "🔴",
@@ -153,10 +601,9 @@ pub fn generate_summary(review: &Review) -> String {
         };
 
         let severity_str = format!("{:?}", s.severity).to_uppercase();
-        let type_str = format!("{:?}", s.suggestion_type);
         md.push_str(&format!(
             "#### {} {} `{}` - {}\n",
-            severity_emoji, severity_str, s.id, type_str
+            severity_emoji, severity_str, s.id, s.suggestion_type
         ));
 
         md.push_str(&format!(
@@ -166,10 +613,44 @@ pub fn generate_summary(review: &Review) -> String {
 
         md.push_str(&format!("{}\n\n", s.description));
 
+        if !s.tags.is_empty() {
+            md.push_str(&format!("**Tags:** {}\n\n", s.tags.join(", ")));
+        }
+
+        if !item.owners.is_empty() {
+            md.push_str(&format!("**Owners:** {}\n\n", item.owners.join(", ")));
+        }
+
+        if let Some(confidence) = s.confidence {
+            md.push_str(&format!("**Confidence:** {:.0}%\n\n", confidence * 100.0));
+        }
+
+        if let Some(consensus) = &s.consensus {
+            md.push_str(&format!(
+                "**Consensus:** {} ({})\n\n",
+                consensus.label(),
+                consensus.flagged_by.join(", ")
+            ));
+        }
+
         if let Some(fix) = &s.proposed_fix {
             md.push_str(&format!("**Proposed fix:**\n```\n{}\n```\n\n", fix));
         }
 
+        if let Some(replacement) = &s.replacement {
+            md.push_str(&format!(
+                "**Replacement:** lines {}-{}\n```diff\n",
+                replacement.line_start, replacement.line_end
+            ));
+            for line in replacement.old_text.lines() {
+                md.push_str(&format!("-{}\n", line));
+            }
+            for line in replacement.new_text.lines() {
+                md.push_str(&format!("+{}\n", line));
+            }
+            md.push_str("```\n\n");
+        }
+
         // Show decision if available
         if let Some(decision) = &item.decision {
             let decision_emoji = match decision.decision {
@@ -177,12 +658,35 @@ pub fn generate_summary(review: &Review) -> String {
                 crate::models::HumanDecision::Rejected => "❌ REJECTED",
                 crate::models::HumanDecision::Deferred => "⏸️ DEFERRED",
             };
-            md.push_str(&format!("**Decision:** {} by {}\n", decision_emoji, decision.decided_by));
+            match decision.deferred_until {
+                Some(until) => md.push_str(&format!(
+                    "**Decision:** {} by {} (until {})\n",
+                    decision_emoji, decision.decided_by, until
+                )),
+                None => md.push_str(&format!(
+                    "**Decision:** {} by {}\n",
+                    decision_emoji, decision.decided_by
+                )),
+            }
             if let Some(reason) = &decision.reason {
                 md.push_str(&format!("> {}\n\n", reason));
             }
         }
 
+        if let Some(applied_at) = item.applied_at {
+            md.push_str(&format!(
+                "**Applied:** {}\n\n",
+                applied_at.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+
+        if let Some(sha) = &item.resolved_in {
+            md.push_str(&format!(
+                "**Resolved:** lines changed in `{}`\n\n",
+                &sha[..7.min(sha.len())]
+            ));
+        }
+
         md.push_str("---\n\n");
     }
 
@@ -197,6 +701,13 @@ pub fn generate_summary(review: &Review) -> String {
     md
 }
 
+fn hidden_note(hidden: usize) -> String {
+    format!(
+        "{} low-confidence finding(s) not shown, see `ai-review show`.\n\n",
+        hidden
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,6 +727,86 @@ mod tests {
         assert!(summary.contains("No issues found"));
     }
 
+    fn make_item(id: &str, file: &str, description: &str) -> SuggestionItem {
+        SuggestionItem::test(id, file)
+            .with_lines(10, 12)
+            .with_description(description)
+    }
+
+    #[test]
+    fn test_carry_forward_decisions() {
+        let context = ReviewContext {
+            pr_number: Some(1),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: "abc".to_string(),
+            base_sha: None,
+        };
+
+        let mut previous = Review::new(context.clone());
+        let mut rejected = make_item("S001", "src/a.rs", "Possible overflow when adding counts");
+        rejected.decision = Some(DecisionRecord {
+            suggestion_id: "S001".to_string(),
+            decision: HumanDecision::Rejected,
+            reason: Some("Counts are bounded".to_string()),
+            decided_by: "alice".to_string(),
+            decided_at: chrono::Utc::now(),
+            deferred_until: None,
+            verified: false,
+        });
+        previous.suggestions.push(rejected);
+        previous
+            .suggestions
+            .push(make_item("S002", "src/b.rs", "Unused variable"));
+
+        let mut review = Review::new(ReviewContext {
+            commit_sha: "def".to_string(),
+            ..context
+        });
+        review
+            .suggestions
+            .push(make_item("S001", "src/b.rs", "Unused variable"));
+        review.suggestions.push(make_item(
+            "S002",
+            "src/a.rs",
+            "Possible overflow when adding the counts",
+        ));
+        review.suggestions.push(make_item(
+            "S003",
+            "src/c.rs",
+            "Possible overflow when adding counts",
+        ));
+
+        assert_eq!(carry_forward_decisions(&previous, &mut review), 1);
+
+        assert!(review.suggestions[0].decision.is_none());
+        let carried = review.suggestions[1].decision.as_ref().unwrap();
+        assert_eq!(carried.suggestion_id, "S002");
+        assert_eq!(carried.decision, HumanDecision::Rejected);
+        assert_eq!(carried.decided_by, "alice");
+        assert!(review.suggestions[2].decision.is_none());
+    }
+
+    #[test]
+    fn test_generate_summary_consensus() {
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: "abc".to_string(),
+            base_sha: None,
+        });
+        let mut item = make_item("S001", "src/a.rs", "Possible overflow");
+        item.suggestion.consensus = Some(Consensus {
+            flagged_by: vec!["gpt".to_string(), "claude".to_string()],
+            reviewers: 3,
+        });
+        review.suggestions.push(item);
+
+        let summary = generate_summary(&review);
+        assert!(summary.contains("**Consensus:** flagged by 2/3 reviewers (gpt, claude)"));
+    }
+
     #[test]
     fn test_generate_summary_empty_no_pr() {
         let review = Review::new(ReviewContext {
@@ -229,4 +820,535 @@ mod tests {
         let summary = generate_summary(&review);
         assert!(summary.contains("No issues found"));
     }
+
+    /// Reports one finding per file in the diff and records the diffs it was sent
+    struct PerFileReviewer {
+        seen: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Reviewer for PerFileReviewer {
+        fn name(&self) -> &str {
+            "per-file"
+        }
+
+        async fn review(
+            &self,
+            diff: &str,
+            _context: &ReviewContext,
+            _rejections: Option<&Rejections>,
+        ) -> Result<ReviewOutput> {
+            self.seen.lock().unwrap().push(diff.to_string());
+            let suggestions = crate::cache::split_diff(diff)
+                .iter()
+                .enumerate()
+                .map(|(i, file)| {
+                    make_item(&format!("S{:03}", i + 1), &file.path, "Possible overflow").suggestion
+                })
+                .collect();
+            Ok(ReviewOutput {
+                suggestions,
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_review_reuses_cached_files() {
+        let file = |path: &str, line: &str| {
+            format!(
+                "diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n@@ -1 +1,2 @@\n a\n+{1}\n",
+                path, line
+            )
+        };
+        let context = |sha: &str| ReviewContext {
+            pr_number: None,
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: sha.to_string(),
+            base_sha: None,
+        };
+
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let orchestrator = Orchestrator::new(
+            PerFileReviewer { seen: seen.clone() },
+            crate::ledger::SqliteLedger::open_in_memory().unwrap(),
+        );
+
+        let first = format!(
+            "{}{}",
+            file("src/a.rs", "let x = 1;"),
+            file("src/b.rs", "b()")
+        );
+        let review = orchestrator
+            .review(&first, context("aaa"), None)
+            .await
+            .unwrap();
+        assert_eq!(review.suggestions.len(), 2);
+
+        // Cherry-picked with an extra change to b.rs: only b.rs goes to the model
+        let second = format!(
+            "{}{}",
+            file("src/a.rs", "let x = 1;"),
+            file("src/b.rs", "c()")
+        );
+        let review = orchestrator
+            .review(&second, context("bbb"), None)
+            .await
+            .unwrap();
+        assert_eq!(review.suggestions.len(), 2);
+        assert_eq!(review.suggestions[0].suggestion.location.file, "src/a.rs");
+        assert_eq!(review.suggestions[1].suggestion.id, "S002");
+        {
+            let seen = seen.lock().unwrap();
+            assert_eq!(seen.len(), 2);
+            assert!(!seen[1].contains("src/a.rs"));
+        }
+
+        // Identical diff on another commit: no model call at all
+        orchestrator
+            .review(&second, context("ccc"), None)
+            .await
+            .unwrap();
+        assert_eq!(seen.lock().unwrap().len(), 2);
+
+        let orchestrator = orchestrator.with_cache(false);
+        orchestrator
+            .review(&second, context("ddd"), None)
+            .await
+            .unwrap();
+        assert_eq!(seen.lock().unwrap().len(), 3);
+    }
+
+    /// Reviewer whose output always had a suggestion dropped by validation
+    struct DegradedReviewer {
+        calls: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Reviewer for DegradedReviewer {
+        fn name(&self) -> &str {
+            "degraded"
+        }
+
+        async fn review(
+            &self,
+            _diff: &str,
+            _context: &ReviewContext,
+            _rejections: Option<&Rejections>,
+        ) -> Result<ReviewOutput> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(ReviewOutput {
+                suggestions: vec![make_item("S001", "src/a.rs", "Possible overflow").suggestion],
+                quality: OutputQuality {
+                    dropped_suggestions: 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_review_skips_caching_degraded_output() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1,2 @@\n a\n+b\n";
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let orchestrator = Orchestrator::new(
+            DegradedReviewer {
+                calls: calls.clone(),
+            },
+            crate::ledger::SqliteLedger::open_in_memory().unwrap(),
+        );
+
+        for sha in ["aaa", "bbb"] {
+            let context = ReviewContext {
+                pr_number: None,
+                repo: "test/repo".to_string(),
+                branch: None,
+                commit_sha: sha.to_string(),
+                base_sha: None,
+            };
+            orchestrator.review(diff, context, None).await.unwrap();
+        }
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    /// Reviewer that reports the same findings for any diff
+    struct FixedReviewer {
+        suggestions: Vec<Suggestion>,
+    }
+
+    #[async_trait::async_trait]
+    impl Reviewer for FixedReviewer {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn review(
+            &self,
+            _diff: &str,
+            _context: &ReviewContext,
+            _rejections: Option<&Rejections>,
+        ) -> Result<ReviewOutput> {
+            Ok(ReviewOutput {
+                suggestions: self.suggestions.clone(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_review_applies_min_severity_and_auto_rules() {
+        let finding = |id: &str, suggestion_type: SuggestionType, severity: Severity| {
+            let mut item = make_item(id, "src/a.rs", &format!("Finding {}", id));
+            item.suggestion.suggestion_type = suggestion_type;
+            item.suggestion.severity = severity;
+            item.suggestion
+        };
+        let config = Config::parse(
+            r#"
+review:
+  min_severity:
+    documentation: high
+auto_rules:
+  - condition: "type == 'style'"
+    action: auto_dismiss
+    reason: Formatting is enforced by CI
+"#,
+        )
+        .unwrap();
+        let ledger = crate::ledger::SqliteLedger::open_in_memory().unwrap();
+        let orchestrator = Orchestrator::new(
+            FixedReviewer {
+                suggestions: vec![
+                    finding("S001", SuggestionType::Documentation, Severity::Low),
+                    finding("S002", SuggestionType::Style, Severity::Low),
+                    finding("S003", SuggestionType::Logic, Severity::Medium),
+                ],
+            },
+            ledger,
+        )
+        .with_config(config)
+        .with_cache(false);
+
+        let context = ReviewContext {
+            pr_number: Some(1),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: "abc".to_string(),
+            base_sha: None,
+        };
+        let review = orchestrator.review("diff", context, None).await.unwrap();
+
+        // The low documentation finding never reaches the ledger
+        let ids: Vec<&str> = review
+            .suggestions
+            .iter()
+            .map(|item| item.suggestion.id.as_str())
+            .collect();
+        assert_eq!(ids, ["S002", "S003"]);
+
+        let dismissed = review.suggestions[0].decision.as_ref().unwrap();
+        assert_eq!(dismissed.decision, HumanDecision::Rejected);
+        assert_eq!(dismissed.decided_by, "auto-rules");
+        assert!(review.suggestions[1].decision.is_none());
+        assert_eq!(review.status, ReviewStatus::Pending);
+
+        let saved = orchestrator.ledger().load(&review.id).unwrap().unwrap();
+        assert_eq!(saved.suggestions.len(), 2);
+        assert!(saved.suggestions[0].decision.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_review_assigns_owners() {
+        let diff = ["src/a.rs", "web/b.js"]
+            .iter()
+            .map(|path| {
+                format!(
+                    "diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n@@ -1 +1,2 @@\n a\n+b\n",
+                    path
+                )
+            })
+            .collect::<String>();
+        let config = crate::config::OwnersConfig::default();
+        let owners = Owners::new("*.js @frontend\n", &config).unwrap();
+        let orchestrator = Orchestrator::new(
+            PerFileReviewer {
+                seen: Default::default(),
+            },
+            crate::ledger::SqliteLedger::open_in_memory().unwrap(),
+        )
+        .with_owners(owners);
+
+        let context = ReviewContext {
+            pr_number: Some(1),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: "aaa".to_string(),
+            base_sha: None,
+        };
+        let review = orchestrator.review(&diff, context, None).await.unwrap();
+        assert!(review.suggestions[0].owners.is_empty());
+        assert_eq!(review.suggestions[1].owners, ["@frontend"]);
+        assert!(generate_summary(&review).contains("**Owners:** @frontend\n"));
+
+        let stored = orchestrator.ledger().load(&review.id).unwrap().unwrap();
+        assert_eq!(stored.suggestions[1].owners, ["@frontend"]);
+    }
+
+    #[test]
+    fn test_carry_forward_findings() {
+        let context = ReviewContext {
+            pr_number: Some(1),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: "abc".to_string(),
+            base_sha: None,
+        };
+
+        let mut previous = Review::new(context.clone());
+        let mut above = make_item("S001", "src/a.rs", "Unchecked index");
+        above.suggestion.location.line_start = 2;
+        above.suggestion.location.line_end = 3;
+        let mut below = make_item("S002", "src/a.rs", "Possible overflow");
+        below.decision = Some(DecisionRecord {
+            suggestion_id: "S002".to_string(),
+            decision: HumanDecision::Accepted,
+            reason: None,
+            decided_by: "alice".to_string(),
+            decided_at: chrono::Utc::now(),
+            deferred_until: None,
+            verified: false,
+        });
+        previous.suggestions.push(above);
+        previous.suggestions.push(below);
+        previous
+            .suggestions
+            .push(make_item("S003", "src/old.rs", "Unused variable"));
+
+        // Line 3 of a.rs is replaced and two lines are added after line 5;
+        // old.rs is renamed with a line added at the top
+        let changes = crate::cache::split_diff(concat!(
+            "diff --git a/src/a.rs b/src/a.rs\n",
+            "--- a/src/a.rs\n",
+            "+++ b/src/a.rs\n",
+            "@@ -3 +3 @@\n",
+            "-old\n",
+            "+new\n",
+            "@@ -5,0 +6,2 @@\n",
+            "+one\n",
+            "+two\n",
+            "diff --git a/src/old.rs b/src/new.rs\n",
+            "similarity index 90%\n",
+            "rename from src/old.rs\n",
+            "rename to src/new.rs\n",
+            "--- a/src/old.rs\n",
+            "+++ b/src/new.rs\n",
+            "@@ -1,2 +1,3 @@\n",
+            "+use std::fmt;\n",
+            " fn main() {\n",
+            " }\n",
+        ));
+
+        let mut review = Review::new(ReviewContext {
+            commit_sha: "def".to_string(),
+            ..context
+        });
+        assert_eq!(carry_forward_findings(&previous, &changes, &mut review), 1);
+        assert_eq!(review.suggestions.len(), 3);
+
+        assert_eq!(review.suggestions[0].resolved_in.as_deref(), Some("def"));
+        assert!(!review.suggestions[0].is_pending());
+
+        let moved = &review.suggestions[1];
+        assert_eq!(moved.suggestion.id, "S002");
+        assert_eq!(moved.suggestion.location.line_start, 12);
+        assert_eq!(moved.suggestion.location.line_end, 14);
+        assert_eq!(moved.decision.as_ref().unwrap().decided_by, "alice");
+        assert!(moved.resolved_in.is_none());
+
+        let renamed = &review.suggestions[2].suggestion.location;
+        assert_eq!(renamed.file, "src/new.rs");
+        assert_eq!(renamed.line_start, 11);
+    }
+
+    #[test]
+    fn test_generate_summary_resolved() {
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: "abc".to_string(),
+            base_sha: None,
+        });
+        let mut item = make_item("S001", "src/a.rs", "Possible overflow");
+        item.resolved_in = Some("0123456789abcdef".to_string());
+        review.suggestions.push(item);
+
+        let summary = generate_summary(&review);
+        assert!(summary.contains("| Medium | 0 |"));
+        assert!(summary.contains("**Resolved:** lines changed in `0123456`"));
+    }
+
+    #[test]
+    fn test_generate_pr_comment_min_confidence() {
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: "abc".to_string(),
+            base_sha: None,
+        });
+        let mut unsure = make_item("S001", "src/a.rs", "Might overflow");
+        unsure.suggestion.confidence = Some(0.3);
+        let mut sure = make_item("S002", "src/b.rs", "Overflows on empty input");
+        sure.suggestion.confidence = Some(0.9);
+        review.suggestions.push(unsure);
+        review.suggestions.push(sure);
+
+        let config: Config = serde_yaml::from_str("review:\n  min_confidence: 0.5\n").unwrap();
+        let comment = generate_pr_comment(&review, &config);
+        assert!(comment.contains("| Medium | 1 |"));
+        assert!(!comment.contains("Might overflow"));
+        assert!(comment.contains("**Confidence:** 90%"));
+        assert!(comment.contains("1 low-confidence finding(s) not shown"));
+
+        // The full summary, as written to the review markdown, keeps everything
+        let summary = generate_summary(&review);
+        assert!(summary.contains("Might overflow"));
+        assert!(!summary.contains("not shown"));
+
+        review.suggestions.remove(1);
+        let comment = generate_pr_comment(&review, &config);
+        assert!(comment.starts_with("## AI Code Review Summary\n\nNo issues found.\n1 low"));
+    }
+
+    #[tokio::test]
+    async fn test_review_incremental() {
+        let pr_diff = concat!(
+            "diff --git a/src/a.rs b/src/a.rs\n",
+            "--- a/src/a.rs\n",
+            "+++ b/src/a.rs\n",
+            "@@ -1 +1,20 @@\n",
+            " a\n",
+            "+b\n",
+        );
+        let interdiff = concat!(
+            "diff --git a/src/a.rs b/src/a.rs\n",
+            "--- a/src/a.rs\n",
+            "+++ b/src/a.rs\n",
+            "@@ -11 +11 @@\n",
+            "-old\n",
+            "+new\n",
+            "diff --git a/src/main_only.rs b/src/main_only.rs\n",
+            "--- a/src/main_only.rs\n",
+            "+++ b/src/main_only.rs\n",
+            "@@ -1 +1 @@\n",
+            "-x\n",
+            "+y\n",
+        );
+        let context = |sha: &str| ReviewContext {
+            pr_number: Some(7),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: sha.to_string(),
+            base_sha: None,
+        };
+
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let orchestrator = Orchestrator::new(
+            PerFileReviewer { seen: seen.clone() },
+            crate::ledger::SqliteLedger::open_in_memory().unwrap(),
+        )
+        .with_cache(false)
+        .with_interdiff(move |from: &str, to: &str| {
+            recorded.lock().unwrap().push(format!("{}..{}", from, to));
+            Ok(interdiff.to_string())
+        });
+
+        let first = orchestrator
+            .review(pr_diff, context("aaa"), None)
+            .await
+            .unwrap();
+        assert_eq!(first.suggestions.len(), 1);
+        assert!(calls.lock().unwrap().is_empty());
+
+        // Lines 10-12 were flagged and line 11 changed, so the finding is resolved
+        // and the reviewer only sees the interdiff for files in the PR
+        let second = orchestrator
+            .review(pr_diff, context("bbb"), None)
+            .await
+            .unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec!["aaa..bbb".to_string()]);
+        {
+            let seen = seen.lock().unwrap();
+            assert_eq!(seen.len(), 2);
+            assert!(seen[1].contains("@@ -11 +11 @@"));
+            assert!(!seen[1].contains("main_only.rs"));
+        }
+        assert_eq!(second.previous_review_id, Some(first.id));
+        assert_eq!(second.suggestions.len(), 2);
+        assert_eq!(second.suggestions[0].suggestion.id, "S001");
+        assert_eq!(second.suggestions[0].resolved_in.as_deref(), Some("bbb"));
+        assert_eq!(second.suggestions[1].suggestion.id, "S002");
+        assert!(second.suggestions[1].is_pending());
+
+        let stored = orchestrator.ledger().load(&second.id).unwrap().unwrap();
+        assert_eq!(stored.suggestions[0].resolved_in.as_deref(), Some("bbb"));
+    }
+
+    #[tokio::test]
+    async fn test_review_skips_decided_findings_raised_again() {
+        let pr_diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n";
+        let interdiff = concat!(
+            "diff --git a/src/a.rs b/src/a.rs\n",
+            "--- a/src/a.rs\n",
+            "+++ b/src/a.rs\n",
+            "@@ -1 +1 @@\n",
+            "-old\n",
+            "+new\n",
+        );
+        let context = |sha: &str| ReviewContext {
+            pr_number: Some(7),
+            repo: "test/repo".to_string(),
+            branch: None,
+            commit_sha: sha.to_string(),
+            base_sha: None,
+        };
+
+        let orchestrator = Orchestrator::new(
+            FixedReviewer {
+                suggestions: vec![make_item("S001", "src/a.rs", "Possible overflow").suggestion],
+            },
+            crate::ledger::SqliteLedger::open_in_memory().unwrap(),
+        )
+        .with_cache(false)
+        .with_interdiff(move |_: &str, _: &str| Ok(interdiff.to_string()));
+
+        let mut first = orchestrator
+            .review(pr_diff, context("aaa"), None)
+            .await
+            .unwrap();
+        first.suggestions[0].decision = Some(crate::policy::Identity::from("bob").record(
+            "S001",
+            HumanDecision::Rejected,
+            Some("Can't overflow".to_string()),
+            None,
+        ));
+        orchestrator.ledger().save(&first).unwrap();
+
+        // The push doesn't touch the finding, and the reviewer reports it again
+        let second = orchestrator
+            .review(pr_diff, context("bbb"), None)
+            .await
+            .unwrap();
+        assert_eq!(second.suggestions.len(), 1);
+        assert_eq!(second.suggestions[0].suggestion.id, "S001");
+        assert_eq!(
+            second.suggestions[0].decision.as_ref().map(|d| d.decision),
+            Some(HumanDecision::Rejected)
+        );
+    }
 }
@@ -0,0 +1,323 @@
+//! Finding ownership from CODEOWNERS and config overrides
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use tracing::{info, warn};
+
+use crate::config::OwnersConfig;
+use crate::models::{Review, SuggestionItem};
+
+/// Where GitHub looks for CODEOWNERS, in order
+pub(crate) const CODEOWNERS_PATHS: &[&str] =
+    &["CODEOWNERS", ".github/CODEOWNERS", "docs/CODEOWNERS"];
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A CODEOWNERS line: files matching the pattern and who owns them
+#[derive(Debug, Clone)]
+struct OwnerRule {
+    patterns: Vec<Pattern>,
+    owners: Vec<String>,
+}
+
+impl OwnerRule {
+    fn matches(&self, file: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|p| p.matches_with(file, MATCH_OPTIONS))
+    }
+}
+
+/// Resolves who owns a file and who may decide on findings
+#[derive(Debug, Clone, Default)]
+pub struct Owners {
+    rules: Vec<OwnerRule>,
+    overrides: Vec<OwnerRule>,
+    admins: Vec<String>,
+    teams: BTreeMap<String, Vec<String>>,
+}
+
+impl Owners {
+    /// Build from CODEOWNERS content and the `owners` config section
+    pub fn new(codeowners: &str, config: &OwnersConfig) -> Result<Self> {
+        let mut overrides = Vec::new();
+        for entry in &config.overrides {
+            let patterns = entry
+                .paths
+                .iter()
+                .map(|path| compile(path))
+                .collect::<Result<Vec<_>>>()?;
+            overrides.push(OwnerRule {
+                patterns: patterns.into_iter().flatten().collect(),
+                owners: entry.owners.clone(),
+            });
+        }
+
+        Ok(Self {
+            rules: parse_codeowners(codeowners),
+            overrides,
+            admins: config.admins.clone(),
+            teams: config.teams.clone(),
+        })
+    }
+
+    /// Load the CODEOWNERS file of the checkout at `root`
+    ///
+    /// A missing CODEOWNERS file is only an error when `owners.codeowners` names it.
+    pub fn load(root: &Path, config: &OwnersConfig) -> Result<Self> {
+        let path = match &config.codeowners {
+            Some(path) => Some(root.join(path)),
+            None => CODEOWNERS_PATHS
+                .iter()
+                .map(|path| root.join(path))
+                .find(|path| path.exists()),
+        };
+
+        let content = match path {
+            Some(path) => {
+                let content = fs::read_to_string(&path).with_context(|| {
+                    format!("Failed to read CODEOWNERS file: {}", path.display())
+                })?;
+                info!(path = %path.display(), "Loaded CODEOWNERS");
+                content
+            }
+            None => String::new(),
+        };
+
+        Self::new(&content, config)
+    }
+
+    /// Owners of `file`; overrides win over CODEOWNERS, and the last matching line wins
+    pub fn owners_of(&self, file: &str) -> &[String] {
+        self.overrides
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(file))
+            .or_else(|| self.rules.iter().rev().find(|rule| rule.matches(file)))
+            .map_or(&[], |rule| &rule.owners)
+    }
+
+    /// Assign owners to the findings of `review` that have none, returning how many got one
+    pub fn assign(&self, review: &mut Review) -> usize {
+        let mut assigned = 0;
+        for item in &mut review.suggestions {
+            if item.owners.is_empty() {
+                item.owners = self.owners_of(&item.suggestion.location.file).to_vec();
+                if !item.owners.is_empty() {
+                    assigned += 1;
+                }
+            }
+        }
+        assigned
+    }
+
+    /// Whether `user` is one of `owners`, directly or through a configured team
+    pub fn is_owner(&self, user: &str, owners: &[String]) -> bool {
+        let user = handle(user);
+        owners.iter().any(|owner| {
+            handle(owner) == user
+                || self.teams.iter().any(|(team, members)| {
+                    handle(team) == handle(owner) && members.iter().any(|m| handle(m) == user)
+                })
+        })
+    }
+
+    /// Whether `user` is listed in `owners.admins`
+    pub fn is_admin(&self, user: &str) -> bool {
+        self.admins
+            .iter()
+            .any(|admin| handle(admin) == handle(user))
+    }
+
+    /// Whether `user` may decide on `item`: an owner, an admin, or anyone if it has no owner
+    pub fn can_decide(&self, user: &str, item: &SuggestionItem) -> bool {
+        item.owners.is_empty() || self.is_admin(user) || self.is_owner(user, &item.owners)
+    }
+}
+
+/// Parse CODEOWNERS content, skipping comments and lines with invalid patterns
+fn parse_codeowners(content: &str) -> Vec<OwnerRule> {
+    let mut rules = Vec::new();
+
+    for (number, line) in content.lines().enumerate() {
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let Some(pattern) = fields.next() else {
+            continue;
+        };
+        // A pattern without owners leaves matching files unowned
+        let owners = fields.map(str::to_string).collect();
+
+        match compile(pattern) {
+            Ok(patterns) => rules.push(OwnerRule { patterns, owners }),
+            Err(e) => warn!(line = number + 1, error = %e, "Skipping CODEOWNERS line"),
+        }
+    }
+
+    rules
+}
+
+/// Translate a CODEOWNERS (gitignore-style) pattern into globs over repository paths
+///
+/// Patterns containing a slash are anchored at the root, others match at any depth.
+/// A pattern whose last segment has no wildcard also matches everything beneath it.
+fn compile(pattern: &str) -> Result<Vec<Pattern>> {
+    let trimmed = pattern.trim_start_matches('/').trim_end_matches('/');
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let base = if anchored || trimmed.starts_with("**") {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+
+    let mut globs = vec![base.clone()];
+    if !trimmed.rsplit('/').next().unwrap_or_default().contains('*') {
+        globs.push(format!("{}/**", base));
+    }
+
+    globs
+        .iter()
+        .map(|g| Pattern::new(g).with_context(|| format!("Invalid owner pattern: {}", pattern)))
+        .collect()
+}
+
+/// Compare names the way GitHub does: without the `@` and ignoring case
+fn handle(name: &str) -> String {
+    name.trim_start_matches('@').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OwnerOverride;
+    use crate::models::{ReviewContext, Severity};
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*       @alice
+*.js    @frontend-lead  # inline comment
+/docs/  @org/docs
+apps/   @octocat
+src/generated/
+**/logs @ops
+";
+
+    fn owners(config: &OwnersConfig) -> Owners {
+        Owners::new(CODEOWNERS, config).unwrap()
+    }
+
+    fn item(file: &str, owners: &[&str]) -> SuggestionItem {
+        SuggestionItem::test("S001", file)
+            .with_severity(Severity::High)
+            .with_description("Bug")
+            .with_owners(owners)
+    }
+
+    #[test]
+    fn test_owners_of() {
+        let owners = owners(&OwnersConfig::default());
+
+        assert_eq!(owners.owners_of("README.md"), ["@alice"]);
+        assert_eq!(owners.owners_of("web/app.js"), ["@frontend-lead"]);
+        assert_eq!(owners.owners_of("docs/guide/intro.md"), ["@org/docs"]);
+        // Anchored patterns don't match deeper directories of the same name
+        assert_eq!(owners.owners_of("src/docs/mod.rs"), ["@alice"]);
+        assert_eq!(owners.owners_of("apps/web/main.rs"), ["@octocat"]);
+        assert_eq!(owners.owners_of("src/generated/api.rs"), [] as [&str; 0]);
+        assert_eq!(owners.owners_of("deploy/logs/app.log"), ["@ops"]);
+    }
+
+    #[test]
+    fn test_overrides_win() {
+        let config = OwnersConfig {
+            overrides: vec![OwnerOverride {
+                paths: vec!["apps/billing/".to_string(), "*.sql".to_string()],
+                owners: vec!["@bob".to_string()],
+            }],
+            ..Default::default()
+        };
+        let owners = owners(&config);
+
+        assert_eq!(owners.owners_of("apps/billing/invoice.rs"), ["@bob"]);
+        assert_eq!(owners.owners_of("migrations/001.sql"), ["@bob"]);
+        assert_eq!(owners.owners_of("apps/web/main.rs"), ["@octocat"]);
+    }
+
+    #[test]
+    fn test_assign() {
+        let owners = owners(&OwnersConfig::default());
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc".to_string(),
+            base_sha: None,
+        });
+        review.suggestions.push(item("apps/web/main.rs", &[]));
+        review.suggestions.push(item("src/generated/api.rs", &[]));
+        review
+            .suggestions
+            .push(item("apps/web/main.rs", &["@carol"]));
+
+        assert_eq!(owners.assign(&mut review), 1);
+        assert_eq!(review.suggestions[0].owners, ["@octocat"]);
+        assert!(review.suggestions[1].owners.is_empty());
+        assert_eq!(review.suggestions[2].owners, ["@carol"]);
+    }
+
+    #[test]
+    fn test_can_decide() {
+        let config = OwnersConfig {
+            admins: vec!["root".to_string()],
+            teams: BTreeMap::from([(
+                "@org/docs".to_string(),
+                vec!["dana".to_string(), "@Erin".to_string()],
+            )]),
+            ..Default::default()
+        };
+        let owners = owners(&config);
+
+        let owned = item("apps/web/main.rs", &["@octocat"]);
+        assert!(owners.can_decide("octocat", &owned));
+        assert!(owners.can_decide("@OctoCat", &owned));
+        assert!(owners.can_decide("root", &owned));
+        assert!(!owners.can_decide("dana", &owned));
+
+        let team_owned = item("docs/index.md", &["@org/docs"]);
+        assert!(owners.can_decide("dana", &team_owned));
+        assert!(owners.can_decide("erin", &team_owned));
+        assert!(!owners.can_decide("octocat", &team_owned));
+
+        assert!(owners.can_decide("anyone", &item("src/generated/api.rs", &[])));
+    }
+
+    #[test]
+    fn test_load_missing_codeowners() {
+        let dir = tempfile::tempdir().unwrap();
+        let owners = Owners::load(dir.path(), &OwnersConfig::default()).unwrap();
+        assert!(owners.owners_of("src/main.rs").is_empty());
+
+        let config = OwnersConfig {
+            codeowners: Some("OWNERS".to_string()),
+            ..Default::default()
+        };
+        assert!(Owners::load(dir.path(), &config).is_err());
+
+        fs::create_dir(dir.path().join(".github")).unwrap();
+        fs::write(dir.path().join(".github/CODEOWNERS"), "*.rs @rustacean\n").unwrap();
+        let owners = Owners::load(dir.path(), &OwnersConfig::default()).unwrap();
+        assert_eq!(owners.owners_of("src/main.rs"), ["@rustacean"]);
+    }
+}
@@ -0,0 +1,285 @@
+//! Who is deciding, and whether they may make a decision
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+
+use crate::config::DecisionPolicy;
+use crate::models::{DecisionRecord, HumanDecision, Severity, SuggestionItem};
+use crate::owners::Owners;
+
+/// The person recording a decision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub user: String,
+    /// `user` is the GitHub actor reported by GitHub Actions, not a claimed name
+    pub verified: bool,
+}
+
+impl From<&str> for Identity {
+    fn from(user: &str) -> Self {
+        Self {
+            user: user.to_string(),
+            verified: false,
+        }
+    }
+}
+
+impl Identity {
+    /// The GitHub actor inside GitHub Actions, otherwise `user` or `$USER`
+    pub fn resolve(user: Option<&str>) -> Result<Self> {
+        Self::from_env(user, |key| std::env::var(key).ok())
+    }
+
+    fn from_env(user: Option<&str>, env: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        if env("GITHUB_ACTIONS").as_deref() == Some("true") {
+            let actor = env("GITHUB_ACTOR")
+                .filter(|actor| !actor.is_empty())
+                .context("GITHUB_ACTOR is not set in GitHub Actions")?;
+            if let Some(user) = user {
+                if !user.trim_start_matches('@').eq_ignore_ascii_case(&actor) {
+                    anyhow::bail!(
+                        "--user {} doesn't match the GitHub actor {}; decisions in CI are recorded as the actor",
+                        user,
+                        actor
+                    );
+                }
+            }
+            return Ok(Self {
+                user: actor,
+                verified: true,
+            });
+        }
+
+        let user = user
+            .map(str::to_string)
+            .or_else(|| env("USER"))
+            .filter(|user| !user.is_empty())
+            .context("No user to record the decision for; pass --user or set $USER")?;
+
+        Ok(Self {
+            user,
+            verified: false,
+        })
+    }
+
+    /// A decision on `suggestion_id` made by this identity now
+    pub fn record(
+        &self,
+        suggestion_id: &str,
+        decision: HumanDecision,
+        reason: Option<String>,
+        deferred_until: Option<NaiveDate>,
+    ) -> DecisionRecord {
+        DecisionRecord {
+            suggestion_id: suggestion_id.to_string(),
+            decision,
+            reason,
+            decided_by: self.user.clone(),
+            decided_at: Utc::now(),
+            deferred_until,
+            verified: self.verified,
+        }
+    }
+}
+
+/// Checks decisions against finding owners and `decision_policy`
+#[derive(Debug, Clone, Default)]
+pub struct Permissions {
+    owners: Owners,
+    policy: DecisionPolicy,
+}
+
+impl Permissions {
+    pub fn new(owners: Owners, policy: DecisionPolicy) -> Self {
+        Self { owners, policy }
+    }
+
+    /// Check that `identity` may decide on `item` at all
+    ///
+    /// Admins may decide on any finding; everyone else must own it and be allowed to
+    /// decide on its severity.
+    pub fn check_decider(&self, identity: &Identity, item: &SuggestionItem) -> Result<()> {
+        if self.policy.require_verified && !identity.verified {
+            anyhow::bail!(
+                "Decisions must be made in CI, where {} can be verified as a GitHub user",
+                identity.user
+            );
+        }
+        if self.owners.is_admin(&identity.user) {
+            return Ok(());
+        }
+
+        let s = &item.suggestion;
+        if !self.owners.can_decide(&identity.user, item) {
+            anyhow::bail!(
+                "{} is owned by {}; only its owners or an admin can decide on it",
+                s.id,
+                item.owners.join(", ")
+            );
+        }
+
+        if let Some(deciders) = self.deciders(s.severity) {
+            if !self.owners.is_owner(&identity.user, deciders) {
+                anyhow::bail!(
+                    "Only {} can decide on {} findings like {}",
+                    deciders.join(", "),
+                    severity_name(s.severity),
+                    s.id
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check a decision by `identity` on `item`, including the reason it needs
+    pub fn check(
+        &self,
+        identity: &Identity,
+        item: &SuggestionItem,
+        decision: HumanDecision,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        self.check_decider(identity, item)?;
+
+        let s = &item.suggestion;
+        let needs_reason = decision == HumanDecision::Rejected
+            && self
+                .policy
+                .reject_reason_required
+                .iter()
+                .any(|sev| sev.parse::<Severity>().is_ok_and(|sev| sev == s.severity));
+        if needs_reason && reason.is_none_or(|r| r.trim().is_empty()) {
+            anyhow::bail!(
+                "Rejecting {} finding {} needs a reason",
+                severity_name(s.severity),
+                s.id
+            );
+        }
+
+        Ok(())
+    }
+
+    fn deciders(&self, severity: Severity) -> Option<&[String]> {
+        self.policy
+            .deciders
+            .iter()
+            .find(|(sev, _)| sev.parse::<Severity>().is_ok_and(|sev| sev == severity))
+            .map(|(_, deciders)| deciders.as_slice())
+    }
+}
+
+fn severity_name(severity: Severity) -> String {
+    format!("{:?}", severity).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OwnersConfig;
+    use crate::models::SuggestionType;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn item(severity: Severity, owners: &[&str]) -> SuggestionItem {
+        SuggestionItem::test("S001", "src/auth.rs")
+            .with_type(SuggestionType::Security)
+            .with_severity(severity)
+            .with_lines(1, 2)
+            .with_description("Token compared with ==")
+            .with_owners(owners)
+    }
+
+    fn permissions(policy: DecisionPolicy) -> Permissions {
+        let config = OwnersConfig {
+            admins: vec!["root".to_string()],
+            teams: BTreeMap::from([("@org/security".to_string(), vec!["sam".to_string()])]),
+            ..Default::default()
+        };
+        Permissions::new(Owners::new("", &config).unwrap(), policy)
+    }
+
+    #[test]
+    fn test_identity_from_env() {
+        let env = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            move |key: &str| vars.get(key).cloned()
+        };
+
+        let local = Identity::from_env(None, env(&[("USER", "alice")])).unwrap();
+        assert_eq!(local, Identity::from("alice"));
+        let claimed = Identity::from_env(Some("bob"), env(&[("USER", "alice")])).unwrap();
+        assert_eq!(claimed.user, "bob");
+        assert!(!claimed.verified);
+        assert!(Identity::from_env(None, env(&[])).is_err());
+
+        let ci = env(&[
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_ACTOR", "octocat"),
+            ("USER", "runner"),
+        ]);
+        let actor = Identity::from_env(None, &ci).unwrap();
+        assert_eq!(actor.user, "octocat");
+        assert!(actor.verified);
+        assert!(Identity::from_env(Some("@OctoCat"), &ci).is_ok());
+        // Nobody can claim to be someone else in CI
+        assert!(Identity::from_env(Some("alice"), &ci).is_err());
+    }
+
+    #[test]
+    fn test_check_decider() {
+        let policy = DecisionPolicy {
+            deciders: BTreeMap::from([("critical".to_string(), vec!["@org/security".to_string()])]),
+            ..Default::default()
+        };
+        let permissions = permissions(policy);
+
+        let high = item(Severity::High, &["@alice"]);
+        assert!(permissions.check_decider(&"alice".into(), &high).is_ok());
+        let err = permissions.check_decider(&"bob".into(), &high).unwrap_err();
+        assert!(err.to_string().contains("owned by @alice"));
+
+        let critical = item(Severity::Critical, &["@alice", "@sam"]);
+        let err = permissions
+            .check_decider(&"alice".into(), &critical)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Only @org/security can decide on critical findings like S001"
+        );
+        assert!(permissions.check_decider(&"sam".into(), &critical).is_ok());
+        assert!(permissions.check_decider(&"root".into(), &critical).is_ok());
+    }
+
+    #[test]
+    fn test_require_verified_and_reason() {
+        let policy = DecisionPolicy {
+            reject_reason_required: vec!["high".to_string(), "critical".to_string()],
+            require_verified: true,
+            ..Default::default()
+        };
+        let permissions = permissions(policy);
+        let high = item(Severity::High, &[]);
+        let verified = Identity {
+            user: "alice".to_string(),
+            verified: true,
+        };
+
+        assert!(permissions.check_decider(&"alice".into(), &high).is_err());
+        assert!(permissions.check_decider(&verified, &high).is_ok());
+
+        let reject = |reason| permissions.check(&verified, &high, HumanDecision::Rejected, reason);
+        assert!(reject(None).is_err());
+        assert!(reject(Some("  ")).is_err());
+        assert!(reject(Some("Intentional")).is_ok());
+        assert!(permissions
+            .check(&verified, &high, HumanDecision::Accepted, None)
+            .is_ok());
+        let low = item(Severity::Low, &[]);
+        assert!(permissions
+            .check(&verified, &low, HumanDecision::Rejected, None)
+            .is_ok());
+    }
+}
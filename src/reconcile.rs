@@ -0,0 +1,352 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::models::{DecisionRecord, HumanDecision, Review};
+use crate::orchestrator::generate_summary;
+
+/// A decision block found in review markdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownDecision {
+    pub suggestion_id: String,
+    pub decision: HumanDecision,
+    pub decided_by: String,
+    pub reason: Option<String>,
+    pub deferred_until: Option<NaiveDate>,
+}
+
+/// A suggestion whose markdown decision disagrees with the ledger
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub suggestion_id: String,
+    pub ledger: HumanDecision,
+    pub markdown: HumanDecision,
+}
+
+/// Outcome of reconciling one review with its markdown
+#[derive(Debug, Default)]
+pub struct ReconcileReport {
+    /// Suggestions whose markdown decision was copied into the ledger
+    pub imported: Vec<String>,
+    /// Suggestions decided differently in the ledger and the markdown
+    pub conflicts: Vec<Conflict>,
+    /// Suggestion IDs in the markdown that the review doesn't know about
+    pub unknown: Vec<String>,
+}
+
+/// Path of the markdown file for a review's commit
+pub fn review_markdown_path(reviews_dir: impl AsRef<Path>, commit_sha: &str) -> PathBuf {
+    reviews_dir.as_ref().join(format!("{}.md", commit_sha))
+}
+
+/// Regenerate the markdown file for a review from the ledger state
+pub fn write_review_markdown(reviews_dir: impl AsRef<Path>, review: &Review) -> Result<PathBuf> {
+    let reviews_dir = reviews_dir.as_ref();
+    fs::create_dir_all(reviews_dir).with_context(|| {
+        format!(
+            "Failed to create reviews directory: {}",
+            reviews_dir.display()
+        )
+    })?;
+
+    let path = review_markdown_path(reviews_dir, &review.commit_sha);
+    fs::write(&path, generate_summary(review))
+        .with_context(|| format!("Failed to write review markdown: {}", path.display()))?;
+
+    debug!(path = %path.display(), "Wrote review markdown");
+
+    Ok(path)
+}
+
+/// Extract the review ID from a "**Review ID:** `...`" line
+pub fn parse_review_id(content: &str) -> Option<Uuid> {
+    content
+        .lines()
+        .find(|l| l.starts_with("**Review ID:**"))
+        .and_then(|l| {
+            let start = l.find('`')? + 1;
+            let end = l[start..].find('`')? + start;
+            Uuid::parse_str(&l[start..end]).ok()
+        })
+}
+
+/// Parse all decision blocks from review markdown
+pub fn parse_decisions(content: &str) -> Vec<MarkdownDecision> {
+    let mut decisions = Vec::new();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut current_id: Option<String> = None;
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+
+        // Suggestion headers look like "#### 🟠 HIGH `S001` - Logic"
+        if line.starts_with("####") {
+            current_id = line.find('`').and_then(|start| {
+                let rest = &line[start + 1..];
+                rest.find('`').map(|end| rest[..end].to_string())
+            });
+        } else if let Some(rest) = line.strip_prefix("**Decision:**") {
+            if let (Some(id), Some((decision, decided_by, deferred_until))) =
+                (current_id.take(), parse_decision_line(rest))
+            {
+                // Reason is quoted on the following lines
+                let mut reason = String::new();
+                while i + 1 < lines.len() && lines[i + 1].starts_with('>') {
+                    i += 1;
+                    if !reason.is_empty() {
+                        reason.push(' ');
+                    }
+                    reason.push_str(lines[i].trim_start_matches('>').trim());
+                }
+
+                decisions.push(MarkdownDecision {
+                    suggestion_id: id,
+                    decision,
+                    decided_by,
+                    reason: if reason.is_empty() {
+                        None
+                    } else {
+                        Some(reason)
+                    },
+                    deferred_until,
+                });
+            }
+        }
+
+        i += 1;
+    }
+
+    decisions
+}
+
+/// Parse the text after "**Decision:**", e.g. " ❌ REJECTED by alice (until 2025-01-31)"
+fn parse_decision_line(rest: &str) -> Option<(HumanDecision, String, Option<NaiveDate>)> {
+    let decision = if rest.contains("ACCEPTED") {
+        HumanDecision::Accepted
+    } else if rest.contains("REJECTED") {
+        HumanDecision::Rejected
+    } else if rest.contains("DEFERRED") {
+        HumanDecision::Deferred
+    } else {
+        return None;
+    };
+
+    let by = &rest[rest.find(" by ")? + 4..];
+    let (decided_by, deferred_until) = match by.find(" (until ") {
+        Some(pos) => {
+            let date = by[pos + 8..].trim_end_matches(')').trim();
+            (&by[..pos], NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        }
+        None => (by, None),
+    };
+
+    Some((decision, decided_by.trim().to_string(), deferred_until))
+}
+
+/// Copy markdown decisions into a review, leaving conflicting ledger decisions untouched
+pub fn reconcile_review(review: &mut Review, decisions: &[MarkdownDecision]) -> ReconcileReport {
+    let mut report = ReconcileReport::default();
+
+    for md in decisions {
+        let item = match review
+            .suggestions
+            .iter_mut()
+            .find(|s| s.suggestion.id == md.suggestion_id)
+        {
+            Some(item) => item,
+            None => {
+                report.unknown.push(md.suggestion_id.clone());
+                continue;
+            }
+        };
+
+        match &item.decision {
+            Some(existing) if existing.decision != md.decision => {
+                report.conflicts.push(Conflict {
+                    suggestion_id: md.suggestion_id.clone(),
+                    ledger: existing.decision,
+                    markdown: md.decision,
+                });
+            }
+            Some(_) => {}
+            None => {
+                item.decision = Some(DecisionRecord {
+                    suggestion_id: md.suggestion_id.clone(),
+                    decision: md.decision,
+                    reason: md.reason.clone(),
+                    decided_by: md.decided_by.clone(),
+                    decided_at: Utc::now(),
+                    deferred_until: md.deferred_until,
+                    verified: false,
+                });
+                report.imported.push(md.suggestion_id.clone());
+            }
+        }
+    }
+
+    info!(
+        id = %review.id,
+        imported = report.imported.len(),
+        conflicts = report.conflicts.len(),
+        "Reconciled review markdown"
+    );
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ReviewContext, Severity, SuggestionItem};
+
+    fn make_review() -> Review {
+        let mut review = Review::new(ReviewContext {
+            pr_number: None,
+            repo: "owner/repo".to_string(),
+            branch: Some("main".to_string()),
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+
+        for id in ["S001", "S002", "S003"] {
+            review.suggestions.push(
+                SuggestionItem::test(id, "src/main.rs")
+                    .with_severity(Severity::High)
+                    .with_lines(10, 15)
+                    .with_description("Test suggestion"),
+            );
+        }
+
+        review
+    }
+
+    #[test]
+    fn test_parse_decisions() {
+        let content = r#"## AI Code Review Summary
+
+#### 🟠 HIGH `S001` - Security
+**File:** `src/main.rs` (lines 10-15)
+
+This is a security issue description.
+
+**Decision:** ❌ REJECTED by claude
+> This is intentional behavior
+> for testing purposes.
+
+---
+
+#### 🟢 LOW `S002` - Style
+**File:** `src/lib.rs` (lines 20-25)
+
+This is a style issue.
+
+**Decision:** ⏸️ DEFERRED by alice (until 2025-03-01)
+---
+
+#### 🟢 LOW `S003` - Style
+**File:** `src/lib.rs` (lines 30-35)
+
+Undecided.
+
+---
+"#;
+
+        let decisions = parse_decisions(content);
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].suggestion_id, "S001");
+        assert_eq!(decisions[0].decision, HumanDecision::Rejected);
+        assert_eq!(decisions[0].decided_by, "claude");
+        assert_eq!(
+            decisions[0].reason.as_deref(),
+            Some("This is intentional behavior for testing purposes.")
+        );
+        assert_eq!(decisions[1].suggestion_id, "S002");
+        assert_eq!(decisions[1].decision, HumanDecision::Deferred);
+        assert_eq!(decisions[1].decided_by, "alice");
+        assert_eq!(
+            decisions[1].deferred_until,
+            NaiveDate::from_ymd_opt(2025, 3, 1)
+        );
+    }
+
+    #[test]
+    fn test_summary_round_trip() {
+        let mut review = make_review();
+        review.suggestions[0].decision = Some(DecisionRecord {
+            suggestion_id: "S001".to_string(),
+            decision: HumanDecision::Accepted,
+            reason: Some("Good catch".to_string()),
+            decided_by: "bob".to_string(),
+            decided_at: Utc::now(),
+            deferred_until: None,
+            verified: false,
+        });
+
+        let summary = generate_summary(&review);
+        assert_eq!(parse_review_id(&summary), Some(review.id));
+
+        let decisions = parse_decisions(&summary);
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].suggestion_id, "S001");
+        assert_eq!(decisions[0].decided_by, "bob");
+        assert_eq!(decisions[0].reason.as_deref(), Some("Good catch"));
+    }
+
+    #[test]
+    fn test_reconcile_review() {
+        let mut review = make_review();
+        review.suggestions[1].decision = Some(DecisionRecord {
+            suggestion_id: "S002".to_string(),
+            decision: HumanDecision::Accepted,
+            reason: None,
+            decided_by: "bob".to_string(),
+            decided_at: Utc::now(),
+            deferred_until: None,
+            verified: false,
+        });
+
+        let decisions = vec![
+            MarkdownDecision {
+                suggestion_id: "S001".to_string(),
+                decision: HumanDecision::Rejected,
+                decided_by: "alice".to_string(),
+                reason: Some("False positive".to_string()),
+                deferred_until: None,
+            },
+            MarkdownDecision {
+                suggestion_id: "S002".to_string(),
+                decision: HumanDecision::Rejected,
+                decided_by: "alice".to_string(),
+                reason: None,
+                deferred_until: None,
+            },
+            MarkdownDecision {
+                suggestion_id: "S999".to_string(),
+                decision: HumanDecision::Rejected,
+                decided_by: "alice".to_string(),
+                reason: None,
+                deferred_until: None,
+            },
+        ];
+
+        let report = reconcile_review(&mut review, &decisions);
+
+        assert_eq!(report.imported, vec!["S001".to_string()]);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].suggestion_id, "S002");
+        assert_eq!(report.unknown, vec!["S999".to_string()]);
+
+        let s001 = review.suggestions[0].decision.as_ref().unwrap();
+        assert_eq!(s001.decision, HumanDecision::Rejected);
+        assert_eq!(s001.decided_by, "alice");
+
+        // Conflicting ledger decision is kept
+        let s002 = review.suggestions[1].decision.as_ref().unwrap();
+        assert_eq!(s002.decision, HumanDecision::Accepted);
+    }
+}
@@ -0,0 +1,350 @@
+//! Repositories sharing one ledger: selecting them and loading their configuration
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use tracing::info;
+
+use crate::config::{Config, CONFIG_PATH};
+use crate::github::{parse_repo, GitHubClient};
+use crate::models::Review;
+use crate::owners::{Owners, CODEOWNERS_PATHS};
+use crate::policy::Permissions;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// A `--repo` argument: an `owner/repo` name or a glob such as `acme/*`
+///
+/// Matching ignores case, like GitHub.
+#[derive(Debug, Clone)]
+pub struct RepoPattern {
+    raw: String,
+    pattern: Pattern,
+}
+
+impl FromStr for RepoPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = s.trim().to_string();
+        if raw.is_empty() {
+            anyhow::bail!("Repository pattern is empty");
+        }
+        let pattern =
+            Pattern::new(&raw).with_context(|| format!("Invalid repository pattern: {}", raw))?;
+        Ok(Self { raw, pattern })
+    }
+}
+
+impl fmt::Display for RepoPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl RepoPattern {
+    pub fn matches(&self, repo: &str) -> bool {
+        self.pattern.matches_with(repo, MATCH_OPTIONS)
+    }
+
+    /// The repository name, unless this is a glob matching several
+    pub fn exact(&self) -> Option<&str> {
+        (!self.raw.contains(['*', '?', '['])).then_some(self.raw.as_str())
+    }
+}
+
+/// Review configuration and finding owners of one repository
+#[derive(Debug, Clone, Default)]
+pub struct RepoConfig {
+    pub config: Config,
+    pub owners: Owners,
+}
+
+impl RepoConfig {
+    /// Load from the checkout at `root`
+    pub fn load(root: &Path) -> Result<Self> {
+        let config = Config::load(root.join(CONFIG_PATH))?;
+        let owners = Owners::load(root, &config.owners)?;
+        Ok(Self { config, owners })
+    }
+
+    /// Fetch `.ai-review/config.yml` and CODEOWNERS of `repo` at `commit_sha`
+    ///
+    /// Lets one ledger serve many repositories without a checkout of each. Missing
+    /// files fall back to defaults, as they do locally.
+    pub async fn fetch(github: &GitHubClient, repo: &str, commit_sha: &str) -> Result<Self> {
+        let (owner, name) = parse_repo(repo)?;
+
+        let config = match github
+            .find_file_contents(owner, name, CONFIG_PATH, commit_sha)
+            .await?
+        {
+            Some(content) => {
+                info!(repo, commit_sha, "Loaded repository configuration");
+                Config::parse(&content)
+                    .with_context(|| format!("Failed to parse {} in {}", CONFIG_PATH, repo))?
+            }
+            None => {
+                info!(repo, "Repository has no configuration, using defaults");
+                Config::default()
+            }
+        };
+
+        let codeowners_paths = match &config.owners.codeowners {
+            Some(path) => vec![path.as_str()],
+            None => CODEOWNERS_PATHS.to_vec(),
+        };
+        let mut codeowners = None;
+        for path in codeowners_paths {
+            if let Some(content) = github
+                .find_file_contents(owner, name, path, commit_sha)
+                .await?
+            {
+                info!(repo, path, "Loaded CODEOWNERS");
+                codeowners = Some(content);
+                break;
+            }
+        }
+        if codeowners.is_none() {
+            if let Some(path) = &config.owners.codeowners {
+                anyhow::bail!("CODEOWNERS file {} not found in {}", path, repo);
+            }
+        }
+
+        let owners = Owners::new(codeowners.as_deref().unwrap_or_default(), &config.owners)?;
+        Ok(Self { config, owners })
+    }
+
+    /// Who may decide on this repository's findings
+    pub fn permissions(&self) -> Permissions {
+        Permissions::new(self.owners.clone(), self.config.decision_policy.clone())
+    }
+}
+
+/// Configuration of each repository a command works on
+///
+/// Locally every repository uses the working directory's configuration. For a shared
+/// ledger, each repository's own is fetched at its most recently reviewed commit.
+#[derive(Debug, Clone, Default)]
+pub struct RepoConfigs {
+    fallback: RepoConfig,
+    repos: HashMap<String, RepoConfig>,
+}
+
+impl RepoConfigs {
+    /// Use the configuration of the checkout at `root` for every repository
+    pub fn local(root: &Path) -> Result<Self> {
+        Ok(Self {
+            fallback: RepoConfig::load(root)?,
+            repos: HashMap::new(),
+        })
+    }
+
+    /// Fetch the configuration of every repository `reviews` belong to
+    pub async fn fetch<'a>(
+        github: &GitHubClient,
+        reviews: impl IntoIterator<Item = &'a Review>,
+    ) -> Result<Self> {
+        let mut latest: HashMap<&str, &Review> = HashMap::new();
+        for review in reviews {
+            let entry = latest.entry(review.repo.as_str()).or_insert(review);
+            if review.created_at > entry.created_at {
+                *entry = review;
+            }
+        }
+
+        let mut repos = HashMap::new();
+        for (repo, review) in latest {
+            let config = RepoConfig::fetch(github, repo, &review.commit_sha).await?;
+            repos.insert(repo.to_string(), config);
+        }
+
+        Ok(Self {
+            fallback: RepoConfig::default(),
+            repos,
+        })
+    }
+
+    /// Configuration of `repo`
+    pub fn get(&self, repo: &str) -> &RepoConfig {
+        self.repos.get(repo).unwrap_or(&self.fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HumanDecision, ReviewContext, Severity, SuggestionItem};
+    use crate::policy::Identity;
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A contents API response for `path`, with `content` already base64 encoded
+    fn file(path: &str, content: &str) -> serde_json::Value {
+        let url = format!("https://api.github.com/repos/acme/api/contents/{}", path);
+        json!({
+            "name": path.rsplit('/').next(),
+            "path": path,
+            "sha": "0123456789abcdef",
+            "size": 64,
+            "url": url,
+            "type": "file",
+            "encoding": "base64",
+            "content": content,
+            "_links": { "self": url }
+        })
+    }
+
+    #[test]
+    fn test_repo_pattern() {
+        let exact: RepoPattern = "acme/api".parse().unwrap();
+        assert!(exact.matches("acme/api"));
+        assert!(exact.matches("Acme/API"));
+        assert!(!exact.matches("acme/api-gateway"));
+        assert_eq!(exact.exact(), Some("acme/api"));
+
+        let glob: RepoPattern = "acme/*".parse().unwrap();
+        assert!(glob.matches("acme/api"));
+        assert!(glob.matches("acme/web"));
+        assert!(!glob.matches("other/api"));
+        assert_eq!(glob.exact(), None);
+        assert_eq!(glob.to_string(), "acme/*");
+
+        let any: RepoPattern = "*".parse().unwrap();
+        assert!(any.matches("other/api"));
+        assert!("".parse::<RepoPattern>().is_err());
+        assert!("acme/[".parse::<RepoPattern>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let server = MockServer::start().await;
+        let github = GitHubClient::with_base_url("token", &server.uri()).unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/api/contents/.ai-review/config.yml"))
+            .and(query_param("ref", "abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(file(
+                ".ai-review/config.yml",
+                // review: { exclude_patterns: ["vendor/**"] }, owners: { admins: [root] }
+                "cmV2aWV3OgogIGV4Y2x1ZGVfcGF0dGVybnM6IFsidmVuZG9yLyoqIl0Kb3duZXJzOgogIGFkbWluczogW3Jvb3RdCg==",
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/api/contents/.github/CODEOWNERS"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    // *.rs @rustacean
+                    .set_body_json(file(".github/CODEOWNERS", "Ki5ycyBAcnVzdGFjZWFuCg==")),
+            )
+            .mount(&server)
+            .await;
+        // Everything else, including the other CODEOWNERS locations, is missing
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "message": "Not Found",
+                "documentation_url": "https://docs.github.com/rest"
+            })))
+            .mount(&server)
+            .await;
+
+        let repo = RepoConfig::fetch(&github, "acme/api", "abc123")
+            .await
+            .unwrap();
+        assert_eq!(repo.config.review.exclude_patterns, ["vendor/**"]);
+        assert_eq!(repo.owners.owners_of("src/main.rs"), ["@rustacean"]);
+        assert!(repo.owners.is_admin("root"));
+
+        let defaults = RepoConfig::fetch(&github, "acme/web", "abc123")
+            .await
+            .unwrap();
+        assert!(defaults.owners.owners_of("src/main.rs").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_per_repo_policies() {
+        let server = MockServer::start().await;
+        let github = GitHubClient::with_base_url("token", &server.uri()).unwrap();
+
+        // decision_policy: { reject_reason_required: [high] }
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/api/contents/.ai-review/config.yml"))
+            .and(query_param("ref", "new111"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(file(
+                ".ai-review/config.yml",
+                "ZGVjaXNpb25fcG9saWN5OgogIHJlamVjdF9yZWFzb25fcmVxdWlyZWQ6IFtoaWdoXQo=",
+            )))
+            .mount(&server)
+            .await;
+        // decision_policy: { require_verified: true }
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/web/contents/.ai-review/config.yml"))
+            .and(query_param("ref", "web222"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(file(
+                ".ai-review/config.yml",
+                "ZGVjaXNpb25fcG9saWN5OgogIHJlcXVpcmVfdmVyaWZpZWQ6IHRydWUK",
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "message": "Not Found"
+            })))
+            .mount(&server)
+            .await;
+
+        let review = |repo: &str, sha: &str, age_hours: i64| {
+            let mut review = Review::new(ReviewContext {
+                pr_number: Some(1),
+                repo: repo.to_string(),
+                branch: None,
+                commit_sha: sha.to_string(),
+                base_sha: None,
+            });
+            review.created_at -= chrono::Duration::hours(age_hours);
+            review.suggestions.push(
+                SuggestionItem::test("S001", "src/lib.rs")
+                    .with_severity(Severity::High)
+                    .with_description("Off by one"),
+            );
+            review
+        };
+        // The configuration at the newest reviewed commit of acme/api is used
+        let reviews = [
+            review("acme/api", "old000", 2),
+            review("acme/api", "new111", 1),
+            review("acme/web", "web222", 1),
+        ];
+        let configs = RepoConfigs::fetch(&github, &reviews).await.unwrap();
+
+        let alice = Identity::from("alice");
+        let item = &reviews[0].suggestions[0];
+        let api = configs.get("acme/api").permissions();
+        assert!(api
+            .check(&alice, item, HumanDecision::Rejected, None)
+            .is_err());
+        assert!(api.check_decider(&alice, item).is_ok());
+
+        let web = configs.get("acme/web").permissions();
+        assert!(web
+            .check(&alice, item, HumanDecision::Rejected, Some("Fine"))
+            .is_err());
+        let verified = Identity {
+            user: "alice".to_string(),
+            verified: true,
+        };
+        assert!(web
+            .check(&verified, item, HumanDecision::Rejected, None)
+            .is_ok());
+    }
+}
@@ -2,9 +2,7 @@ use chrono::{DateTime, Utc};
 use tracing::{debug, info};
 
 use crate::config::{AutoAction, AutoRule, Config};
-use crate::models::{
-    DecisionRecord, HumanDecision, Review, Severity, SuggestionItem, SuggestionType,
-};
+use crate::models::{DecisionRecord, HumanDecision, Review, Severity, SuggestionItem};
 
 /// Rules engine for automatic decisions
 pub struct RulesEngine {
@@ -25,8 +23,8 @@ impl RulesEngine {
         let mut count = 0;
 
         for item in &mut review.suggestions {
-            if item.decision.is_some() {
-                continue; // Already decided
+            if !item.is_pending() {
+                continue; // Already decided or resolved
             }
 
             if let Some((action, reason)) = self.evaluate_rules(item, review.created_at) {
@@ -42,6 +40,8 @@ impl RulesEngine {
                     reason: Some(format!("[Auto] {}", reason)),
                     decided_by: "auto-rules".to_string(),
                     decided_at: Utc::now(),
+                    deferred_until: None,
+                    verified: false,
                 });
 
                 info!(
@@ -97,6 +97,18 @@ impl RulesEngine {
         // Parse expressions like "field == 'value'" or "field > 0.95"
         let expr = expr.trim();
 
+        // Handle glob matches like "file_path matches 'src/legacy/**'"
+        if let Some((field, pattern)) = expr.split_once(" matches ") {
+            let pattern = pattern.trim().trim_matches('\'').trim_matches('"');
+            let Ok(pattern) = glob::Pattern::new(pattern) else {
+                return false;
+            };
+            return self
+                .get_field_values(field.trim(), ctx)
+                .iter()
+                .any(|value| pattern.matches(value));
+        }
+
         // Handle equality checks
         if expr.contains("==") {
             let parts: Vec<&str> = expr.split("==").collect();
@@ -105,7 +117,7 @@ impl RulesEngine {
             }
             let field = parts[0].trim();
             let value = parts[1].trim().trim_matches('\'').trim_matches('"');
-            return self.get_field_value(field, ctx) == value;
+            return self.get_field_values(field, ctx).iter().any(|v| v == value);
         }
 
         // Handle greater than
@@ -171,9 +183,19 @@ impl RulesEngine {
             "type" => ctx.suggestion_type.clone(),
             "age_days" => ctx.age_days.to_string(),
             "file_path" => ctx.file_path.clone(),
+            // Findings without a confidence never match a comparison
+            "confidence" => ctx.confidence.map(|c| c.to_string()).unwrap_or_default(),
             _ => String::new(),
         }
     }
+
+    /// Values to compare for `==` and `matches`; `tag` matches if any tag does
+    fn get_field_values(&self, field: &str, ctx: &RuleContext) -> Vec<String> {
+        match field {
+            "tag" => ctx.tags.clone(),
+            _ => vec![self.get_field_value(field, ctx)],
+        }
+    }
 }
 
 /// Context extracted from a suggestion for rule evaluation
@@ -182,6 +204,8 @@ struct RuleContext {
     suggestion_type: String,
     age_days: i64,
     file_path: String,
+    tags: Vec<String>,
+    confidence: Option<f64>,
 }
 
 impl RuleContext {
@@ -190,9 +214,11 @@ impl RuleContext {
 
         Self {
             severity: severity_to_string(item.suggestion.severity),
-            suggestion_type: type_to_string(item.suggestion.suggestion_type),
+            suggestion_type: item.suggestion.suggestion_type.as_str().to_string(),
             age_days,
             file_path: item.suggestion.location.file.clone(),
+            tags: item.suggestion.tags.clone(),
+            confidence: item.suggestion.confidence,
         }
     }
 }
@@ -207,38 +233,17 @@ fn severity_to_string(s: Severity) -> String {
     .to_string()
 }
 
-fn type_to_string(t: SuggestionType) -> String {
-    match t {
-        SuggestionType::Security => "security",
-        SuggestionType::Performance => "performance",
-        SuggestionType::Style => "style",
-        SuggestionType::Logic => "logic",
-        SuggestionType::Documentation => "documentation",
-    }
-    .to_string()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{Location, Suggestion};
+    use crate::models::SuggestionType;
 
     fn make_suggestion(severity: Severity, stype: SuggestionType) -> SuggestionItem {
-        SuggestionItem {
-            suggestion: Suggestion {
-                id: "S001".to_string(),
-                suggestion_type: stype,
-                severity,
-                location: Location {
-                    file: "src/main.rs".to_string(),
-                    line_start: 10,
-                    line_end: 15,
-                },
-                description: "Test suggestion".to_string(),
-                proposed_fix: None,
-            },
-            decision: None,
-        }
+        SuggestionItem::test("S001", "src/main.rs")
+            .with_type(stype)
+            .with_severity(severity)
+            .with_lines(10, 15)
+            .with_description("Test suggestion")
     }
 
     #[test]
@@ -284,4 +289,80 @@ mod tests {
         let result = engine.evaluate_rules(&item, created_at);
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_file_path_glob() {
+        let rules = vec![AutoRule {
+            condition: "file_path matches 'src/legacy/**'".to_string(),
+            action: AutoAction::AutoDefer,
+            reason: "Legacy code".to_string(),
+        }];
+
+        let engine = RulesEngine::new(rules);
+        let created_at = Utc::now();
+
+        let mut item = make_suggestion(Severity::High, SuggestionType::Logic);
+        item.suggestion.location.file = "src/legacy/old/db.rs".to_string();
+        let result = engine.evaluate_rules(&item, created_at);
+        assert_eq!(result.unwrap().0, AutoAction::AutoDefer);
+
+        item.suggestion.location.file = "src/main.rs".to_string();
+        assert!(engine.evaluate_rules(&item, created_at).is_none());
+    }
+
+    #[test]
+    fn test_custom_type_and_tags() {
+        let rules = vec![
+            AutoRule {
+                condition: "type == 'concurrency' AND tag == 'flaky'".to_string(),
+                action: AutoAction::AutoDefer,
+                reason: "Known flaky area".to_string(),
+            },
+            AutoRule {
+                condition: "tag matches 'generated-*'".to_string(),
+                action: AutoAction::AutoDismiss,
+                reason: "Generated code".to_string(),
+            },
+        ];
+
+        let engine = RulesEngine::new(rules);
+        let created_at = Utc::now();
+
+        let mut item = make_suggestion(
+            Severity::Medium,
+            SuggestionType::Custom("concurrency".to_string()),
+        );
+        assert!(engine.evaluate_rules(&item, created_at).is_none());
+
+        item.suggestion.tags = vec!["locking".to_string(), "flaky".to_string()];
+        let result = engine.evaluate_rules(&item, created_at);
+        assert_eq!(result.unwrap().0, AutoAction::AutoDefer);
+
+        let mut item = make_suggestion(Severity::Low, SuggestionType::Style);
+        item.suggestion.tags = vec!["generated-protobuf".to_string()];
+        let result = engine.evaluate_rules(&item, created_at);
+        assert_eq!(result.unwrap().0, AutoAction::AutoDismiss);
+    }
+
+    #[test]
+    fn test_confidence() {
+        let rules = vec![AutoRule {
+            condition: "confidence < 0.5 AND severity == 'low'".to_string(),
+            action: AutoAction::AutoDismiss,
+            reason: "Low confidence nit".to_string(),
+        }];
+
+        let engine = RulesEngine::new(rules);
+        let created_at = Utc::now();
+
+        let mut item = make_suggestion(Severity::Low, SuggestionType::Style);
+        assert!(engine.evaluate_rules(&item, created_at).is_none());
+
+        item.suggestion.confidence = Some(0.3);
+        let result = engine.evaluate_rules(&item, created_at);
+        assert_eq!(result.unwrap().0, AutoAction::AutoDismiss);
+
+        item.suggestion.confidence = Some(0.9);
+        assert!(engine.evaluate_rules(&item, created_at).is_none());
+    }
 }
@@ -0,0 +1,896 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::ledger::RepoStats;
+use crate::models::{
+    HumanDecision, LlmInteraction, OutputQuality, Review, ReviewStatus, Severity, SuggestionItem,
+    SuggestionType,
+};
+use crate::repos::RepoPattern;
+
+/// How many files and reviewers to list
+const TOP_N: usize = 10;
+
+const SEVERITIES: [Severity; 4] = [
+    Severity::Critical,
+    Severity::High,
+    Severity::Medium,
+    Severity::Low,
+];
+
+const SUGGESTION_TYPES: [SuggestionType; 5] = [
+    SuggestionType::Security,
+    SuggestionType::Performance,
+    SuggestionType::Logic,
+    SuggestionType::Style,
+    SuggestionType::Documentation,
+];
+
+/// Decision counts for a group of suggestions
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DecisionRates {
+    pub total: u64,
+    pub accepted: u64,
+    pub rejected: u64,
+    pub deferred: u64,
+    pub undecided: u64,
+}
+
+impl DecisionRates {
+    fn record(&mut self, decision: Option<HumanDecision>) {
+        self.total += 1;
+        match decision {
+            Some(HumanDecision::Accepted) => self.accepted += 1,
+            Some(HumanDecision::Rejected) => self.rejected += 1,
+            Some(HumanDecision::Deferred) => self.deferred += 1,
+            None => self.undecided += 1,
+        }
+    }
+
+    fn decided(&self) -> u64 {
+        self.accepted + self.rejected + self.deferred
+    }
+
+    /// Share of decided suggestions that were accepted
+    pub fn acceptance_rate(&self) -> Option<f64> {
+        (self.decided() > 0).then(|| self.accepted as f64 / self.decided() as f64)
+    }
+
+    /// Share of decided suggestions that were rejected
+    pub fn rejection_rate(&self) -> Option<f64> {
+        (self.decided() > 0).then(|| self.rejected as f64 / self.decided() as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeverityRates {
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub rates: DecisionRates,
+    pub acceptance_rate: Option<f64>,
+    pub rejection_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeRates {
+    pub suggestion_type: SuggestionType,
+    #[serde(flatten)]
+    pub rates: DecisionRates,
+    pub acceptance_rate: Option<f64>,
+    pub rejection_rate: Option<f64>,
+}
+
+/// Activity of one repository in a shared ledger
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepoRates {
+    pub repo: String,
+    pub reviews: u64,
+    pub pending_suggestions: u64,
+    #[serde(flatten)]
+    pub rates: DecisionRates,
+    pub acceptance_rate: Option<f64>,
+    pub rejection_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileCount {
+    pub file: String,
+    pub findings: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewerCount {
+    pub reviewer: String,
+    pub decisions: u64,
+}
+
+/// Activity in the week starting on `week_start` (a Monday)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WeeklyBucket {
+    pub week_start: NaiveDate,
+    pub reviews: u64,
+    pub findings: u64,
+    pub accepted: u64,
+    pub rejected: u64,
+    pub deferred: u64,
+}
+
+/// LLM usage and estimated cost for one calendar month
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MonthlySpend {
+    /// Month as `YYYY-MM`
+    pub month: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Estimated cost in USD of the requests that could be priced
+    pub cost_usd: f64,
+}
+
+/// Picks the reviews and findings statistics count, so that each finding counts once
+///
+/// Superseded (stale) reviews live on in the review that replaced them and aren't
+/// counted. Findings a follow-up review carried over from its nearest counted
+/// ancestor, walking through stale reviews, are left to that ancestor.
+pub(crate) struct CountedFindings<'a> {
+    reviews: HashMap<Uuid, &'a Review>,
+}
+
+impl<'a> CountedFindings<'a> {
+    pub(crate) fn new(reviews: impl IntoIterator<Item = &'a Review>) -> Self {
+        Self {
+            reviews: reviews.into_iter().map(|r| (r.id, r)).collect(),
+        }
+    }
+
+    /// Whether `review` counts at all
+    pub(crate) fn is_counted(review: &Review) -> bool {
+        review.status != ReviewStatus::Stale
+    }
+
+    /// Nearest earlier counted review, skipping stale ones in between
+    fn counted_ancestor(&self, review: &Review) -> Option<&'a Review> {
+        let mut id = review.previous_review_id;
+        // Bounded so a corrupt ledger with a cycle can't loop forever
+        for _ in 0..self.reviews.len() {
+            let previous = *self.reviews.get(&id?)?;
+            if Self::is_counted(previous) {
+                return Some(previous);
+            }
+            id = previous.previous_review_id;
+        }
+        None
+    }
+
+    /// Findings counted for `review`: none if it's stale, otherwise those not carried
+    /// over from its counted ancestor
+    pub(crate) fn findings(&self, review: &'a Review) -> Vec<&'a SuggestionItem> {
+        if !Self::is_counted(review) {
+            return Vec::new();
+        }
+        let ancestor = self.counted_ancestor(review);
+        review
+            .suggestions
+            .iter()
+            .filter(|item| {
+                ancestor.is_none_or(|ancestor| {
+                    !ancestor
+                        .suggestions
+                        .iter()
+                        .any(|old| old.suggestion.is_same_finding(&item.suggestion))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Aggregate statistics over a set of reviews
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewStats {
+    pub repo: Option<String>,
+    pub since: Option<NaiveDate>,
+    pub overview: RepoStats,
+    pub overall: DecisionRates,
+    pub by_type: Vec<TypeRates>,
+    pub by_severity: Vec<SeverityRates>,
+    pub by_repo: Vec<RepoRates>,
+    /// Mean time from review creation to decision, in hours
+    pub mean_hours_to_decision: Option<f64>,
+    /// Model output that had to be coerced, dropped or repaired
+    pub output_quality: OutputQuality,
+    pub top_files: Vec<FileCount>,
+    pub top_reviewers: Vec<ReviewerCount>,
+    pub weekly: Vec<WeeklyBucket>,
+    pub monthly_spend: Vec<MonthlySpend>,
+}
+
+impl ReviewStats {
+    /// Compute statistics for reviews of repositories matching `repo`, created on or
+    /// after `since`
+    ///
+    /// Works on any ledger backend since it only needs the reviews and the LLM
+    /// interactions recorded for them. Each finding is counted once, see [`CountedFindings`].
+    pub fn compute(
+        reviews: &[Review],
+        interactions: &[LlmInteraction],
+        repo: Option<&RepoPattern>,
+        since: Option<NaiveDate>,
+    ) -> Self {
+        let reviews: Vec<&Review> = reviews
+            .iter()
+            .filter(|r| repo.is_none_or(|repo| repo.matches(&r.repo)))
+            .filter(|r| since.is_none_or(|since| r.created_at.date_naive() >= since))
+            .collect();
+        let counted = CountedFindings::new(reviews.iter().copied());
+
+        let mut overview = RepoStats {
+            pending_reviews: 0,
+            decided_reviews: 0,
+            total_reviews: 0,
+            pending_suggestions: 0,
+            critical_pending: 0,
+        };
+        let mut overall = DecisionRates::default();
+        let mut by_type: HashMap<SuggestionType, DecisionRates> = HashMap::new();
+        let mut by_severity: HashMap<Severity, DecisionRates> = HashMap::new();
+        let mut by_repo: BTreeMap<&str, RepoRates> = BTreeMap::new();
+        let mut files: HashMap<&str, u64> = HashMap::new();
+        let mut reviewers: HashMap<&str, u64> = HashMap::new();
+        let mut weekly: BTreeMap<NaiveDate, WeeklyBucket> = BTreeMap::new();
+        let mut decision_time = Duration::zero();
+        let mut decision_count = 0;
+        let mut output_quality = OutputQuality::default();
+
+        for review in &reviews {
+            // Stale reviews were still real model runs
+            output_quality.add(&review.output_quality);
+            if !CountedFindings::is_counted(review) {
+                continue;
+            }
+
+            overview.total_reviews += 1;
+            match review.status {
+                ReviewStatus::Pending => overview.pending_reviews += 1,
+                ReviewStatus::Decided => overview.decided_reviews += 1,
+                _ => {}
+            }
+
+            let date = review.created_at.date_naive();
+            let week_start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+            let bucket = weekly.entry(week_start).or_insert_with(|| WeeklyBucket {
+                week_start,
+                ..Default::default()
+            });
+            bucket.reviews += 1;
+
+            let repo = by_repo
+                .entry(review.repo.as_str())
+                .or_insert_with(|| RepoRates {
+                    repo: review.repo.clone(),
+                    ..Default::default()
+                });
+            repo.reviews += 1;
+
+            for item in counted.findings(review) {
+                let s = &item.suggestion;
+                let decision = item.decision.as_ref().map(|d| d.decision);
+
+                overall.record(decision);
+                repo.rates.record(decision);
+                by_type
+                    .entry(s.suggestion_type.clone())
+                    .or_default()
+                    .record(decision);
+                by_severity.entry(s.severity).or_default().record(decision);
+                *files.entry(s.location.file.as_str()).or_default() += 1;
+
+                bucket.findings += 1;
+                match decision {
+                    Some(HumanDecision::Accepted) => bucket.accepted += 1,
+                    Some(HumanDecision::Rejected) => bucket.rejected += 1,
+                    Some(HumanDecision::Deferred) => bucket.deferred += 1,
+                    None => {}
+                }
+
+                match &item.decision {
+                    Some(record) => {
+                        *reviewers.entry(record.decided_by.as_str()).or_default() += 1;
+
+                        let elapsed = record.decided_at - review.created_at;
+                        if elapsed >= Duration::zero() {
+                            decision_time += elapsed;
+                            decision_count += 1;
+                        }
+                    }
+                    None if review.status == ReviewStatus::Pending && item.is_pending() => {
+                        overview.pending_suggestions += 1;
+                        repo.pending_suggestions += 1;
+                        if s.severity == Severity::Critical {
+                            overview.critical_pending += 1;
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        // Built-in types are always listed, custom categories once they have findings
+        let mut custom: Vec<SuggestionType> = by_type
+            .keys()
+            .filter(|t| !SUGGESTION_TYPES.contains(t))
+            .cloned()
+            .collect();
+        custom.sort();
+        let by_type = SUGGESTION_TYPES
+            .into_iter()
+            .chain(custom)
+            .map(|t| {
+                let rates = by_type.remove(&t).unwrap_or_default();
+                TypeRates {
+                    suggestion_type: t,
+                    acceptance_rate: rates.acceptance_rate(),
+                    rejection_rate: rates.rejection_rate(),
+                    rates,
+                }
+            })
+            .collect();
+
+        let by_severity = SEVERITIES
+            .iter()
+            .map(|s| {
+                let rates = by_severity.remove(s).unwrap_or_default();
+                SeverityRates {
+                    severity: *s,
+                    acceptance_rate: rates.acceptance_rate(),
+                    rejection_rate: rates.rejection_rate(),
+                    rates,
+                }
+            })
+            .collect();
+
+        let by_repo = by_repo
+            .into_values()
+            .map(|mut r| {
+                r.acceptance_rate = r.rates.acceptance_rate();
+                r.rejection_rate = r.rates.rejection_rate();
+                r
+            })
+            .collect();
+
+        let review_ids: HashSet<_> = reviews.iter().map(|r| r.id).collect();
+        let mut monthly: BTreeMap<String, MonthlySpend> = BTreeMap::new();
+        for i in interactions
+            .iter()
+            .filter(|i| i.review_id.is_some_and(|id| review_ids.contains(&id)))
+        {
+            let month = i.created_at.format("%Y-%m").to_string();
+            let spend = monthly
+                .entry(month.clone())
+                .or_insert_with(|| MonthlySpend {
+                    month,
+                    ..Default::default()
+                });
+            spend.requests += 1;
+            spend.prompt_tokens += i.prompt_tokens.map(u64::from).unwrap_or_default();
+            spend.completion_tokens += i.completion_tokens.map(u64::from).unwrap_or_default();
+            spend.cost_usd += i.cost_usd.unwrap_or_default();
+        }
+
+        let mean_hours_to_decision = (decision_count > 0)
+            .then(|| decision_time.num_seconds() as f64 / 3600.0 / decision_count as f64);
+
+        Self {
+            repo: repo.map(|r| r.to_string()),
+            since,
+            overview,
+            overall,
+            by_type,
+            by_severity,
+            by_repo,
+            mean_hours_to_decision,
+            output_quality,
+            top_files: top_n(files)
+                .into_iter()
+                .map(|(file, findings)| FileCount { file, findings })
+                .collect(),
+            top_reviewers: top_n(reviewers)
+                .into_iter()
+                .map(|(reviewer, decisions)| ReviewerCount {
+                    reviewer,
+                    decisions,
+                })
+                .collect(),
+            weekly: weekly.into_values().collect(),
+            monthly_spend: monthly.into_values().collect(),
+        }
+    }
+
+    /// Render the statistics as plain-text tables
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+
+        let scope = self.repo.as_deref().unwrap_or("all repositories");
+        match self.since {
+            Some(since) => writeln!(out, "Review statistics for {} since {}", scope, since),
+            None => writeln!(out, "Review statistics for {}", scope),
+        }
+        .unwrap();
+        writeln!(out).unwrap();
+
+        writeln!(
+            out,
+            "Reviews: {} total, {} pending, {} decided",
+            self.overview.total_reviews,
+            self.overview.pending_reviews,
+            self.overview.decided_reviews
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "Suggestions: {} total, {} pending ({} critical)",
+            self.overall.total, self.overview.pending_suggestions, self.overview.critical_pending
+        )
+        .unwrap();
+        match self.mean_hours_to_decision {
+            Some(hours) => writeln!(out, "Mean time to decision: {:.1} hours", hours),
+            None => writeln!(out, "Mean time to decision: -"),
+        }
+        .unwrap();
+        writeln!(
+            out,
+            "Model output: {} coerced fields, {} dropped fields, {} dropped suggestions, {} repair rounds",
+            self.output_quality.coerced_fields,
+            self.output_quality.dropped_fields,
+            self.output_quality.dropped_suggestions,
+            self.output_quality.repair_rounds
+        )
+        .unwrap();
+
+        // A per-repository breakdown only says something for a shared ledger
+        if self.by_repo.len() > 1 {
+            let width = self
+                .by_repo
+                .iter()
+                .map(|r| r.repo.len())
+                .max()
+                .unwrap_or_default()
+                .max(16);
+            writeln!(
+                out,
+                "\n{:<width$} {:>7} {:>7} {:>6} {:>9} {:>9} {:>9}",
+                "Repository", "Reviews", "Pending", "Total", "Accepted", "Rejected", "Deferred"
+            )
+            .unwrap();
+            for r in &self.by_repo {
+                writeln!(
+                    out,
+                    "{:<width$} {:>7} {:>7} {:>6} {:>9} {:>9} {:>9}",
+                    r.repo,
+                    r.reviews,
+                    r.pending_suggestions,
+                    r.rates.total,
+                    format_count(r.rates.accepted, r.acceptance_rate),
+                    format_count(r.rates.rejected, r.rejection_rate),
+                    r.rates.deferred
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(
+            out,
+            "\n{:<16} {:>6} {:>9} {:>9} {:>9}",
+            "Type", "Total", "Accepted", "Rejected", "Deferred"
+        )
+        .unwrap();
+        for t in &self.by_type {
+            write_rates_row(&mut out, &t.suggestion_type.to_string(), &t.rates);
+        }
+
+        writeln!(
+            out,
+            "\n{:<16} {:>6} {:>9} {:>9} {:>9}",
+            "Severity", "Total", "Accepted", "Rejected", "Deferred"
+        )
+        .unwrap();
+        for s in &self.by_severity {
+            write_rates_row(&mut out, &format!("{:?}", s.severity), &s.rates);
+        }
+
+        if !self.top_files.is_empty() {
+            writeln!(out, "\nFiles with the most findings:").unwrap();
+            for f in &self.top_files {
+                writeln!(out, "  {:>5}  {}", f.findings, f.file).unwrap();
+            }
+        }
+
+        if !self.top_reviewers.is_empty() {
+            writeln!(out, "\nMost active reviewers:").unwrap();
+            for r in &self.top_reviewers {
+                writeln!(out, "  {:>5}  {}", r.decisions, r.reviewer).unwrap();
+            }
+        }
+
+        if !self.weekly.is_empty() {
+            writeln!(
+                out,
+                "\n{:<12} {:>7} {:>8} {:>9} {:>9} {:>9}",
+                "Week of", "Reviews", "Findings", "Accepted", "Rejected", "Deferred"
+            )
+            .unwrap();
+            for w in &self.weekly {
+                writeln!(
+                    out,
+                    "{:<12} {:>7} {:>8} {:>9} {:>9} {:>9}",
+                    w.week_start.to_string(),
+                    w.reviews,
+                    w.findings,
+                    w.accepted,
+                    w.rejected,
+                    w.deferred
+                )
+                .unwrap();
+            }
+        }
+
+        if !self.monthly_spend.is_empty() {
+            writeln!(
+                out,
+                "\n{:<12} {:>8} {:>13} {:>13} {:>10}",
+                "LLM spend", "Requests", "Prompt tok", "Output tok", "Est. cost"
+            )
+            .unwrap();
+            for m in &self.monthly_spend {
+                writeln!(
+                    out,
+                    "{:<12} {:>8} {:>13} {:>13} {:>10}",
+                    m.month,
+                    m.requests,
+                    m.prompt_tokens,
+                    m.completion_tokens,
+                    format!("${:.2}", m.cost_usd)
+                )
+                .unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+fn write_rates_row(out: &mut String, label: &str, rates: &DecisionRates) {
+    writeln!(
+        out,
+        "{:<16} {:>6} {:>9} {:>9} {:>9}",
+        label,
+        rates.total,
+        format_count(rates.accepted, rates.acceptance_rate()),
+        format_count(rates.rejected, rates.rejection_rate()),
+        rates.deferred
+    )
+    .unwrap();
+}
+
+fn format_count(count: u64, rate: Option<f64>) -> String {
+    match rate {
+        Some(rate) => format!("{} ({:.0}%)", count, rate * 100.0),
+        None => count.to_string(),
+    }
+}
+
+/// Highest counts first, ties broken by name
+fn top_n(counts: HashMap<&str, u64>) -> Vec<(String, u64)> {
+    let mut counts: Vec<(String, u64)> = counts
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(TOP_N);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DecisionRecord, ReviewContext, SuggestionItem};
+    use chrono::{TimeZone, Utc};
+
+    fn make_review(repo: &str, created: (i32, u32, u32)) -> Review {
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(1),
+            repo: repo.to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+        review.created_at = Utc
+            .with_ymd_and_hms(created.0, created.1, created.2, 12, 0, 0)
+            .unwrap();
+        review
+    }
+
+    fn add(
+        review: &mut Review,
+        file: &str,
+        severity: Severity,
+        suggestion_type: SuggestionType,
+        decision: Option<(HumanDecision, &str, i64)>,
+    ) {
+        let id = format!("S{:03}", review.suggestions.len() + 1);
+        let item = SuggestionItem::test(&id, file)
+            .with_type(suggestion_type)
+            .with_severity(severity);
+        review.suggestions.push(SuggestionItem {
+            decision: decision.map(|(decision, by, hours)| DecisionRecord {
+                suggestion_id: id,
+                decision,
+                reason: None,
+                decided_by: by.to_string(),
+                decided_at: review.created_at + Duration::hours(hours),
+                deferred_until: None,
+                verified: false,
+            }),
+            ..item
+        });
+    }
+
+    fn sample() -> Vec<Review> {
+        // Monday 2024-06-03 and Wednesday 2024-06-12
+        let mut first = make_review("owner/repo", (2024, 6, 3));
+        add(
+            &mut first,
+            "src/db.rs",
+            Severity::Critical,
+            SuggestionType::Security,
+            Some((HumanDecision::Accepted, "alice", 2)),
+        );
+        add(
+            &mut first,
+            "src/db.rs",
+            Severity::Low,
+            SuggestionType::Style,
+            Some((HumanDecision::Rejected, "bob", 4)),
+        );
+        first.status = ReviewStatus::Decided;
+
+        let mut second = make_review("owner/repo", (2024, 6, 12));
+        add(
+            &mut second,
+            "src/db.rs",
+            Severity::Critical,
+            SuggestionType::Security,
+            None,
+        );
+        add(
+            &mut second,
+            "src/api.rs",
+            Severity::Medium,
+            SuggestionType::Logic,
+            Some((HumanDecision::Accepted, "alice", 6)),
+        );
+
+        let other = make_review("other/repo", (2024, 6, 12));
+
+        vec![first, second, other]
+    }
+
+    #[test]
+    fn test_compute_rates_and_totals() {
+        let repo = "owner/repo".parse().unwrap();
+        let stats = ReviewStats::compute(&sample(), &[], Some(&repo), None);
+
+        assert_eq!(stats.overview.total_reviews, 2);
+        assert_eq!(stats.overview.pending_reviews, 1);
+        assert_eq!(stats.overview.pending_suggestions, 1);
+        assert_eq!(stats.overview.critical_pending, 1);
+
+        assert_eq!(stats.overall.total, 4);
+        assert_eq!(stats.overall.undecided, 1);
+
+        let security = &stats.by_type[0];
+        assert_eq!(security.suggestion_type, SuggestionType::Security);
+        assert_eq!(security.rates.total, 2);
+        assert_eq!(security.acceptance_rate, Some(1.0));
+
+        let low = &stats.by_severity[3];
+        assert_eq!(low.severity, Severity::Low);
+        assert_eq!(low.rejection_rate, Some(1.0));
+
+        assert_eq!(stats.mean_hours_to_decision, Some(4.0));
+        assert_eq!(stats.top_files[0].file, "src/db.rs");
+        assert_eq!(stats.top_files[0].findings, 3);
+        assert_eq!(stats.top_reviewers[0].reviewer, "alice");
+        assert_eq!(stats.top_reviewers[0].decisions, 2);
+    }
+
+    #[test]
+    fn test_pr_reviewed_twice() {
+        // Superseded while pending: only the follow-up, which carries the finding, counts
+        let mut stale = make_review("owner/repo", (2024, 6, 3));
+        add(
+            &mut stale,
+            "src/db.rs",
+            Severity::High,
+            SuggestionType::Security,
+            None,
+        );
+        stale.status = ReviewStatus::Stale;
+        let mut follow_up = make_review("owner/repo", (2024, 6, 4));
+        follow_up.previous_review_id = Some(stale.id);
+        follow_up.suggestions = stale.suggestions.clone();
+        add(
+            &mut follow_up,
+            "src/api.rs",
+            Severity::Low,
+            SuggestionType::Style,
+            None,
+        );
+        follow_up.suggestions[0].decision = Some(DecisionRecord {
+            suggestion_id: "S001".to_string(),
+            decision: HumanDecision::Accepted,
+            reason: None,
+            decided_by: "alice".to_string(),
+            decided_at: follow_up.created_at,
+            deferred_until: None,
+            verified: false,
+        });
+
+        let stats = ReviewStats::compute(&[stale, follow_up.clone()], &[], None, None);
+        assert_eq!(stats.overview.total_reviews, 1);
+        assert_eq!(stats.overall.total, 2);
+        assert_eq!(stats.overall.accepted, 1);
+
+        // Decided before the follow-up: its decision carried forward is counted once
+        let mut decided = follow_up.clone();
+        decided.id = Uuid::new_v4();
+        decided.suggestions.truncate(1);
+        decided.status = ReviewStatus::Decided;
+        let mut next = follow_up;
+        next.previous_review_id = Some(decided.id);
+
+        let stats = ReviewStats::compute(&[decided.clone(), next.clone()], &[], None, None);
+        assert_eq!(stats.overview.total_reviews, 2);
+        assert_eq!(stats.overall.total, 2);
+        assert_eq!(stats.overall.accepted, 1);
+        assert_eq!(stats.top_reviewers[0].decisions, 1);
+
+        // Decided, then a push superseded before anyone looked, then another push:
+        // the last review's carried findings still belong to the first
+        let mut stale = next.clone();
+        stale.id = Uuid::new_v4();
+        stale.status = ReviewStatus::Stale;
+        let mut last = next;
+        last.id = Uuid::new_v4();
+        last.previous_review_id = Some(stale.id);
+
+        let stats = ReviewStats::compute(&[decided, stale, last], &[], None, None);
+        assert_eq!(stats.overview.total_reviews, 2);
+        assert_eq!(stats.overall.total, 2);
+        assert_eq!(stats.overall.accepted, 1);
+        assert_eq!(stats.top_reviewers[0].decisions, 1);
+    }
+
+    #[test]
+    fn test_custom_type_rates() {
+        let mut reviews = sample();
+        add(
+            &mut reviews[2],
+            "src/pool.rs",
+            Severity::High,
+            SuggestionType::Custom("concurrency".to_string()),
+            Some((HumanDecision::Accepted, "carol", 1)),
+        );
+        let stats = ReviewStats::compute(&reviews, &[], None, None);
+
+        let types: Vec<String> = stats
+            .by_type
+            .iter()
+            .map(|t| t.suggestion_type.to_string())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "Security",
+                "Performance",
+                "Logic",
+                "Style",
+                "Documentation",
+                "concurrency"
+            ]
+        );
+        assert_eq!(stats.by_type[5].rates.total, 1);
+        assert_eq!(stats.by_type[5].acceptance_rate, Some(1.0));
+    }
+
+    #[test]
+    fn test_repo_breakdown() {
+        let mut reviews = sample();
+        add(
+            &mut reviews[2],
+            "web/app.js",
+            Severity::High,
+            SuggestionType::Logic,
+            Some((HumanDecision::Rejected, "carol", 1)),
+        );
+        reviews.push(make_review("acme/api", (2024, 6, 12)));
+
+        let repo = "*/repo".parse().unwrap();
+        let stats = ReviewStats::compute(&reviews, &[], Some(&repo), None);
+        assert_eq!(stats.overview.total_reviews, 3);
+        let repos: Vec<&str> = stats.by_repo.iter().map(|r| r.repo.as_str()).collect();
+        assert_eq!(repos, ["other/repo", "owner/repo"]);
+        assert_eq!(stats.by_repo[0].reviews, 1);
+        assert_eq!(stats.by_repo[0].rejection_rate, Some(1.0));
+        assert_eq!(stats.by_repo[1].reviews, 2);
+        assert_eq!(stats.by_repo[1].pending_suggestions, 1);
+        assert_eq!(stats.by_repo[1].rates.total, 4);
+
+        let table = stats.to_table();
+        assert!(table.contains("Review statistics for */repo"));
+        assert!(table.contains("Repository"));
+        // A single repository needs no breakdown
+        let repo = "owner/*".parse().unwrap();
+        let single = ReviewStats::compute(&reviews, &[], Some(&repo), None);
+        assert!(!single.to_table().contains("Repository"));
+    }
+
+    #[test]
+    fn test_weekly_buckets_and_since() {
+        let stats = ReviewStats::compute(&sample(), &[], None, None);
+        assert_eq!(stats.weekly.len(), 2);
+        assert_eq!(
+            stats.weekly[0].week_start,
+            NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()
+        );
+        assert_eq!(
+            stats.weekly[1].week_start,
+            NaiveDate::from_ymd_opt(2024, 6, 10).unwrap()
+        );
+        assert_eq!(stats.weekly[1].reviews, 2);
+        assert_eq!(stats.weekly[1].findings, 2);
+
+        let stats =
+            ReviewStats::compute(&sample(), &[], None, NaiveDate::from_ymd_opt(2024, 6, 10));
+        assert_eq!(stats.overview.total_reviews, 2);
+        assert_eq!(stats.overall.accepted, 1);
+        assert!(stats.to_table().contains("since 2024-06-10"));
+    }
+
+    #[test]
+    fn test_monthly_spend() {
+        let reviews = sample();
+        let interaction = |review: &Review, month: u32, cost: Option<f64>| LlmInteraction {
+            id: uuid::Uuid::new_v4(),
+            review_id: Some(review.id),
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            created_at: Utc.with_ymd_and_hms(2024, month, 15, 12, 0, 0).unwrap(),
+            latency_ms: 900,
+            request: serde_json::json!({}),
+            response: serde_json::json!({}),
+            prompt_tokens: Some(1000),
+            completion_tokens: Some(200),
+            cost_usd: cost,
+        };
+        let interactions = vec![
+            interaction(&reviews[0], 6, Some(0.5)),
+            interaction(&reviews[1], 6, None),
+            interaction(&reviews[1], 7, Some(0.25)),
+            interaction(&reviews[2], 6, Some(9.0)),
+        ];
+
+        let repo = "owner/repo".parse().unwrap();
+        let stats = ReviewStats::compute(&reviews, &interactions, Some(&repo), None);
+        assert_eq!(stats.monthly_spend.len(), 2);
+        assert_eq!(stats.monthly_spend[0].month, "2024-06");
+        assert_eq!(stats.monthly_spend[0].requests, 2);
+        assert_eq!(stats.monthly_spend[0].prompt_tokens, 2000);
+        assert_eq!(stats.monthly_spend[0].cost_usd, 0.5);
+        assert_eq!(stats.monthly_spend[1].month, "2024-07");
+        assert!(stats.to_table().contains("2024-07"));
+    }
+}
@@ -4,6 +4,8 @@ use std::path::Path;
 use anyhow::Result;
 use tracing::{debug, info};
 
+use crate::models::{HumanDecision, Review};
+
 /// A rejected finding extracted from review markdown files
 #[derive(Debug, Clone)]
 pub struct RejectedFinding {
@@ -37,7 +39,7 @@ impl Rejections {
             let entry = entry?;
             let path = entry.path();
 
-            if path.extension().map_or(false, |ext| ext == "md") {
+            if path.extension().is_some_and(|ext| ext == "md") {
                 if let Ok(content) = fs::read_to_string(&path) {
                     let findings = parse_rejections(&content);
                     items.extend(findings);
@@ -55,6 +57,33 @@ impl Rejections {
         Self::load_from_reviews("lgtm-reviews")
     }
 
+    /// Collect rejected findings straight from ledger reviews, e.g. those of one repository
+    pub fn from_reviews<'a>(reviews: impl IntoIterator<Item = &'a Review>) -> Self {
+        let items: Vec<RejectedFinding> = reviews
+            .into_iter()
+            .flat_map(|review| &review.suggestions)
+            .filter_map(|item| {
+                let decision = item.decision.as_ref()?;
+                if decision.decision != HumanDecision::Rejected {
+                    return None;
+                }
+                let s = &item.suggestion;
+                Some(RejectedFinding {
+                    file: s.location.file.clone(),
+                    line_start: s.location.line_start,
+                    line_end: s.location.line_end,
+                    finding_type: s.suggestion_type.as_str().to_string(),
+                    description: s.description.clone(),
+                    reason: decision.reason.clone().unwrap_or_default(),
+                })
+            })
+            .collect();
+
+        info!(count = items.len(), "Loaded rejections from the ledger");
+
+        Self { items }
+    }
+
     /// Generate a prompt snippet listing rejected findings for OpenAI
     pub fn to_prompt(&self) -> String {
         if self.items.is_empty() {
@@ -105,6 +134,11 @@ fn parse_rejections(content: &str) -> Vec<RejectedFinding> {
             while j < lines.len() {
                 let line = lines[j];
                 if line.starts_with("**Proposed fix:**")
+                    || line.starts_with("**Replacement:**")
+                    || line.starts_with("**Tags:**")
+                    || line.starts_with("**Owners:**")
+                    || line.starts_with("**Confidence:**")
+                    || line.starts_with("**Consensus:**")
                     || line.starts_with("**Decision:**")
                     || line.starts_with("####")
                     || line.starts_with("---")
@@ -238,6 +272,68 @@ Format better
         assert!(findings[0].reason.contains("intentional behavior"));
     }
 
+    #[test]
+    fn test_parse_rejections_with_consensus() {
+        let content = r#"#### 🟠 HIGH `S001` - Logic
+**File:** `src/main.rs` (lines 10-15)
+
+Off by one in loop bound.
+
+**Tags:** bounds, loops
+
+**Consensus:** flagged by 2/3 reviewers (gpt, claude)
+
+**Decision:** ❌ REJECTED by alice
+> The bound is inclusive on purpose.
+
+---
+"#;
+
+        let findings = parse_rejections(content);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].description, "Off by one in loop bound.");
+    }
+
+    #[test]
+    fn test_from_reviews() {
+        use crate::models::{ReviewContext, Severity, SuggestionItem, SuggestionType};
+
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(7),
+            repo: "acme/api".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+        for id in ["S001", "S002"] {
+            review.suggestions.push(
+                SuggestionItem::test(id, "src/main.rs")
+                    .with_type(SuggestionType::Security)
+                    .with_severity(Severity::High)
+                    .with_lines(10, 15)
+                    .with_description(format!("Description of {}", id)),
+            );
+        }
+        let alice = crate::policy::Identity::from("alice");
+        review
+            .record_decision(alice.record(
+                "S001",
+                HumanDecision::Rejected,
+                Some("Intentional".to_string()),
+                None,
+            ))
+            .unwrap();
+        review
+            .record_decision(alice.record("S002", HumanDecision::Accepted, None, None))
+            .unwrap();
+
+        let rejections = Rejections::from_reviews([&review]);
+        assert_eq!(rejections.items.len(), 1);
+        assert_eq!(rejections.items[0].finding_type, "security");
+        assert_eq!(rejections.items[0].description, "Description of S001");
+        assert_eq!(rejections.items[0].reason, "Intentional");
+    }
+
     #[test]
     fn test_parse_file_line() {
         let line = "**File:** `src/adapters/codex.rs` (lines 195-200)";
@@ -0,0 +1,246 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use uuid::Uuid;
+
+use crate::ledger::{Ledger, PostgresLedger};
+use crate::models::Review;
+
+/// Shortest commit SHA prefix accepted as a target
+const MIN_SHA_PREFIX: usize = 4;
+
+/// A reference to a review given on the command line
+///
+/// Accepts `#123` (PR number), a full or abbreviated commit SHA, or a review UUID.
+/// Bare numbers shorter than 7 digits are treated as PR numbers for compatibility.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewTarget {
+    Pr(u64),
+    Commit(String),
+    Id(Uuid),
+}
+
+impl FromStr for ReviewTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Some(pr) = s.strip_prefix('#') {
+            let pr = pr
+                .parse()
+                .with_context(|| format!("Invalid PR number: {}", s))?;
+            return Ok(ReviewTarget::Pr(pr));
+        }
+
+        if let Ok(id) = Uuid::parse_str(s) {
+            return Ok(ReviewTarget::Id(id));
+        }
+
+        if s.len() < 7 && !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(ReviewTarget::Pr(s.parse()?));
+        }
+
+        if s.len() >= MIN_SHA_PREFIX && s.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(ReviewTarget::Commit(s.to_lowercase()));
+        }
+
+        anyhow::bail!(
+            "Invalid review target '{}'. Expected #<pr>, a commit SHA or a review ID",
+            s
+        )
+    }
+}
+
+impl fmt::Display for ReviewTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewTarget::Pr(pr) => write!(f, "PR #{}", pr),
+            ReviewTarget::Commit(sha) => write!(f, "commit {}", sha),
+            ReviewTarget::Id(id) => write!(f, "review {}", id),
+        }
+    }
+}
+
+impl ReviewTarget {
+    /// Find the review this target refers to
+    pub fn resolve<L: Ledger>(&self, ledger: &L, repo: Option<&str>) -> Result<Review> {
+        let found = match self {
+            ReviewTarget::Pr(pr) => ledger
+                .load_by_pr(pr_repo(repo)?, *pr)?
+                .into_iter()
+                .collect(),
+            ReviewTarget::Id(id) => ledger.load(id)?.into_iter().collect(),
+            ReviewTarget::Commit(prefix) => ledger.list_by_commit_prefix(repo, prefix)?,
+        };
+        self.select(found, repo)
+    }
+
+    /// Find the review this target refers to in a PostgreSQL ledger
+    pub async fn resolve_postgres(
+        &self,
+        ledger: &PostgresLedger,
+        repo: Option<&str>,
+    ) -> Result<Review> {
+        let found = match self {
+            ReviewTarget::Pr(pr) => ledger
+                .load_by_pr(pr_repo(repo)?, *pr)
+                .await?
+                .into_iter()
+                .collect(),
+            ReviewTarget::Id(id) => ledger.load(id).await?.into_iter().collect(),
+            ReviewTarget::Commit(prefix) => ledger.list_by_commit_prefix(repo, prefix).await?,
+        };
+        self.select(found, repo)
+    }
+
+    /// Pick the review from the ledger's matches for this target
+    fn select(&self, mut reviews: Vec<Review>, repo: Option<&str>) -> Result<Review> {
+        let review = match self {
+            ReviewTarget::Pr(_) | ReviewTarget::Id(_) => reviews.pop(),
+            ReviewTarget::Commit(prefix) => {
+                let mut commits: Vec<(&str, &str)> = reviews
+                    .iter()
+                    .map(|r| (r.repo.as_str(), r.commit_sha.as_str()))
+                    .collect();
+                commits.sort();
+                commits.dedup();
+
+                if commits.len() > 1 {
+                    let candidates: Vec<String> = commits
+                        .iter()
+                        .map(|(repo, sha)| format!("  {} in {}", sha, repo))
+                        .collect();
+                    anyhow::bail!(
+                        "Commit prefix '{}' is ambiguous, it matches:\n{}",
+                        prefix,
+                        candidates.join("\n")
+                    );
+                }
+
+                // Newest review of the single matching commit
+                reviews.sort_by_key(|r| r.created_at);
+                reviews.pop()
+            }
+        };
+
+        match (review, repo) {
+            (Some(review), Some(repo)) if review.repo != repo => {
+                anyhow::bail!("{} belongs to {}, not {}", self, review.repo, repo)
+            }
+            (Some(review), _) => Ok(review),
+            (None, Some(repo)) => anyhow::bail!("No review found for {} in {}", self, repo),
+            (None, None) => anyhow::bail!("No review found for {}", self),
+        }
+    }
+}
+
+fn pr_repo(repo: Option<&str>) -> Result<&str> {
+    repo.context("--repo is required to look up a review by PR number")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::JsonLedger;
+    use crate::models::ReviewContext;
+    use tempfile::tempdir;
+
+    fn save_review(ledger: &JsonLedger, repo: &str, pr: Option<u64>, sha: &str) -> Review {
+        let review = Review::new(ReviewContext {
+            pr_number: pr,
+            repo: repo.to_string(),
+            branch: None,
+            commit_sha: sha.to_string(),
+            base_sha: None,
+        });
+        ledger.save(&review).unwrap();
+        review
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            "#123".parse::<ReviewTarget>().unwrap(),
+            ReviewTarget::Pr(123)
+        );
+        assert_eq!("42".parse::<ReviewTarget>().unwrap(), ReviewTarget::Pr(42));
+        assert_eq!(
+            "ABC1234".parse::<ReviewTarget>().unwrap(),
+            ReviewTarget::Commit("abc1234".to_string())
+        );
+        assert_eq!(
+            "1234567".parse::<ReviewTarget>().unwrap(),
+            ReviewTarget::Commit("1234567".to_string())
+        );
+
+        let id = Uuid::new_v4();
+        assert_eq!(
+            id.to_string().parse::<ReviewTarget>().unwrap(),
+            ReviewTarget::Id(id)
+        );
+
+        assert!("#abc".parse::<ReviewTarget>().is_err());
+        assert!("main".parse::<ReviewTarget>().is_err());
+        assert!("".parse::<ReviewTarget>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_commit_prefix() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+
+        let review = save_review(&ledger, "owner/repo", None, "abc1234def");
+        save_review(&ledger, "owner/repo", None, "abd9999aaa");
+
+        let target: ReviewTarget = "abc1".parse().unwrap();
+        assert_eq!(target.resolve(&ledger, None).unwrap().id, review.id);
+
+        let target: ReviewTarget = "abc1234def".parse().unwrap();
+        assert_eq!(
+            target.resolve(&ledger, Some("owner/repo")).unwrap().id,
+            review.id
+        );
+
+        let err = "ab00"
+            .parse::<ReviewTarget>()
+            .unwrap()
+            .resolve(&ledger, None);
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_resolve_ambiguous_prefix() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+
+        save_review(&ledger, "owner/repo", None, "abc1234def");
+        save_review(&ledger, "owner/repo", None, "abc1299aaa");
+
+        let target: ReviewTarget = "abc12".parse().unwrap();
+        let err = target.resolve(&ledger, None).unwrap_err().to_string();
+        assert!(err.contains("ambiguous"));
+        assert!(err.contains("abc1234def"));
+        assert!(err.contains("abc1299aaa"));
+    }
+
+    #[test]
+    fn test_resolve_pr_and_id() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path()).unwrap();
+
+        let review = save_review(&ledger, "owner/repo", Some(7), "abc1234");
+
+        let target = ReviewTarget::Pr(7);
+        assert!(target.resolve(&ledger, None).is_err());
+        assert_eq!(
+            target.resolve(&ledger, Some("owner/repo")).unwrap().id,
+            review.id
+        );
+
+        let target = ReviewTarget::Id(review.id);
+        assert_eq!(target.resolve(&ledger, None).unwrap().id, review.id);
+        assert!(target.resolve(&ledger, Some("other/repo")).is_err());
+    }
+}
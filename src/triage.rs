@@ -0,0 +1,620 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, terminal};
+use tracing::debug;
+
+use crate::ledger::Ledger;
+use crate::models::{HumanDecision, Review, SuggestionItem};
+use crate::policy::{Identity, Permissions};
+use crate::reconcile::write_review_markdown;
+
+/// Lines of code shown around a suggestion's location
+const CONTEXT_LINES: u32 = 3;
+
+/// Action chosen for a suggestion during triage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriageAction {
+    Accept,
+    Reject,
+    Defer,
+    Skip,
+    Quit,
+}
+
+impl TriageAction {
+    fn from_key(c: char) -> Option<Self> {
+        match c.to_ascii_lowercase() {
+            'a' => Some(TriageAction::Accept),
+            'r' => Some(TriageAction::Reject),
+            'd' => Some(TriageAction::Defer),
+            's' => Some(TriageAction::Skip),
+            'q' => Some(TriageAction::Quit),
+            _ => None,
+        }
+    }
+
+    fn decision(self) -> Option<HumanDecision> {
+        match self {
+            TriageAction::Accept => Some(HumanDecision::Accepted),
+            TriageAction::Reject => Some(HumanDecision::Rejected),
+            TriageAction::Defer => Some(HumanDecision::Deferred),
+            TriageAction::Skip | TriageAction::Quit => None,
+        }
+    }
+}
+
+/// Source of triage input
+pub trait Prompter {
+    /// Whether the screen should be cleared before each suggestion
+    fn clears_screen(&self) -> bool;
+
+    /// Ask for the action to take on the current suggestion
+    fn action(&mut self) -> Result<TriageAction>;
+
+    /// Ask for an optional reason for the decision
+    fn reason(&mut self) -> Result<Option<String>>;
+}
+
+/// Line-based prompter for dumb terminals and piped input
+pub struct LinePrompter<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> LinePrompter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>> {
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim().to_string()))
+    }
+}
+
+impl<R: BufRead, W: Write> Prompter for LinePrompter<R, W> {
+    fn clears_screen(&self) -> bool {
+        false
+    }
+
+    fn action(&mut self) -> Result<TriageAction> {
+        loop {
+            write!(
+                self.output,
+                "[a]ccept / [r]eject / [d]efer / [s]kip / [q]uit: "
+            )?;
+            self.output.flush()?;
+
+            // End of input behaves like quitting
+            let line = match self.read_line()? {
+                Some(line) => line,
+                None => return Ok(TriageAction::Quit),
+            };
+
+            if let Some(action) = line.chars().next().and_then(TriageAction::from_key) {
+                return Ok(action);
+            }
+        }
+    }
+
+    fn reason(&mut self) -> Result<Option<String>> {
+        write!(self.output, "Reason (optional): ")?;
+        self.output.flush()?;
+
+        Ok(self.read_line()?.filter(|r| !r.is_empty()))
+    }
+}
+
+/// Single-keypress prompter using raw terminal mode
+pub struct KeyPrompter;
+
+/// Restores cooked mode when dropped
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> Result<Self> {
+        terminal::enable_raw_mode().context("Failed to enable raw terminal mode")?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Prompter for KeyPrompter {
+    fn clears_screen(&self) -> bool {
+        true
+    }
+
+    fn action(&mut self) -> Result<TriageAction> {
+        print!("[a]ccept / [r]eject / [d]efer / [s]kip / [q]uit ");
+        io::stdout().flush()?;
+
+        let action = {
+            let _guard = RawModeGuard::enable()?;
+            loop {
+                if let Event::Key(key) = event::read().context("Failed to read key")? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            break TriageAction::Quit;
+                        }
+                        KeyCode::Esc => break TriageAction::Quit,
+                        KeyCode::Char(c) => {
+                            if let Some(action) = TriageAction::from_key(c) {
+                                break action;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        };
+
+        println!();
+        Ok(action)
+    }
+
+    fn reason(&mut self) -> Result<Option<String>> {
+        print!("Reason (optional): ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        let line = line.trim();
+
+        Ok(if line.is_empty() {
+            None
+        } else {
+            Some(line.to_string())
+        })
+    }
+}
+
+/// Whether stdin/stdout can drive the single-keypress UI
+pub fn is_interactive_terminal() -> bool {
+    io::stdin().is_terminal()
+        && io::stdout().is_terminal()
+        && std::env::var("TERM").map_or(true, |t| t != "dumb")
+}
+
+/// Result of triaging one review
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriageOutcome {
+    pub decided: usize,
+    pub quit: bool,
+}
+
+/// Selects which pending suggestions are shown
+pub type TriageFilter = Box<dyn Fn(&Review, &SuggestionItem) -> bool>;
+
+/// Walks a reviewer through pending suggestions, saving each decision to the ledger
+pub struct Triage<P: Prompter> {
+    prompter: P,
+    identity: Identity,
+    checkout_root: PathBuf,
+    reviews_dir: Option<PathBuf>,
+    filter: Option<TriageFilter>,
+    permissions: Permissions,
+    repo_permissions: HashMap<String, Permissions>,
+}
+
+impl<P: Prompter> Triage<P> {
+    pub fn new(
+        prompter: P,
+        identity: impl Into<Identity>,
+        checkout_root: impl AsRef<Path>,
+    ) -> Self {
+        Self {
+            prompter,
+            identity: identity.into(),
+            checkout_root: checkout_root.as_ref().to_path_buf(),
+            reviews_dir: None,
+            filter: None,
+            permissions: Permissions::default(),
+            repo_permissions: HashMap::new(),
+        }
+    }
+
+    /// Only offer suggestions the user may decide on, and enforce the decision policy
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Use these permissions instead for reviews of the repositories they're keyed by
+    pub fn with_repo_permissions(mut self, permissions: HashMap<String, Permissions>) -> Self {
+        self.repo_permissions = permissions;
+        self
+    }
+
+    /// Only triage pending suggestions matching `filter`, e.g. those the user owns
+    pub fn with_filter(
+        mut self,
+        filter: impl Fn(&Review, &SuggestionItem) -> bool + 'static,
+    ) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Regenerate review markdown in this directory after each decision
+    pub fn with_reviews_dir(mut self, reviews_dir: impl AsRef<Path>) -> Self {
+        self.reviews_dir = Some(reviews_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Triage several reviews in order, stopping early if the reviewer quits
+    pub fn run<L: Ledger>(
+        &mut self,
+        ledger: &L,
+        reviews: Vec<Review>,
+        out: &mut impl Write,
+    ) -> Result<usize> {
+        let mut decided = 0;
+
+        for mut review in reviews {
+            let outcome = self.run_review(ledger, &mut review, out)?;
+            decided += outcome.decided;
+            if outcome.quit {
+                break;
+            }
+        }
+
+        Ok(decided)
+    }
+
+    /// Triage the pending suggestions of a review
+    pub fn run_review<L: Ledger>(
+        &mut self,
+        ledger: &L,
+        review: &mut Review,
+        out: &mut impl Write,
+    ) -> Result<TriageOutcome> {
+        let permissions = self
+            .repo_permissions
+            .get(&review.repo)
+            .unwrap_or(&self.permissions)
+            .clone();
+        let pending: Vec<String> = review
+            .pending_suggestions()
+            .into_iter()
+            .filter(|item| self.filter.as_ref().is_none_or(|f| f(review, item)))
+            .filter(|item| permissions.check_decider(&self.identity, item).is_ok())
+            .map(|item| item.suggestion.id.clone())
+            .collect();
+
+        let mut outcome = TriageOutcome {
+            decided: 0,
+            quit: false,
+        };
+
+        for (position, suggestion_id) in pending.iter().enumerate() {
+            let item = review
+                .suggestions
+                .iter()
+                .find(|s| &s.suggestion.id == suggestion_id)
+                .context("Pending suggestion disappeared")?;
+
+            if self.prompter.clears_screen() {
+                execute!(
+                    out,
+                    terminal::Clear(terminal::ClearType::All),
+                    cursor::MoveTo(0, 0)
+                )?;
+            }
+            self.render(out, review, item, position + 1, pending.len())?;
+            out.flush()?;
+
+            let action = self.prompter.action()?;
+            if action == TriageAction::Quit {
+                outcome.quit = true;
+                break;
+            }
+
+            let decision = match action.decision() {
+                Some(decision) => decision,
+                None => continue,
+            };
+            let reason = self.prompter.reason()?;
+
+            if let Err(e) = permissions.check(&self.identity, item, decision, reason.as_deref()) {
+                writeln!(out, "Not recorded: {}\n", e)?;
+                continue;
+            }
+
+            review.record_decision(self.identity.record(suggestion_id, decision, reason, None))?;
+
+            ledger.save(review)?;
+            if let Some(ref dir) = self.reviews_dir {
+                write_review_markdown(dir, review)?;
+            }
+
+            debug!(suggestion_id = %suggestion_id, decision = ?decision, "Triage decision saved");
+
+            writeln!(out, "Recorded {:?} for {}\n", decision, suggestion_id)?;
+            outcome.decided += 1;
+        }
+
+        Ok(outcome)
+    }
+
+    fn render(
+        &self,
+        out: &mut impl Write,
+        review: &Review,
+        item: &SuggestionItem,
+        position: usize,
+        total: usize,
+    ) -> Result<()> {
+        let s = &item.suggestion;
+        let target = match review.pr_number {
+            Some(pr) => format!("PR #{}", pr),
+            None => format!(
+                "commit {}",
+                &review.commit_sha[..7.min(review.commit_sha.len())]
+            ),
+        };
+
+        writeln!(
+            out,
+            "{} in {} - suggestion {}/{}\n",
+            target, review.repo, position, total
+        )?;
+        writeln!(
+            out,
+            "{} [{:?}/{:?}] {} (lines {}-{})\n",
+            s.id,
+            s.severity,
+            s.suggestion_type,
+            s.location.file,
+            s.location.line_start,
+            s.location.line_end
+        )?;
+        writeln!(out, "{}\n", s.description)?;
+
+        if !item.owners.is_empty() {
+            writeln!(out, "Owners: {}\n", item.owners.join(", "))?;
+        }
+
+        if let Some(consensus) = &s.consensus {
+            writeln!(
+                out,
+                "Consensus: {} ({})\n",
+                consensus.label(),
+                consensus.flagged_by.join(", ")
+            )?;
+        }
+
+        if let Some(fix) = &s.proposed_fix {
+            writeln!(out, "Proposed fix:\n{}\n", fix)?;
+        }
+
+        match self.surrounding_code(&s.location.file, s.location.line_start, s.location.line_end) {
+            Some(code) => writeln!(out, "{}", code)?,
+            None => writeln!(out, "(`{}` not found in local checkout)\n", s.location.file)?,
+        }
+
+        Ok(())
+    }
+
+    /// Lines around a location from the local checkout, with the flagged range marked
+    fn surrounding_code(&self, file: &str, line_start: u32, line_end: u32) -> Option<String> {
+        let content = fs::read_to_string(self.checkout_root.join(file)).ok()?;
+
+        let first = line_start.saturating_sub(CONTEXT_LINES).max(1);
+        let last = line_end.saturating_add(CONTEXT_LINES);
+
+        let mut code = String::new();
+        for (n, line) in content
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i as u32 + 1, l))
+            .filter(|(n, _)| *n >= first && *n <= last)
+        {
+            let marker = if n >= line_start && n <= line_end {
+                '>'
+            } else {
+                ' '
+            };
+            code.push_str(&format!("{} {:>5} | {}\n", marker, n, line));
+        }
+
+        Some(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DecisionPolicy;
+    use crate::ledger::JsonLedger;
+    use crate::models::ReviewContext;
+    use crate::owners::Owners;
+    use std::io::Cursor;
+    use tempfile::tempdir;
+
+    fn make_review() -> Review {
+        let mut review = Review::new(ReviewContext {
+            pr_number: Some(7),
+            repo: "owner/repo".to_string(),
+            branch: None,
+            commit_sha: "abc123".to_string(),
+            base_sha: None,
+        });
+
+        for id in ["S001", "S002", "S003"] {
+            let mut item = SuggestionItem::test(id, "src/lib.rs")
+                .with_lines(5, 6)
+                .with_description(format!("Description of {}", id));
+            item.suggestion.proposed_fix = Some("Do it differently".to_string());
+            review.suggestions.push(item);
+        }
+
+        review
+    }
+
+    #[test]
+    fn test_line_prompter_triage() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path().join("ledger")).unwrap();
+        let mut review = make_review();
+        ledger.save(&review).unwrap();
+
+        // Accept with reason, skip, reject without reason
+        let input = Cursor::new("a\nGood catch\ns\nr\n\n");
+        let prompter = LinePrompter::new(input, Vec::new());
+        let mut triage = Triage::new(prompter, "alice", dir.path());
+        let mut out = Vec::new();
+
+        let outcome = triage.run_review(&ledger, &mut review, &mut out).unwrap();
+        assert_eq!(
+            outcome,
+            TriageOutcome {
+                decided: 2,
+                quit: false
+            }
+        );
+
+        let saved = ledger.load(&review.id).unwrap().unwrap();
+        let s001 = saved.suggestions[0].decision.as_ref().unwrap();
+        assert_eq!(s001.decision, HumanDecision::Accepted);
+        assert_eq!(s001.reason.as_deref(), Some("Good catch"));
+        assert_eq!(s001.decided_by, "alice");
+        assert!(saved.suggestions[1].decision.is_none());
+        let s003 = saved.suggestions[2].decision.as_ref().unwrap();
+        assert_eq!(s003.decision, HumanDecision::Rejected);
+        assert!(s003.reason.is_none());
+    }
+
+    #[test]
+    fn test_quit_and_end_of_input() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path().join("ledger")).unwrap();
+        let mut review = make_review();
+
+        let prompter = LinePrompter::new(Cursor::new("x\nd\n\n"), Vec::new());
+        let mut triage = Triage::new(prompter, "alice", dir.path());
+        let outcome = triage
+            .run_review(&ledger, &mut review, &mut Vec::new())
+            .unwrap();
+
+        // Unknown key is re-prompted, then input runs out
+        assert_eq!(outcome.decided, 1);
+        assert!(outcome.quit);
+        assert_eq!(
+            review.suggestions[0].decision.as_ref().unwrap().decision,
+            HumanDecision::Deferred
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path().join("ledger")).unwrap();
+        let mut review = make_review();
+        review.suggestions[1].owners = vec!["@alice".to_string()];
+
+        let prompter = LinePrompter::new(Cursor::new("a\n\n"), Vec::new());
+        let mut triage = Triage::new(prompter, "alice", dir.path())
+            .with_filter(|_, item| item.owners.contains(&"@alice".to_string()));
+        let mut out = Vec::new();
+        let outcome = triage.run_review(&ledger, &mut review, &mut out).unwrap();
+
+        assert_eq!(outcome.decided, 1);
+        assert!(review.suggestions[0].decision.is_none());
+        assert!(review.suggestions[1].decision.is_some());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("suggestion 1/1"));
+        assert!(out.contains("Owners: @alice"));
+    }
+
+    #[test]
+    fn test_policy_requires_reject_reason() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path().join("ledger")).unwrap();
+        let mut review = make_review();
+        let policy = DecisionPolicy {
+            reject_reason_required: vec!["medium".to_string()],
+            ..Default::default()
+        };
+
+        // Reject without a reason, then with one
+        let input = Cursor::new("r\n\nr\nFalse positive\n\n");
+        let prompter = LinePrompter::new(input, Vec::new());
+        let mut triage = Triage::new(prompter, "alice", dir.path())
+            .with_permissions(Permissions::new(Owners::default(), policy));
+        let mut out = Vec::new();
+        let outcome = triage.run_review(&ledger, &mut review, &mut out).unwrap();
+
+        assert_eq!(outcome.decided, 1);
+        assert!(review.suggestions[0].decision.is_none());
+        let s002 = review.suggestions[1].decision.as_ref().unwrap();
+        assert_eq!(s002.reason.as_deref(), Some("False positive"));
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Not recorded: Rejecting medium finding S001 needs a reason"));
+    }
+
+    #[test]
+    fn test_repo_permissions() {
+        let dir = tempdir().unwrap();
+        let ledger = JsonLedger::new(dir.path().join("ledger")).unwrap();
+        let mut api = make_review();
+        api.repo = "acme/api".to_string();
+        let mut web = make_review();
+        web.repo = "acme/web".to_string();
+        let policy = DecisionPolicy {
+            reject_reason_required: vec!["medium".to_string()],
+            ..Default::default()
+        };
+
+        // Reject the first finding of each review without a reason, then quit
+        let input = Cursor::new("r\n\nq\nr\n\nq\n");
+        let prompter = LinePrompter::new(input, Vec::new());
+        let permissions = HashMap::from([(
+            "acme/api".to_string(),
+            Permissions::new(Owners::default(), policy),
+        )]);
+        let mut triage =
+            Triage::new(prompter, "alice", dir.path()).with_repo_permissions(permissions);
+        let mut out = Vec::new();
+        triage.run_review(&ledger, &mut api, &mut out).unwrap();
+        triage.run_review(&ledger, &mut web, &mut out).unwrap();
+
+        assert!(api.suggestions[0].decision.is_none());
+        assert!(web.suggestions[0].decision.is_some());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Not recorded: Rejecting medium finding S001 needs a reason"));
+    }
+
+    #[test]
+    fn test_surrounding_code() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        let content: String = (1..=12).map(|n| format!("line {}\n", n)).collect();
+        fs::write(dir.path().join("src/lib.rs"), content).unwrap();
+
+        let prompter = LinePrompter::new(Cursor::new(""), Vec::new());
+        let triage = Triage::new(prompter, "alice", dir.path());
+
+        let code = triage.surrounding_code("src/lib.rs", 5, 6).unwrap();
+        let lines: Vec<&str> = code.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "      2 | line 2");
+        assert_eq!(lines[3], ">     5 | line 5");
+        assert_eq!(lines[4], ">     6 | line 6");
+        assert!(triage.surrounding_code("missing.rs", 1, 1).is_none());
+    }
+}